@@ -0,0 +1,411 @@
+// ====================================================================
+// 在庫を数える Tシャツ配布 — main.rs の Inventory の実用版
+// ====================================================================
+//
+// main.rs の Inventory は本の例なので:
+//   - giveaway しても在庫が減らない（同じ1枚を何人にでも配れる）
+//   - 色は Red / Blue だけ、同数なら必ず Blue
+//   - 誰に何を配ったか残らない
+//
+// ここでは:
+//   - 色ごとの枚数を持つ。色の型は何でもよい（ShirtColor でも "green" のような文字列でも）
+//   - 配ると1枚減る。全色なくなったら Err(GiveawayError::SoldOut)
+//   - 好みは「第1希望, 第2希望, ...」のリスト。どれも在庫切れなら在庫最多の色
+//   - 在庫最多が同数なら、先に登録した色
+//   - 配った記録（誰に・何色を・第何希望だったか）を残す
+//   - &self のまま使える（中身は Mutex）→ Arc に入れて複数スレッドから配れる
+//
+//   let store = Arc::new(Inventory::with_stock([(Red, 2), (Blue, 1)]));
+//   store.giveaway("alice", &[Blue])?;   // → Blue（Blue は残り 0）
+//   store.giveaway("bob", &[Blue, Red])?; // → Red（第2希望）
+//
+// 「取り置き」もできる: reserve で1枚確保 → confirm で配布が確定
+//   confirm せずに Reservation を捨てると、その1枚は在庫に戻る
+//
+// どの色を渡すかは Strategy で差し替えられる（strategy.rs）
+//   上の「好みの順、なければ在庫最多」は既定の Preferred
+//   Inventory::with_stock(...).with_strategy(RoundRobin::new())
+
+mod strategy;
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+pub use strategy::{Context, FirstCome, Limited, Lottery, Preferred, RoundRobin, Strategy};
+
+pub struct Inventory<C> {
+    state: Mutex<State<C>>,
+}
+
+struct State<C> {
+    // 登録した順。色の数は少ないので Vec を順に探す
+    stock: Vec<(C, u32)>,
+    log: Vec<Grant<C>>,
+    // 取り置き中の人（Limited が「もらった枚数」に数える）
+    pending: Vec<String>,
+    strategy: Box<dyn Strategy<C>>,
+}
+
+// 配布の記録
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grant<C> {
+    pub seq: usize, // 0 から、配った順
+    pub user: String,
+    pub color: C,
+    // もらった色が好みのリストの何番目だったか（None = リストにない色）
+    pub choice: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GiveawayError {
+    SoldOut,
+    // Limited: 1人あたりの上限に達した
+    LimitReached { limit: usize },
+    // FirstCome: 在庫切れなので待ち行列に並んだ（1 始まりの順番）
+    Waitlisted { position: usize },
+}
+
+impl fmt::Display for GiveawayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GiveawayError::SoldOut => write!(f, "all shirts have been given away"),
+            GiveawayError::LimitReached { limit } => {
+                write!(f, "already received the limit of {limit} shirt(s)")
+            }
+            GiveawayError::Waitlisted { position } => {
+                write!(f, "sold out; number {position} on the waitlist")
+            }
+        }
+    }
+}
+
+impl Error for GiveawayError {}
+
+impl<C: PartialEq> Default for Inventory<C> {
+    fn default() -> Inventory<C> {
+        Inventory {
+            state: Mutex::new(State {
+                stock: Vec::new(),
+                log: Vec::new(),
+                pending: Vec::new(),
+                strategy: Box::new(Preferred),
+            }),
+        }
+    }
+}
+
+impl<C: Clone + PartialEq> Inventory<C> {
+    pub fn new() -> Inventory<C> {
+        Inventory::default()
+    }
+
+    pub fn with_stock(stock: impl IntoIterator<Item = (C, u32)>) -> Inventory<C> {
+        let inventory = Inventory::new();
+        for (color, count) in stock {
+            inventory.restock(color, count);
+        }
+        inventory
+    }
+
+    // main.rs の shirts: vec![Blue, Red, Blue] と同じ形から数える
+    pub fn from_shirts(shirts: impl IntoIterator<Item = C>) -> Inventory<C> {
+        Inventory::with_stock(shirts.into_iter().map(|color| (color, 1)))
+    }
+
+    pub fn with_strategy(self, strategy: impl Strategy<C> + 'static) -> Inventory<C> {
+        self.lock().strategy = Box::new(strategy);
+        self
+    }
+
+    // 初めての色なら最後に登録する（count が 0 でも色としては登録される）
+    // 1色の枚数は u32::MAX で頭打ち
+    // 待ち行列に人がいれば、並んだ順に配って、その記録を返す
+    pub fn restock(&self, color: C, count: u32) -> Vec<Grant<C>> {
+        let mut state = self.lock();
+        match state.stock.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, n)) => *n = n.saturating_add(count),
+            None => state.stock.push((color, count)),
+        }
+
+        let mut served = Vec::new();
+        while state.stock.iter().any(|&(_, n)| n > 0) {
+            let Some((user, preferences)) = state.strategy.next_waiting() else {
+                break;
+            };
+            // 上限などで配れなかった人は、行列から外れたまま
+            if let Ok(color) = state.take(&user, &preferences) {
+                let choice = choice(&preferences, &color);
+                served.push(state.record(user, color, choice));
+            }
+        }
+        served
+    }
+
+    pub fn stock(&self, color: &C) -> u32 {
+        count(&self.lock().stock, color)
+    }
+
+    // 色ごとの枚数（登録した順）
+    pub fn stock_levels(&self) -> Vec<(C, u32)> {
+        self.lock().stock.clone()
+    }
+
+    // 色ごとには u32 でも、足すとあふれうるので u64 で数える
+    pub fn total(&self) -> u64 {
+        self.lock().stock.iter().map(|&(_, n)| u64::from(n)).sum()
+    }
+
+    // 全色 0 枚なら None
+    pub fn most_stocked(&self) -> Option<C> {
+        let state = self.lock();
+        most_stocked_slot(&state.stock).map(|slot| state.stock[slot].0.clone())
+    }
+
+    pub fn giveaway(&self, user: &str, preferences: &[C]) -> Result<C, GiveawayError> {
+        self.reserve(user, preferences).map(Reservation::confirm)
+    }
+
+    // 1枚を在庫から外して確保する（配布の記録は confirm したとき）
+    pub fn reserve(
+        &self,
+        user: &str,
+        preferences: &[C],
+    ) -> Result<Reservation<'_, C>, GiveawayError> {
+        let mut state = self.lock();
+        let color = state.take(user, preferences)?;
+        state.pending.push(user.to_string());
+        Ok(Reservation {
+            inventory: self,
+            user: user.to_string(),
+            choice: choice(preferences, &color),
+            color: Some(color),
+        })
+    }
+
+    // 配布の記録（配った順）
+    pub fn audit_log(&self) -> Vec<Grant<C>> {
+        self.lock().log.clone()
+    }
+
+    // その人がもらった色（配った順）
+    pub fn given_to(&self, user: &str) -> Vec<C> {
+        self.lock()
+            .log
+            .iter()
+            .filter(|grant| grant.user == user)
+            .map(|grant| grant.color.clone())
+            .collect()
+    }
+
+    // 在庫切れで待っている人（並んだ順。FirstCome のときだけ）
+    pub fn waitlist(&self) -> Vec<String> {
+        self.lock().strategy.waitlist()
+    }
+
+    // 別スレッドが lock 中に panic しても、在庫の数え方は壊れていないのでそのまま使う
+    fn lock(&self) -> MutexGuard<'_, State<C>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<C: Clone + PartialEq> State<C> {
+    // Strategy に色を選ばせて、その色を1枚減らす
+    fn take(&mut self, user: &str, preferences: &[C]) -> Result<C, GiveawayError> {
+        let ctx = Context {
+            user,
+            preferences,
+            stock: &self.stock,
+            log: &self.log,
+            pending: &self.pending,
+        };
+        let slot = self.strategy.choose(&ctx)?;
+        let (color, n) = &mut self.stock[slot];
+        assert!(*n > 0, "the strategy chose a sold-out color");
+        *n -= 1;
+        Ok(color.clone())
+    }
+
+    fn record(&mut self, user: String, color: C, choice: Option<usize>) -> Grant<C> {
+        let grant = Grant {
+            seq: self.log.len(),
+            user,
+            color,
+            choice,
+        };
+        self.log.push(grant.clone());
+        grant
+    }
+}
+
+fn choice<C: PartialEq>(preferences: &[C], color: &C) -> Option<usize> {
+    preferences.iter().position(|c| c == color)
+}
+
+fn count<C: PartialEq>(stock: &[(C, u32)], color: &C) -> u32 {
+    stock
+        .iter()
+        .find(|(c, _)| c == color)
+        .map_or(0, |&(_, n)| n)
+}
+
+// 枚数が最大の色のうち、先に登録したものの位置
+//   max_by_key は同じ値なら「後ろ」を返すので、自分で比べる
+fn most_stocked_slot<C>(stock: &[(C, u32)]) -> Option<usize> {
+    (0..stock.len())
+        .filter(|&slot| stock[slot].1 > 0)
+        .fold(None, |best: Option<usize>, slot| match best {
+            Some(b) if stock[b].1 >= stock[slot].1 => Some(b),
+            _ => Some(slot),
+        })
+}
+
+// ====================================================================
+// Reservation — 取り置きの1枚
+// ====================================================================
+
+pub struct Reservation<'a, C: Clone + PartialEq> {
+    inventory: &'a Inventory<C>,
+    user: String,
+    // confirm / drop で取り出すので Option
+    color: Option<C>,
+    choice: Option<usize>,
+}
+
+impl<C: Clone + PartialEq> Reservation<'_, C> {
+    pub fn color(&self) -> &C {
+        self.color.as_ref().expect("reservation is still held")
+    }
+
+    // 配布を確定して記録に残す
+    pub fn confirm(mut self) -> C {
+        let color = self.color.take().expect("reservation is still held");
+        let mut state = self.inventory.lock();
+        release_pending(&mut state.pending, &self.user);
+        state.record(std::mem::take(&mut self.user), color.clone(), self.choice);
+        color
+    }
+}
+
+// confirm しなかった1枚は在庫に戻す（待っている人がいれば、その人に渡る）
+impl<C: Clone + PartialEq> Drop for Reservation<'_, C> {
+    fn drop(&mut self) {
+        if let Some(color) = self.color.take() {
+            release_pending(&mut self.inventory.lock().pending, &self.user);
+            self.inventory.restock(color, 1);
+        }
+    }
+}
+
+fn release_pending(pending: &mut Vec<String>, user: &str) {
+    if let Some(i) = pending.iter().position(|u| u == user) {
+        pending.swap_remove(i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    enum ShirtColor {
+        Red,
+        Blue,
+    }
+    use ShirtColor::*;
+
+    #[test]
+    fn giveaway_takes_a_shirt_from_stock() {
+        let store = Inventory::from_shirts([Blue, Red, Blue]);
+        assert_eq!(store.giveaway("alice", &[Red]), Ok(Red));
+        assert_eq!(store.giveaway("bob", &[Red]), Ok(Blue)); // Red は在庫切れ → 在庫最多
+        assert_eq!(store.giveaway("carol", &[]), Ok(Blue));
+        assert_eq!(
+            store.giveaway("dave", &[Red, Blue]),
+            Err(GiveawayError::SoldOut)
+        );
+        assert_eq!(store.stock_levels(), [(Blue, 0), (Red, 0)]);
+    }
+
+    #[test]
+    fn preferences_fall_back_in_order_and_ties_go_to_the_first_color() {
+        let store = Inventory::with_stock([("green", 1), ("navy", 2), ("white", 2)]);
+        assert_eq!(store.most_stocked(), Some("navy"));
+        assert_eq!(store.giveaway("a", &["pink", "green"]), Ok("green"));
+        assert_eq!(store.giveaway("b", &["green", "pink"]), Ok("navy"));
+        assert_eq!(store.giveaway("c", &[]), Ok("white"));
+
+        let log = store.audit_log();
+        let summary: Vec<(&str, &str, Option<usize>)> = log
+            .iter()
+            .map(|g| (g.user.as_str(), g.color, g.choice))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("a", "green", Some(1)),
+                ("b", "navy", None),
+                ("c", "white", None)
+            ]
+        );
+        assert_eq!(store.given_to("b"), ["navy"]);
+    }
+
+    #[test]
+    fn dropped_reservations_return_to_stock() {
+        let store = Inventory::with_stock([(Red, 1)]);
+        let held = store.reserve("alice", &[Red]).unwrap();
+        assert_eq!(*held.color(), Red);
+        assert_eq!(store.giveaway("bob", &[Red]), Err(GiveawayError::SoldOut));
+        drop(held);
+        assert_eq!(store.stock(&Red), 1);
+        assert_eq!(store.giveaway("bob", &[Red]), Ok(Red));
+        assert_eq!(store.audit_log().len(), 1);
+    }
+
+    #[test]
+    fn concurrent_giveaways_never_oversell() {
+        let store = Arc::new(Inventory::with_stock([(Red, 150), (Blue, 250)]));
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let store = Arc::clone(&store);
+                thread::spawn(move || {
+                    (0..100)
+                        .filter(|i| {
+                            let user = format!("user{t}-{i}");
+                            let preference = if i % 2 == 0 { [Red] } else { [Blue] };
+                            store.giveaway(&user, &preference).is_ok()
+                        })
+                        .count()
+                })
+            })
+            .collect();
+        let given: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+
+        assert_eq!(given, 400);
+        assert_eq!(store.total(), 0);
+        let log = store.audit_log();
+        assert_eq!(log.len(), 400);
+        assert!(log.iter().enumerate().all(|(i, g)| g.seq == i));
+        let mut per_color: HashMap<&str, usize> = HashMap::new();
+        for grant in &log {
+            *per_color
+                .entry(if grant.color == Red { "red" } else { "blue" })
+                .or_default() += 1;
+        }
+        assert_eq!(per_color, HashMap::from([("red", 150), ("blue", 250)]));
+    }
+
+    #[test]
+    fn huge_restock_saturates_instead_of_overflowing() {
+        let store = Inventory::with_stock([(Red, u32::MAX), (Blue, u32::MAX)]);
+        store.restock(Red, 10);
+        assert_eq!(store.stock(&Red), u32::MAX);
+        assert_eq!(store.total(), 2 * u64::from(u32::MAX));
+        assert_eq!(store.giveaway("ann", &[Red]), Ok(Red));
+        assert_eq!(store.stock(&Red), u32::MAX - 1);
+    }
+}
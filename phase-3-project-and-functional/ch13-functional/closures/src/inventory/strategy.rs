@@ -0,0 +1,333 @@
+// ====================================================================
+// 配り方（Strategy）— Inventory に差し込む
+// ====================================================================
+//
+// Inventory は「どの色を渡すか」を Strategy に聞き、在庫を減らして記録するだけ
+//
+//   Preferred      好みのリストの順に、なければ在庫最多（Inventory::new の既定）
+//   RoundRobin     好みは見ずに、色を登録順に1枚ずつ順番に
+//   Lottery        在庫の枚数を重みにした抽選（好みの色に在庫があればその中から）
+//                  seed を決めれば同じ結果になる
+//   FirstCome      Preferred と同じ選び方。全色なくなったら待ち行列に並べ、
+//                  restock されたら並んだ順に配る
+//   Limited        ほかの Strategy を包んで、1人がもらえる枚数を制限する
+//
+//   let store = Inventory::with_stock([(Red, 10), (Blue, 10)])
+//       .with_strategy(Limited::new(FirstCome::new(), 1));
+//
+// Strategy は Mutex の中で呼ばれる（同時に呼ばれることはない）ので &mut self でよい
+// Inventory を複数スレッドで共有できるように Send だけ求める
+
+use std::collections::VecDeque;
+
+use super::{GiveawayError, Grant, most_stocked_slot};
+
+pub trait Strategy<C>: Send {
+    // 渡す色の位置（ctx.stock の添字）を返す。その色は在庫が 1 枚以上あること
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError>;
+
+    // 待ち行列の先頭を取り出す（restock のあとに呼ばれる）
+    fn next_waiting(&mut self) -> Option<(String, Vec<C>)> {
+        None
+    }
+
+    // 待っている人（並んだ順）
+    fn waitlist(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl<C, S: Strategy<C> + ?Sized> Strategy<C> for Box<S> {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        (**self).choose(ctx)
+    }
+
+    fn next_waiting(&mut self) -> Option<(String, Vec<C>)> {
+        (**self).next_waiting()
+    }
+
+    fn waitlist(&self) -> Vec<String> {
+        (**self).waitlist()
+    }
+}
+
+// Strategy に見せる在庫と記録
+pub struct Context<'a, C> {
+    pub user: &'a str,
+    pub preferences: &'a [C],
+    // 色ごとの枚数（登録した順）
+    pub stock: &'a [(C, u32)],
+    pub log: &'a [Grant<C>],
+    // 取り置き中の人（confirm も drop もまだ）
+    pub(super) pending: &'a [String],
+}
+
+impl<C: PartialEq> Context<'_, C> {
+    pub fn total(&self) -> u64 {
+        self.stock.iter().map(|&(_, n)| u64::from(n)).sum()
+    }
+
+    // その人がもらった枚数（取り置き中も数える）
+    pub fn received(&self, user: &str) -> usize {
+        self.log.iter().filter(|g| g.user == user).count()
+            + self.pending.iter().filter(|u| *u == user).count()
+    }
+
+    // 好みのリストで最初に在庫がある色
+    pub fn first_preference(&self) -> Option<usize> {
+        self.preferences
+            .iter()
+            .find_map(|color| self.stock.iter().position(|(c, n)| c == color && *n > 0))
+    }
+
+    pub fn most_stocked(&self) -> Option<usize> {
+        most_stocked_slot(self.stock)
+    }
+}
+
+// ====================================================================
+// Preferred — 好みの順、なければ在庫最多
+// ====================================================================
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Preferred;
+
+impl<C: PartialEq> Strategy<C> for Preferred {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        // main.rs の user_preference.unwrap_or_else(|| self.most_stocked()) と同じ形
+        ctx.first_preference()
+            .or_else(|| ctx.most_stocked())
+            .ok_or(GiveawayError::SoldOut)
+    }
+}
+
+// ====================================================================
+// RoundRobin — 色を順番に
+// ====================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+impl RoundRobin {
+    pub fn new() -> RoundRobin {
+        RoundRobin::default()
+    }
+}
+
+impl<C: PartialEq> Strategy<C> for RoundRobin {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        let len = ctx.stock.len();
+        // 前回の次の色から1周して、在庫がある最初の色
+        let slot = (0..len)
+            .map(|k| (self.next + k) % len)
+            .find(|&slot| ctx.stock[slot].1 > 0)
+            .ok_or(GiveawayError::SoldOut)?;
+        self.next = slot + 1;
+        Ok(slot)
+    }
+}
+
+// ====================================================================
+// Lottery — 在庫の枚数で重みをつけた抽選
+// ====================================================================
+
+#[derive(Debug, Clone)]
+pub struct Lottery {
+    state: u64,
+}
+
+impl Lottery {
+    pub fn new(seed: u64) -> Lottery {
+        Lottery { state: seed }
+    }
+
+    // splitmix64（std に乱数がないので自前。抽選に使う程度なら十分）
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl<C: PartialEq> Strategy<C> for Lottery {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        // 好みの色に在庫があればその中から、なければ全色から
+        let preferred: Vec<usize> = (0..ctx.stock.len())
+            .filter(|&slot| ctx.stock[slot].1 > 0 && ctx.preferences.contains(&ctx.stock[slot].0))
+            .collect();
+        let candidates = if preferred.is_empty() {
+            (0..ctx.stock.len())
+                .filter(|&slot| ctx.stock[slot].1 > 0)
+                .collect()
+        } else {
+            preferred
+        };
+
+        let total: u64 = candidates.iter().map(|&s| u64::from(ctx.stock[s].1)).sum();
+        if total == 0 {
+            return Err(GiveawayError::SoldOut);
+        }
+        let mut ticket = self.next_u64() % total;
+        for slot in candidates {
+            let weight = u64::from(ctx.stock[slot].1);
+            if ticket < weight {
+                return Ok(slot);
+            }
+            ticket -= weight;
+        }
+        unreachable!("ticket is below the total weight")
+    }
+}
+
+// ====================================================================
+// FirstCome — 在庫切れなら並んでもらう
+// ====================================================================
+
+#[derive(Debug, Clone)]
+pub struct FirstCome<C> {
+    queue: VecDeque<(String, Vec<C>)>,
+}
+
+impl<C> Default for FirstCome<C> {
+    fn default() -> FirstCome<C> {
+        FirstCome {
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<C> FirstCome<C> {
+    pub fn new() -> FirstCome<C> {
+        FirstCome::default()
+    }
+}
+
+impl<C: Clone + PartialEq + Send> Strategy<C> for FirstCome<C> {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        if ctx.total() > 0 {
+            return Preferred.choose(ctx);
+        }
+        // 2回目以降は並び直さず、今の順番を返す
+        let position = match self.queue.iter().position(|(user, _)| user == ctx.user) {
+            Some(i) => i,
+            None => {
+                self.queue
+                    .push_back((ctx.user.to_string(), ctx.preferences.to_vec()));
+                self.queue.len() - 1
+            }
+        };
+        Err(GiveawayError::Waitlisted {
+            position: position + 1,
+        })
+    }
+
+    fn next_waiting(&mut self) -> Option<(String, Vec<C>)> {
+        self.queue.pop_front()
+    }
+
+    fn waitlist(&self) -> Vec<String> {
+        self.queue.iter().map(|(user, _)| user.clone()).collect()
+    }
+}
+
+// ====================================================================
+// Limited — 1人あたりの上限
+// ====================================================================
+
+#[derive(Debug, Clone)]
+pub struct Limited<S> {
+    inner: S,
+    per_user: usize,
+}
+
+impl<S> Limited<S> {
+    pub fn new(inner: S, per_user: usize) -> Limited<S> {
+        Limited { inner, per_user }
+    }
+}
+
+impl<C: PartialEq, S: Strategy<C>> Strategy<C> for Limited<S> {
+    fn choose(&mut self, ctx: &Context<'_, C>) -> Result<usize, GiveawayError> {
+        if ctx.received(ctx.user) >= self.per_user {
+            return Err(GiveawayError::LimitReached {
+                limit: self.per_user,
+            });
+        }
+        self.inner.choose(ctx)
+    }
+
+    fn next_waiting(&mut self) -> Option<(String, Vec<C>)> {
+        self.inner.next_waiting()
+    }
+
+    fn waitlist(&self) -> Vec<String> {
+        self.inner.waitlist()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(
+        user: &'a str,
+        preferences: &'a [&'static str],
+        stock: &'a [(&'static str, u32)],
+    ) -> Context<'a, &'static str> {
+        Context {
+            user,
+            preferences,
+            stock,
+            log: &[],
+            pending: &[],
+        }
+    }
+
+    #[test]
+    fn round_robin_skips_sold_out_colors() {
+        let stock = [("red", 1), ("green", 0), ("blue", 5)];
+        let mut strategy = RoundRobin::new();
+        let picks: Vec<usize> = (0..4)
+            .map(|_| strategy.choose(&context("a", &["green"], &stock)).unwrap())
+            .collect();
+        assert_eq!(picks, [0, 2, 0, 2]);
+    }
+
+    #[test]
+    fn lottery_follows_the_weights() {
+        let stock = [("red", 1), ("blue", 3)];
+        let mut strategy = Lottery::new(7);
+        let mut counts = [0; 2];
+        for _ in 0..4000 {
+            counts[strategy.choose(&context("a", &[], &stock)).unwrap()] += 1;
+        }
+        // 期待値は 1000 : 3000
+        assert!((800..1200).contains(&counts[0]), "{counts:?}");
+        // 好みの色に在庫があれば、その色しか当たらない
+        assert!((0..100).all(|_| strategy.choose(&context("a", &["red"], &stock)) == Ok(0)));
+    }
+
+    #[test]
+    fn first_come_keeps_one_place_per_user() {
+        let stock = [("red", 0)];
+        let mut strategy = FirstCome::new();
+        let position = |s: &mut FirstCome<_>, user| s.choose(&context(user, &[], &stock));
+        assert_eq!(
+            position(&mut strategy, "a"),
+            Err(GiveawayError::Waitlisted { position: 1 })
+        );
+        assert_eq!(
+            position(&mut strategy, "b"),
+            Err(GiveawayError::Waitlisted { position: 2 })
+        );
+        assert_eq!(
+            position(&mut strategy, "a"),
+            Err(GiveawayError::Waitlisted { position: 1 })
+        );
+        assert_eq!(Strategy::<&str>::waitlist(&strategy), ["a", "b"]);
+    }
+}
@@ -0,0 +1,4 @@
+// main.rs はクロージャの説明（本の例）、ここは他から使える部品
+//   inventory  在庫を数える Tシャツ配布（main.rs の Inventory の実用版）
+
+pub mod inventory;
@@ -76,6 +76,20 @@ fn main() {
         giveaway2
     );
 
+    // 本の Inventory は配っても在庫が減らない → 在庫を数える版（src/inventory.rs）
+    let stock = closures::inventory::Inventory::from_shirts(store.shirts.clone());
+    for (user, preferences) in [
+        ("alice", vec![ShirtColor::Red]),
+        ("bob", vec![ShirtColor::Red]),
+        ("carol", vec![]),
+    ] {
+        match stock.giveaway(user, &preferences) {
+            Ok(color) => println!("{user}（好み {preferences:?}）は {color:?} をもらった"),
+            Err(e) => println!("{user}（好み {preferences:?}）: {e}"),
+        }
+    }
+    println!("残りの在庫: {:?}", stock.stock_levels());
+
     // ====================================================================
     // クロージャの型注釈と型推論
     // ====================================================================
@@ -0,0 +1,215 @@
+// ====================================================================
+// 配布イベントのシミュレーション — Strategy ごとの結果を比べる
+// ====================================================================
+//
+// 同じ来場者の列（好みつき）に、同じ在庫で、Strategy だけ変えて配ってみる
+//   赤 20 / 青 20 / 緑 10 の計 50 枚に 60 人が来る
+//   第1希望は 赤:青:緑 = 5:3:2 くらい、第2希望は残りの色から
+
+use std::sync::Arc;
+use std::thread;
+
+use closures::inventory::{
+    FirstCome, GiveawayError, Grant, Inventory, Limited, Lottery, Preferred, RoundRobin, Strategy,
+};
+
+const COLORS: [&str; 3] = ["red", "blue", "green"];
+
+fn stock() -> [(&'static str, u32); 3] {
+    [("red", 20), ("blue", 20), ("green", 10)]
+}
+
+// 来場者（名前, 好み）。固定の seed の線形合同法で作るので毎回同じ
+fn attendees(n: usize) -> Vec<(String, Vec<&'static str>)> {
+    let mut seed: u32 = 12345;
+    let mut next = move || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (seed >> 16) % 10
+    };
+    (0..n)
+        .map(|i| {
+            let first = match next() {
+                0..=4 => 0,
+                5..=7 => 1,
+                _ => 2,
+            };
+            let second = (first + 1 + next() as usize % 2) % 3;
+            (format!("guest{i}"), vec![COLORS[first], COLORS[second]])
+        })
+        .collect()
+}
+
+struct Outcome {
+    log: Vec<Grant<&'static str>>,
+    refused: Vec<GiveawayError>,
+}
+
+impl Outcome {
+    fn first_choices(&self) -> usize {
+        self.log.iter().filter(|g| g.choice == Some(0)).count()
+    }
+
+    fn per_color(&self) -> [usize; 3] {
+        COLORS.map(|color| self.log.iter().filter(|g| g.color == color).count())
+    }
+}
+
+fn simulate(strategy: impl Strategy<&'static str> + 'static) -> Outcome {
+    let store = Inventory::with_stock(stock()).with_strategy(strategy);
+    let refused = attendees(60)
+        .iter()
+        .filter_map(|(user, preferences)| store.giveaway(user, preferences).err())
+        .collect();
+    Outcome {
+        log: store.audit_log(),
+        refused,
+    }
+}
+
+#[test]
+fn default_strategy_is_preferred() {
+    let store = Inventory::with_stock(stock());
+    for (user, preferences) in attendees(60) {
+        let _ = store.giveaway(&user, &preferences);
+    }
+    assert_eq!(store.audit_log(), simulate(Preferred).log);
+}
+
+#[test]
+fn every_strategy_gives_away_the_whole_stock() {
+    let outcomes = [
+        simulate(Preferred),
+        simulate(RoundRobin::new()),
+        simulate(Lottery::new(1)),
+        simulate(FirstCome::new()),
+    ];
+    for outcome in &outcomes {
+        assert_eq!(outcome.per_color(), [20, 20, 10]);
+        assert_eq!(outcome.refused.len(), 10);
+    }
+    assert!(
+        outcomes[0]
+            .refused
+            .iter()
+            .all(|e| *e == GiveawayError::SoldOut)
+    );
+    assert!(
+        outcomes[3]
+            .refused
+            .iter()
+            .zip(1..)
+            .all(|(e, position)| *e == GiveawayError::Waitlisted { position })
+    );
+}
+
+#[test]
+fn preferred_satisfies_the_most_first_choices() {
+    let preferred = simulate(Preferred).first_choices();
+    let round_robin = simulate(RoundRobin::new()).first_choices();
+    let lottery = simulate(Lottery::new(1)).first_choices();
+    assert!(preferred >= lottery, "{preferred} < {lottery}");
+    assert!(lottery > round_robin, "{lottery} <= {round_robin}");
+}
+
+#[test]
+fn round_robin_keeps_colors_even_while_they_last() {
+    let outcome = simulate(RoundRobin::new());
+    // 緑がなくなる 30 枚目までは、どの時点でも色ごとの差が 1 以内
+    let mut counts = [0; 3];
+    for grant in &outcome.log[..30] {
+        counts[COLORS.iter().position(|&c| c == grant.color).unwrap()] += 1;
+        assert!(counts.iter().max().unwrap() - counts.iter().min().unwrap() <= 1);
+    }
+}
+
+#[test]
+fn lottery_is_reproducible_with_a_seed() {
+    let colors = |outcome: Outcome| -> Vec<&str> { outcome.log.iter().map(|g| g.color).collect() };
+    assert_eq!(
+        colors(simulate(Lottery::new(42))),
+        colors(simulate(Lottery::new(42)))
+    );
+    assert_ne!(
+        colors(simulate(Lottery::new(42))),
+        colors(simulate(Lottery::new(43)))
+    );
+}
+
+#[test]
+fn first_come_serves_the_waitlist_in_order_after_restock() {
+    let store = Inventory::with_stock([("red", 2)]).with_strategy(FirstCome::new());
+    assert_eq!(store.giveaway("a", &["red"]), Ok("red"));
+    assert_eq!(store.giveaway("b", &[]), Ok("red"));
+    assert_eq!(
+        store.giveaway("c", &["blue"]),
+        Err(GiveawayError::Waitlisted { position: 1 })
+    );
+    assert_eq!(
+        store.giveaway("d", &[]),
+        Err(GiveawayError::Waitlisted { position: 2 })
+    );
+
+    // 1枚入荷 → 先に並んだ c に（好みの blue が入ったので blue）
+    let served = store.restock("blue", 1);
+    assert_eq!(
+        served
+            .iter()
+            .map(|g| (g.user.as_str(), g.color, g.choice))
+            .collect::<Vec<_>>(),
+        [("c", "blue", Some(0))]
+    );
+    assert_eq!(store.waitlist(), ["d"]);
+
+    // 次の入荷は d に（d は好みなし → 入った red）
+    store.restock("red", 1);
+    assert!(store.waitlist().is_empty());
+    assert_eq!(store.given_to("d"), ["red"]);
+    assert_eq!(store.total(), 0);
+}
+
+#[test]
+fn limits_hold_across_threads_and_for_waiters() {
+    let store = Arc::new(
+        Inventory::with_stock([("red", 100), ("blue", 100)])
+            .with_strategy(Limited::new(Preferred, 2)),
+    );
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let store = Arc::clone(&store);
+            thread::spawn(move || {
+                for round in 0..10 {
+                    for user in ["x", "y", "z"] {
+                        // 取り置いて捨てても、上限には数えない
+                        if round % 3 == 0 {
+                            drop(store.reserve(user, &["red"]));
+                        } else {
+                            let _ = store.giveaway(user, &["red"]);
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    for user in ["x", "y", "z"] {
+        assert_eq!(store.given_to(user).len(), 2, "{user}");
+    }
+    assert_eq!(
+        store.giveaway("x", &[]),
+        Err(GiveawayError::LimitReached { limit: 2 })
+    );
+
+    // 待ち行列から配るときも上限を見る（上限に達した人は飛ばす）
+    let store =
+        Inventory::with_stock([("red", 1)]).with_strategy(Limited::new(FirstCome::new(), 1));
+    assert_eq!(store.giveaway("a", &[]), Ok("red"));
+    let _ = store.giveaway("b", &[]);
+    let _ = store.giveaway("c", &[]);
+    let held = store.reserve("a", &[]);
+    assert_eq!(held.err(), Some(GiveawayError::LimitReached { limit: 1 }));
+    let served = store.restock("red", 2);
+    let users: Vec<&str> = served.iter().map(|g| g.user.as_str()).collect();
+    assert_eq!(users, ["b", "c"]);
+}
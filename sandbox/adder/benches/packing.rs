@@ -0,0 +1,135 @@
+// ====================================================================
+// ビンパッキングのベンチマーク（cargo bench）
+// ====================================================================
+//
+// 外部クレートを使わない自前のハーネス
+//   - シード固定で生成した item の集合を、戦略 × 回転の有無で詰める
+//   - 所要時間（中央値）・シート枚数・使用率を表にする
+//   - 同じ入力を2回詰めて結果が同じか（決定的か）も確かめる
+//
+// 使い方:
+//   cargo bench --bench packing            — すべて
+//   cargo bench --bench packing -- sprites — 名前に sprites を含むデータだけ
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+use std::time::{Duration, Instant};
+
+use adder::Rectangle;
+use adder::geometry::Rotation;
+use adder::packing::{PackOptions, Strategy, pack};
+use test_support::prop::Rng;
+
+fn between(rng: &mut Rng, low: u32, high: u32) -> u32 {
+    low + rng.next_u32() % (high - low + 1)
+}
+
+struct Dataset {
+    name: &'static str,
+    sheet: Rectangle,
+    items: Vec<Rectangle>,
+}
+
+fn datasets() -> Vec<Dataset> {
+    let mut rng = Rng::new(0x853c_49e6_748f_ea9b);
+    let mut make = |count: usize, min: u32, max: u32| -> Vec<Rectangle> {
+        (0..count)
+            .map(|_| Rectangle {
+                width: between(&mut rng, min, max),
+                height: between(&mut rng, min, max),
+            })
+            .collect()
+    };
+    let labels = make(500, 20, 90);
+    let sprites = make(2000, 4, 64);
+    let mixed = make(300, 10, 400);
+    vec![
+        // 宛名ラベル: 似た大きさが多い
+        Dataset {
+            name: "labels_500",
+            sheet: Rectangle {
+                width: 595,
+                height: 842,
+            },
+            items: labels,
+        },
+        // スプライト: 小さいものが大量
+        Dataset {
+            name: "sprites_2000",
+            sheet: Rectangle {
+                width: 1024,
+                height: 1024,
+            },
+            items: sprites,
+        },
+        // 大小混在
+        Dataset {
+            name: "mixed_300",
+            sheet: Rectangle {
+                width: 800,
+                height: 600,
+            },
+            items: mixed,
+        },
+    ]
+}
+
+const MIN_RUNS: usize = 5;
+const TARGET: Duration = Duration::from_millis(300);
+
+fn main() {
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let mut failed = false;
+
+    println!(
+        "{:<14} {:<11} {:<8} {:>10} {:>7} {:>12}",
+        "dataset", "strategy", "rotation", "median", "sheets", "utilization"
+    );
+    for dataset in datasets() {
+        if filter
+            .as_ref()
+            .is_some_and(|f| !dataset.name.contains(f.as_str()))
+        {
+            continue;
+        }
+        for strategy in Strategy::ALL {
+            for rotation in [Rotation::Fixed, Rotation::Allowed] {
+                let options = PackOptions { strategy, rotation };
+                let packing = pack(&dataset.items, &dataset.sheet, options).unwrap();
+                if pack(&dataset.items, &dataset.sheet, options).unwrap() != packing {
+                    eprintln!(
+                        "{} {strategy:?} {rotation:?}: result is not deterministic",
+                        dataset.name
+                    );
+                    failed = true;
+                }
+
+                // 最低 MIN_RUNS 回、合計 TARGET を超えるまで繰り返して中央値を取る
+                let mut times = Vec::new();
+                let started = Instant::now();
+                while times.len() < MIN_RUNS || started.elapsed() < TARGET {
+                    let start = Instant::now();
+                    black_box(pack(black_box(&dataset.items), &dataset.sheet, options).unwrap());
+                    times.push(start.elapsed());
+                }
+                times.sort();
+                let median = times[times.len() / 2];
+
+                println!(
+                    "{:<14} {:<11} {:<8} {:>8.2}ms {:>7} {:>11.1}%",
+                    dataset.name,
+                    format!("{strategy:?}"),
+                    format!("{rotation:?}"),
+                    median.as_secs_f64() * 1000.0,
+                    packing.sheets,
+                    packing.utilization() * 100.0
+                );
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
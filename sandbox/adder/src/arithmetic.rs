@@ -0,0 +1,289 @@
+// ====================================================================
+// 足し算のオーバーフローを明示的に扱う
+// ====================================================================
+//
+// add(u64, u64) や add_two(i32) の `+` は、あふれると
+//   debug ビルド   → panic（attempt to add with overflow）
+//   release ビルド → 黙って折り返す（2の補数で wrap）
+// ビルドによって振る舞いが変わるので、どうしたいかを呼び出し側で選べるようにする
+//
+//   checked_add     — あふれたら Err(OverflowError)（上にあふれたか下にあふれたかも分かる）
+//   saturating_add  — あふれたら T の最大値・最小値に張り付く
+//   wrapping_add    — あふれたら折り返す（release の `+` と同じ。debug でも panic しない）
+//
+// どれも bounded::Integer を実装した整数型（i8〜i128, isize, u8〜u128, usize）に使える
+// （overflowing_add — 結果と「あふれたか」の組 — さえあれば3つのモードを作れる）
+//
+// u64 に収まらない数の足し算:
+//   add_limbs — u64 を「桁」（limb、下の桁から順）とみなした任意長の足し算
+
+use std::error::Error;
+use std::fmt;
+
+use crate::bounded::Integer;
+
+// ====================================================================
+// エラー
+// ====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowError<T> {
+    Above { lhs: T, rhs: T }, // 最大値を超えた
+    Below { lhs: T, rhs: T }, // 最小値を下回った（符号つきで負の数を足したとき）
+}
+
+impl<T: Integer> fmt::Display for OverflowError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ty = std::any::type_name::<T>();
+        match self {
+            OverflowError::Above { lhs, rhs } => {
+                write!(f, "{lhs} + {rhs} は {ty} の最大値 {} を超えます", T::MAX)
+            }
+            OverflowError::Below { lhs, rhs } => {
+                write!(f, "{lhs} + {rhs} は {ty} の最小値 {} を下回ります", T::MIN)
+            }
+        }
+    }
+}
+
+impl<T: Integer> Error for OverflowError<T> {}
+
+// ====================================================================
+// 3つのモード
+// ====================================================================
+
+pub fn checked_add<T: Integer>(lhs: T, rhs: T) -> Result<T, OverflowError<T>> {
+    match lhs.overflowing_add(rhs) {
+        (sum, false) => Ok(sum),
+        // 正の数を足してあふれたら上、負の数を足してあふれたら下（符号なしは常に上）
+        _ if rhs > T::ZERO => Err(OverflowError::Above { lhs, rhs }),
+        _ => Err(OverflowError::Below { lhs, rhs }),
+    }
+}
+
+pub fn saturating_add<T: Integer>(lhs: T, rhs: T) -> T {
+    match checked_add(lhs, rhs) {
+        Ok(sum) => sum,
+        Err(OverflowError::Above { .. }) => T::MAX,
+        Err(OverflowError::Below { .. }) => T::MIN,
+    }
+}
+
+pub fn wrapping_add<T: Integer>(lhs: T, rhs: T) -> T {
+    lhs.overflowing_add(rhs).0
+}
+
+// ====================================================================
+// 任意長の足し算
+// ====================================================================
+
+// a・b は u64 の桁を下から並べたもの（[下位, 上位, ...]）
+//   例: u64::MAX + 1 → add_limbs(&[u64::MAX], &[1]) == [0, 1]（= 2^64）
+// 結果の最上位の桁が 0 にならないよう、繰り上がりがあるときだけ桁を増やす
+pub fn add_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = false;
+    for (i, &x) in long.iter().enumerate() {
+        let y = short.get(i).copied().unwrap_or(0);
+        let (s1, c1) = x.overflowing_add(y);
+        let (s2, c2) = s1.overflowing_add(u64::from(carry));
+        sum.push(s2);
+        carry = c1 || c2;
+    }
+    if carry {
+        sum.push(1);
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ----------------------------------------------------------------
+    // 8ビットの型は全部の組み合わせを、より広い型で計算した答えと比べる
+    // ----------------------------------------------------------------
+
+    #[test]
+    fn all_u8_pairs() {
+        for a in u8::MIN..=u8::MAX {
+            for b in u8::MIN..=u8::MAX {
+                let exact = u16::from(a) + u16::from(b);
+                match u8::try_from(exact) {
+                    Ok(sum) => assert_eq!(checked_add(a, b), Ok(sum)),
+                    Err(_) => assert_eq!(
+                        checked_add(a, b),
+                        Err(OverflowError::Above { lhs: a, rhs: b })
+                    ),
+                }
+                assert_eq!(u16::from(saturating_add(a, b)), exact.min(255));
+                assert_eq!(u16::from(wrapping_add(a, b)), exact % 256);
+            }
+        }
+    }
+
+    #[test]
+    fn all_i8_pairs() {
+        for a in i8::MIN..=i8::MAX {
+            for b in i8::MIN..=i8::MAX {
+                let exact = i16::from(a) + i16::from(b);
+                let expected = if exact > 127 {
+                    Err(OverflowError::Above { lhs: a, rhs: b })
+                } else if exact < -128 {
+                    Err(OverflowError::Below { lhs: a, rhs: b })
+                } else {
+                    Ok(exact as i8)
+                };
+                assert_eq!(checked_add(a, b), expected);
+                assert_eq!(i16::from(saturating_add(a, b)), exact.clamp(-128, 127));
+                assert_eq!(wrapping_add(a, b), exact as i8);
+            }
+        }
+    }
+
+    // ----------------------------------------------------------------
+    // 広い型は境界付近の値どうしの全部の組み合わせを i128 の答えと比べる
+    // ----------------------------------------------------------------
+
+    macro_rules! boundary_test {
+        ($name:ident, $t:ty) => {
+            #[test]
+            fn $name() {
+                let edges: Vec<$t> = [
+                    <$t>::MIN,
+                    <$t>::MIN.wrapping_add(1),
+                    <$t>::MIN.wrapping_add(2),
+                    <$t>::MAX / 2,
+                    <$t>::MAX / 2 + 1,
+                    <$t>::MAX - 1,
+                    <$t>::MAX,
+                    0,
+                    1,
+                ]
+                .into_iter()
+                .chain(<$t>::try_from(-1).ok())
+                .collect();
+                let (lo, hi) = (<$t>::MIN as i128, <$t>::MAX as i128);
+                for &a in &edges {
+                    for &b in &edges {
+                        let exact = a as i128 + b as i128;
+                        let result = checked_add(a, b);
+                        if exact > hi {
+                            assert_eq!(result, Err(OverflowError::Above { lhs: a, rhs: b }));
+                        } else if exact < lo {
+                            assert_eq!(result, Err(OverflowError::Below { lhs: a, rhs: b }));
+                        } else {
+                            assert_eq!(result.map(|s| s as i128), Ok(exact));
+                        }
+                        assert_eq!(saturating_add(a, b) as i128, exact.clamp(lo, hi));
+                        assert_eq!(wrapping_add(a, b), exact as $t);
+                    }
+                }
+            }
+        };
+    }
+
+    boundary_test!(boundaries_i16, i16);
+    boundary_test!(boundaries_i32, i32);
+    boundary_test!(boundaries_i64, i64);
+    boundary_test!(boundaries_isize, isize);
+    boundary_test!(boundaries_u16, u16);
+    boundary_test!(boundaries_u32, u32);
+    boundary_test!(boundaries_u64, u64);
+    boundary_test!(boundaries_usize, usize);
+
+    #[test]
+    fn boundaries_128_bit() {
+        // i128 で答えを計算できないので、境界の性質を直接確かめる
+        assert_eq!(
+            checked_add(u128::MAX, 1),
+            Err(OverflowError::Above {
+                lhs: u128::MAX,
+                rhs: 1
+            })
+        );
+        assert_eq!(checked_add(u128::MAX - 1, 1), Ok(u128::MAX));
+        assert_eq!(saturating_add(u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(wrapping_add(u128::MAX, 2), 1);
+
+        assert_eq!(
+            checked_add(i128::MIN, -1),
+            Err(OverflowError::Below {
+                lhs: i128::MIN,
+                rhs: -1
+            })
+        );
+        assert_eq!(checked_add(i128::MIN, i128::MAX), Ok(-1));
+        assert_eq!(saturating_add(i128::MAX, 1), i128::MAX);
+        assert_eq!(saturating_add(i128::MIN, i128::MIN), i128::MIN);
+        assert_eq!(wrapping_add(i128::MAX, 1), i128::MIN);
+    }
+
+    #[test]
+    fn add_and_add_two_edges() {
+        // lib.rs の add / add_two があふれる境界
+        assert!(checked_add(u64::MAX, 0).is_ok());
+        assert!(checked_add(u64::MAX, 1).is_err());
+        assert_eq!(checked_add(i32::MAX - 2, 2), Ok(i32::MAX));
+        assert_eq!(
+            checked_add(i32::MAX - 1, 2).unwrap_err().to_string(),
+            "2147483646 + 2 は i32 の最大値 2147483647 を超えます"
+        );
+    }
+
+    // ----------------------------------------------------------------
+    // 任意長の足し算
+    // ----------------------------------------------------------------
+
+    fn to_u128(limbs: &[u64]) -> u128 {
+        assert!(limbs.len() <= 2);
+        limbs
+            .iter()
+            .rev()
+            .fold(0, |acc, &limb| (acc << 64) | u128::from(limb))
+    }
+
+    fn split(v: u128) -> Vec<u64> {
+        if v >> 64 == 0 {
+            vec![v as u64]
+        } else {
+            vec![v as u64, (v >> 64) as u64]
+        }
+    }
+
+    #[test]
+    fn limbs_agree_with_u128() {
+        let values = [
+            0u128,
+            1,
+            u64::MAX as u128,
+            u64::MAX as u128 + 1,
+            u128::MAX / 2,
+            0x1234_5678_9abc_def0_fedc_ba98_7654_3210,
+        ];
+        for &a in &values {
+            for &b in &values {
+                let sum = add_limbs(&split(a), &split(b));
+                match a.checked_add(b) {
+                    Some(exact) => assert_eq!(to_u128(&sum), exact, "{a} + {b}"),
+                    None => {
+                        // u128 を超えたら3桁目が 1 になり、下2桁は折り返した値
+                        assert_eq!(sum.len(), 3);
+                        assert_eq!(sum[2], 1);
+                        assert_eq!(to_u128(&sum[..2]), a.wrapping_add(b));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn limbs_carry_through_every_digit() {
+        let all_ones = [u64::MAX; 4];
+        assert_eq!(add_limbs(&all_ones, &[1]), vec![0, 0, 0, 0, 1]);
+        assert_eq!(add_limbs(&[1], &all_ones), vec![0, 0, 0, 0, 1]);
+        assert_eq!(add_limbs(&[], &[]), Vec::<u64>::new());
+        assert_eq!(add_limbs(&[5, 7], &[]), vec![5, 7]);
+    }
+}
@@ -0,0 +1,581 @@
+// ====================================================================
+// 任意精度の整数 — BigInt
+// ====================================================================
+//
+// u64（add の引数）や u128 を超える合計・積を、桁あふれなしで計算する
+//
+//   let total: BigInt = amounts.iter().map(|&a| BigInt::from(a)).sum();
+//   let x: BigInt = "-123456789012345678901234567890".parse()?;
+//   let y = BigInt::from_str_radix("ffff", 16)?;
+//
+// 表現: 符号 + 絶対値（u64 の桁を下から並べた Vec<u64>、bigint/magnitude.rs）
+//   0 は常に「負でない・桁なし」→ 同じ値の表現は1通りなので derive(PartialEq, Hash) でよい
+//
+// 演算:
+//   + - * は演算子（&a + &b のように参照どうしでも使える）
+//   / % と div_rem は Rust の整数と同じく 0 に向かって切り捨て（余りの符号は割られる数と同じ）
+//   0 で割ると panic（整数と同じ）。panic させたくなければ checked_div_rem
+//
+// 文字列:
+//   parse / FromStr — 10進（"0x" で始まれば16進）、先頭に + か -
+//   from_str_radix  — 2〜36 進数
+//   Display は10進、{:x} {:X} は16進（{:#x} で 0x つき）、to_str_radix で任意の基数
+
+mod magnitude;
+mod radix;
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u64>, // 正規化済み（最上位の桁は 0 でない）
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    // 0 のときは符号を付けない
+    fn from_parts(negative: bool, magnitude: Vec<u64>) -> BigInt {
+        BigInt {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    // 絶対値を2進で書いたときの桁数（0 は 0）
+    pub fn bits(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() as u64 * 64 - u64::from(top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    // 0 に向かって切り捨てた (商, 余り)。rhs が 0 なら None
+    pub fn checked_div_rem(&self, rhs: &BigInt) -> Option<(BigInt, BigInt)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (q, r) = magnitude::div_rem(&self.magnitude, &rhs.magnitude);
+        Some((
+            BigInt::from_parts(self.negative != rhs.negative, q),
+            BigInt::from_parts(self.negative, r),
+        ))
+    }
+
+    pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        self.checked_div_rem(rhs)
+            .expect("attempt to divide by zero")
+    }
+
+    // radix は 2〜36（範囲外なら panic — 標準の from_str_radix と同じ）
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in 2..=36, got {radix}"
+        );
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        let offset = s.len() - digits.len();
+        let magnitude =
+            radix::parse(digits, radix).map_err(|position| ParseBigIntError::InvalidDigit {
+                position: offset + position,
+            })?;
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in 2..=36, got {radix}"
+        );
+        let digits = radix::format(&self.magnitude, radix, false);
+        if self.negative {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+}
+
+// ====================================================================
+// エラー
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,                            // 数字が1つもない（"" や "-"）
+    InvalidDigit { position: usize }, // その位置（バイト）の文字が数字でない
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit { position } => {
+                write!(f, "invalid digit found at byte {position}")
+            }
+        }
+    }
+}
+
+impl Error for ParseBigIntError {}
+
+// プリミティブ型に収まらない
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromBigIntError;
+
+impl fmt::Display for TryFromBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BigInt out of range for the target integer type")
+    }
+}
+
+impl Error for TryFromBigIntError {}
+
+// ====================================================================
+// 変換
+// ====================================================================
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(v: $t) -> BigInt {
+                    BigInt::from_parts(false, magnitude::from_u128(v as u128))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(v: $t) -> BigInt {
+                    let v = v as i128;
+                    BigInt::from_parts(v < 0, magnitude::from_u128(v.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
+
+// 逆向き: いったん u128 / i128 にしてから目的の型に収める
+macro_rules! try_into_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<&BigInt> for $t {
+                type Error = TryFromBigIntError;
+
+                fn try_from(v: &BigInt) -> Result<$t, TryFromBigIntError> {
+                    let abs = magnitude::to_u128(&v.magnitude).ok_or(TryFromBigIntError)?;
+                    if v.negative {
+                        // -2^127 まで（i128::MIN の絶対値は i128 に収まらない）
+                        let neg = 0i128.checked_sub_unsigned(abs).ok_or(TryFromBigIntError)?;
+                        <$t>::try_from(neg).map_err(|_| TryFromBigIntError)
+                    } else {
+                        <$t>::try_from(abs).map_err(|_| TryFromBigIntError)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+try_into_primitive!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'-' | b'+') => s.split_at(1),
+            _ => ("", s),
+        };
+        match rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+            Some(hex) => {
+                // エラーの位置は元の文字列でのバイト位置にそろえる
+                let parsed = BigInt::from_str_radix(&format!("{sign}{hex}"), 16);
+                parsed.map_err(|e| match e {
+                    ParseBigIntError::InvalidDigit { position } => ParseBigIntError::InvalidDigit {
+                        position: position + 2,
+                    },
+                    e => e,
+                })
+            }
+            None => BigInt::from_str_radix(s, 10),
+        }
+    }
+}
+
+// ====================================================================
+// 表示 — pad_integral で幅・+・# などの書式指定にも対応する
+// ====================================================================
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = radix::format(&self.magnitude, 10, false);
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = radix::format(&self.magnitude, 16, false);
+        f.pad_integral(!self.negative, "0x", &digits)
+    }
+}
+
+impl fmt::UpperHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = radix::format(&self.magnitude, 16, true);
+        f.pad_integral(!self.negative, "0x", &digits)
+    }
+}
+
+// ====================================================================
+// 比較
+// ====================================================================
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => magnitude::cmp(&self.magnitude, &other.magnitude),
+            // 負どうしは絶対値が大きい方が小さい
+            (true, true) => magnitude::cmp(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// ====================================================================
+// 演算子 — 本体は &BigInt どうし、所有権を取る版はそれに委ねる
+// ====================================================================
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(
+                self.negative,
+                magnitude::add(&self.magnitude, &rhs.magnitude),
+            );
+        }
+        // 符号が違う → 絶対値の大きい方から小さい方を引き、大きい方の符号にする
+        match magnitude::cmp(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                rhs.negative,
+                magnitude::sub(&rhs.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                magnitude::sub(&self.magnitude, &rhs.magnitude),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &(-rhs)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            magnitude::mul(&self.magnitude, &rhs.magnitude),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+macro_rules! forward_owned {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl $trait for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, rhs: BigInt) -> BigInt {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl $trait<&BigInt> for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, rhs: &BigInt) -> BigInt {
+                    (&self).$method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::prop::Rng;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn sums_beyond_u64() {
+        let total: BigInt = [u64::MAX, u64::MAX, 2].into_iter().map(BigInt::from).sum();
+        assert_eq!(total.to_string(), "36893488147419103232"); // 2^65
+        assert_eq!(total, BigInt::from(2u128 << 64));
+    }
+
+    #[test]
+    fn known_values() {
+        // 30! と 2^128
+        let factorial = (1..=30u32).fold(BigInt::from(1), |acc, n| acc * BigInt::from(n));
+        assert_eq!(factorial, big("265252859812191058636308480000000"));
+        let two_128 = BigInt::from(u128::MAX) + BigInt::from(1);
+        assert_eq!(
+            two_128.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(format!("{two_128:x}"), format!("1{}", "0".repeat(32)));
+        assert_eq!(u128::try_from(&two_128), Err(TryFromBigIntError));
+        assert_eq!(two_128.bits(), 129);
+        assert_eq!((-two_128).bits(), 129);
+        assert_eq!(BigInt::from(-5).bits(), 3);
+        assert_eq!(BigInt::zero().bits(), 0);
+    }
+
+    #[test]
+    fn parsing_and_formatting() {
+        assert_eq!(big("-0"), BigInt::zero());
+        assert!(!big("-0").is_negative());
+        assert_eq!(big("+42"), BigInt::from(42));
+        assert_eq!(big("0xFF"), BigInt::from(255));
+        assert_eq!(big("-0x10"), BigInt::from(-16));
+        assert_eq!(
+            BigInt::from_str_radix("zz", 36),
+            Ok(BigInt::from(36 * 36 - 1))
+        );
+
+        assert_eq!("".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!("0x".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!(
+            "12a4".parse::<BigInt>(),
+            Err(ParseBigIntError::InvalidDigit { position: 2 })
+        );
+        assert_eq!(
+            "-0x1g".parse::<BigInt>(),
+            Err(ParseBigIntError::InvalidDigit { position: 4 })
+        );
+
+        let n = BigInt::from(-255);
+        assert_eq!(format!("{n:>6}"), "  -255");
+        assert_eq!(format!("{n:#X}"), "-0xFF");
+        assert_eq!(format!("{:+}", BigInt::from(7)), "+7");
+        assert_eq!(n.to_str_radix(2), "-11111111");
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn division_by_zero_panics() {
+        let _ = BigInt::from(1) / BigInt::zero();
+    }
+
+    // ----------------------------------------------------------------
+    // u128 / i128 で計算できる範囲の値で、結果が一致するか比べる（差分テスト）
+    // ----------------------------------------------------------------
+
+    // 桁数のばらばらな i64（0・±1・境界値も混ぜる）
+    fn random_i64(rng: &mut Rng) -> i64 {
+        match rng.next_u64() % 6 {
+            0 => [0, 1, -1, i64::MIN, i64::MAX][rng.below(5) as usize],
+            1 => (rng.next_u64() % 1000) as i64 - 500,
+            2 => (rng.next_u64() >> (rng.next_u64() % 63)) as i64,
+            _ => rng.next_u64() as i64,
+        }
+    }
+
+    #[test]
+    fn agrees_with_i128() {
+        let mut rng = Rng::new(0x4f6c_dd1d_2545_f491);
+        for _ in 0..20_000 {
+            let (a, b) = (random_i64(&mut rng), random_i64(&mut rng));
+            let (x, y) = (i128::from(a), i128::from(b));
+            let (ba, bb) = (BigInt::from(a), BigInt::from(b));
+
+            assert_eq!(&ba + &bb, BigInt::from(x + y));
+            assert_eq!(&ba - &bb, BigInt::from(x - y));
+            assert_eq!(&ba * &bb, BigInt::from(x * y));
+            assert_eq!(ba.cmp(&bb), a.cmp(&b));
+            if b != 0 {
+                assert_eq!(ba.div_rem(&bb), (BigInt::from(x / y), BigInt::from(x % y)));
+            } else {
+                assert_eq!(ba.checked_div_rem(&bb), None);
+            }
+
+            let product = x * y;
+            let big_product = BigInt::from(product);
+            assert_eq!(big_product.to_string(), product.to_string());
+            assert_eq!(format!("{big_product:x}"), {
+                let hex = format!("{:x}", product.unsigned_abs());
+                if product < 0 { format!("-{hex}") } else { hex }
+            });
+            assert_eq!(big(&product.to_string()), big_product);
+            assert_eq!(i128::try_from(&big_product), Ok(product));
+            assert_eq!(
+                i64::try_from(&big_product).ok(),
+                i64::try_from(product).ok()
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_u128_division() {
+        let mut rng = Rng::new(0x1d2e_3f40_5a6b_7c8d);
+        for _ in 0..20_000 {
+            let a = u128::from(rng.next_u64()) << 64 | u128::from(rng.next_u64());
+            let b = match rng.next_u64() % 3 {
+                0 => u128::from(rng.next_u64()),
+                1 => a >> (rng.next_u64() % 128),
+                _ => u128::from(rng.next_u64()) << 64 | u128::from(rng.next_u64()),
+            };
+            if b == 0 {
+                continue;
+            }
+            let (q, r) = BigInt::from(a).div_rem(&BigInt::from(b));
+            assert_eq!(
+                (q, r),
+                (BigInt::from(a / b), BigInt::from(a % b)),
+                "{a} / {b}"
+            );
+        }
+    }
+
+    #[test]
+    fn large_operands_round_trip() {
+        // Karatsuba が使われる大きさ（数百桁）で、掛けて割ると元に戻るか
+        let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..20 {
+            let digits = |rng: &mut Rng, n: usize| -> String {
+                let mut s = (1 + rng.next_u64() % 9).to_string();
+                s.extend((1..n).map(|_| char::from(b'0' + (rng.next_u64() % 10) as u8)));
+                s
+            };
+            let a = big(&digits(&mut rng, 700));
+            let b = big(&format!("-{}", digits(&mut rng, 650)));
+            let c = big(&digits(&mut rng, 30));
+
+            let product = &a * &b;
+            assert_eq!(&product / &b, a);
+            assert!((&product % &b).is_zero());
+            assert_eq!(big(&product.to_string()), product);
+            assert_eq!(
+                BigInt::from_str_radix(&product.to_str_radix(16), 16),
+                Ok(product.clone())
+            );
+
+            // 分配法則
+            assert_eq!(&a * &(&b + &c), &(&a * &b) + &(&a * &c));
+            let (q, r) = (&product + &c).div_rem(&a);
+            assert_eq!(&(&q * &a) + &r, &product + &c);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// ====================================================================
+// 絶対値（magnitude）の計算 — u64 の桁を下から並べた Vec<u64>
+// ====================================================================
+//
+// 「正規化」された表現だけを扱う: 最上位の桁は 0 でない（0 は空の Vec）
+//   → 桁数の比較だけで大小がほぼ決まり、同じ値の表現が1通りになる
+//
+// 掛け算:  短い方が KARATSUBA_THRESHOLD 桁未満なら筆算 O(n·m)、それ以上は Karatsuba O(n^1.58)
+// 割り算:  Knuth の Algorithm D（The Art of Computer Programming 4.3.1）
+//          1桁で割るときは u128 ÷ u64 を繰り返すだけの short division
+
+use std::cmp::Ordering;
+
+use crate::arithmetic::add_limbs;
+
+// これより短い数は筆算の方が速い（目安。おおよそ 32〜64 桁で逆転する）
+const KARATSUBA_THRESHOLD: usize = 32;
+
+pub fn normalize(mut v: Vec<u64>) -> Vec<u64> {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+    v
+}
+
+pub fn from_u128(v: u128) -> Vec<u64> {
+    normalize(vec![v as u64, (v >> 64) as u64])
+}
+
+// 2桁以下なら u128 に直す
+pub fn to_u128(v: &[u64]) -> Option<u128> {
+    match v {
+        [] => Some(0),
+        [lo] => Some(u128::from(*lo)),
+        [lo, hi] => Some(u128::from(*hi) << 64 | u128::from(*lo)),
+        _ => None,
+    }
+}
+
+pub fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+pub fn add(a: &[u64], b: &[u64]) -> Vec<u64> {
+    add_limbs(a, b)
+}
+
+// a >= b であること
+pub fn sub(a: &[u64], b: &[u64]) -> Vec<u64> {
+    debug_assert!(cmp(a, b) != Ordering::Less);
+    let mut diff = a.to_vec();
+    sub_in_place(&mut diff, b);
+    normalize(diff)
+}
+
+fn sub_in_place(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if y == 0 && !borrow && i >= b.len() {
+            break;
+        }
+        let (d1, b1) = x.overflowing_sub(y);
+        let (d2, b2) = d1.overflowing_sub(u64::from(borrow));
+        *x = d2;
+        borrow = b1 || b2;
+    }
+    debug_assert!(!borrow);
+}
+
+// acc[shift..] += v（acc は十分な長さがあること）
+fn add_shifted(acc: &mut [u64], v: &[u64], shift: usize) {
+    let mut carry = false;
+    for (i, slot) in acc[shift..].iter_mut().enumerate() {
+        let y = v.get(i).copied().unwrap_or(0);
+        if y == 0 && !carry && i >= v.len() {
+            break;
+        }
+        let (s1, c1) = slot.overflowing_add(y);
+        let (s2, c2) = s1.overflowing_add(u64::from(carry));
+        *slot = s2;
+        carry = c1 || c2;
+    }
+    debug_assert!(!carry);
+}
+
+// ====================================================================
+// 掛け算
+// ====================================================================
+
+pub fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        schoolbook(a, b)
+    } else {
+        karatsuba(a, b)
+    }
+}
+
+// 筆算: 1桁 × 1桁 は u128 に収まる（(2^64-1)^2 + 2·(2^64-1) = 2^128 - 1）
+fn schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let t = u128::from(x) * u128::from(y) + u128::from(out[i + j]) + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+    normalize(out)
+}
+
+// a = a1·B^m + a0, b = b1·B^m + b0（B = 2^64）とすると
+//   a·b = z2·B^2m + z1·B^m + z0
+//   z0 = a0·b0, z2 = a1·b1, z1 = (a0 + a1)(b0 + b1) - z0 - z2
+// 4回の掛け算が3回になる
+fn karatsuba(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let m = a.len().max(b.len()) / 2;
+    let mut out = vec![0u64; a.len() + b.len() + 1];
+
+    // 長さが大きく違うと短い方が m 桁以下になる → 長い方だけ分けて2回掛ける
+    if a.len().min(b.len()) <= m {
+        let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+        let (lo, hi) = long.split_at(m);
+        add_shifted(&mut out, &mul(&normalize(lo.to_vec()), short), 0);
+        add_shifted(&mut out, &mul(hi, short), m);
+        return normalize(out);
+    }
+
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let (a0, b0) = (normalize(a0.to_vec()), normalize(b0.to_vec()));
+
+    let z0 = mul(&a0, &b0);
+    let z2 = mul(a1, b1);
+    let mut z1 = mul(&add(&a0, a1), &add(&b0, b1));
+    sub_in_place(&mut z1, &z0);
+    sub_in_place(&mut z1, &z2);
+    let z1 = normalize(z1);
+
+    add_shifted(&mut out, &z0, 0);
+    add_shifted(&mut out, &z1, m);
+    add_shifted(&mut out, &z2, 2 * m);
+    normalize(out)
+}
+
+// v · factor + addend（文字列からの変換で使う）
+pub fn mul_small_add(v: &mut Vec<u64>, factor: u64, addend: u64) {
+    let mut carry = u128::from(addend);
+    for limb in v.iter_mut() {
+        let t = u128::from(*limb) * u128::from(factor) + carry;
+        *limb = t as u64;
+        carry = t >> 64;
+    }
+    if carry > 0 {
+        v.push(carry as u64);
+    }
+}
+
+// ====================================================================
+// 割り算
+// ====================================================================
+
+// 1桁で割る: 上の桁から「余り·B + 次の桁」を割っていく
+pub fn div_rem_small(a: &[u64], d: u64) -> (Vec<u64>, u64) {
+    assert!(d != 0);
+    let mut q = vec![0u64; a.len()];
+    let mut rem = 0u128;
+    for (i, &limb) in a.iter().enumerate().rev() {
+        let cur = rem << 64 | u128::from(limb);
+        q[i] = (cur / u128::from(d)) as u64;
+        rem = cur % u128::from(d);
+    }
+    (normalize(q), rem as u64)
+}
+
+// (商, 余り)。b は 0 でないこと
+pub fn div_rem(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    assert!(!b.is_empty());
+    if cmp(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if let [d] = b {
+        let (q, r) = div_rem_small(a, *d);
+        return (q, normalize(vec![r]));
+    }
+
+    // 割る数の最上位の桁の最上位ビットが 1 になるようにずらす（商の見積もりが最大2つずれるだけになる）
+    let shift = b[b.len() - 1].leading_zeros();
+    let b = shl_bits(b, shift);
+    let mut a = shl_bits(a, shift);
+    a.push(0);
+    let n = b.len();
+    let m = a.len() - n;
+    let (top, second) = (u128::from(b[n - 1]), u128::from(b[n - 2]));
+    let mut q = vec![0u64; m];
+
+    for j in (0..m).rev() {
+        // 上の2桁 ÷ 割る数の最上位の桁 で商の1桁を見積もり、3桁目を見て補正する
+        let num = u128::from(a[j + n]) << 64 | u128::from(a[j + n - 1]);
+        let mut qhat = num / top;
+        let mut rhat = num % top;
+        while qhat >> 64 != 0 || qhat * second > (rhat << 64 | u128::from(a[j + n - 2])) {
+            qhat -= 1;
+            rhat += top;
+            if rhat >> 64 != 0 {
+                break;
+            }
+        }
+
+        // a[j..=j+n] -= qhat · b
+        let mut carry = 0u128;
+        let mut borrow = false;
+        for i in 0..n {
+            let p = qhat * u128::from(b[i]) + carry;
+            carry = p >> 64;
+            let (d1, b1) = a[i + j].overflowing_sub(p as u64);
+            let (d2, b2) = d1.overflowing_sub(u64::from(borrow));
+            a[i + j] = d2;
+            borrow = b1 || b2;
+        }
+        let (d1, b1) = a[j + n].overflowing_sub(carry as u64);
+        let (d2, b2) = d1.overflowing_sub(u64::from(borrow));
+        a[j + n] = d2;
+
+        // 引きすぎた（見積もりが1大きかった）ら1回分足し戻す — 起きるのはまれ
+        if b1 || b2 {
+            qhat -= 1;
+            let mut carry = false;
+            for i in 0..n {
+                let (s1, c1) = a[i + j].overflowing_add(b[i]);
+                let (s2, c2) = s1.overflowing_add(u64::from(carry));
+                a[i + j] = s2;
+                carry = c1 || c2;
+            }
+            a[j + n] = a[j + n].wrapping_add(u64::from(carry));
+        }
+        q[j] = qhat as u64;
+    }
+
+    let rem = shr_bits(&a[..n], shift);
+    (normalize(q), normalize(rem))
+}
+
+fn shl_bits(v: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return v.to_vec();
+    }
+    let mut out = Vec::with_capacity(v.len() + 1);
+    let mut carry = 0u64;
+    for &limb in v {
+        out.push(limb << shift | carry);
+        carry = limb >> (64 - shift);
+    }
+    if carry != 0 {
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_bits(v: &[u64], shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return v.to_vec();
+    }
+    let mut out = vec![0u64; v.len()];
+    for i in 0..v.len() {
+        let hi = v.get(i + 1).map_or(0, |&next| next << (64 - shift));
+        out[i] = v[i] >> shift | hi;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::prop::Rng;
+
+    // 0 や u64::MAX の桁が多いと繰り上がり・借りの境界を通りやすい
+    fn random_limbs(rng: &mut Rng, len: usize) -> Vec<u64> {
+        let v = (0..len)
+            .map(|_| match rng.next_u64() % 4 {
+                0 => 0,
+                1 => u64::MAX,
+                _ => rng.next_u64(),
+            })
+            .collect();
+        normalize(v)
+    }
+
+    #[test]
+    fn karatsuba_agrees_with_schoolbook() {
+        let mut rng = Rng::new(0x853c_49e6_748f_ea9b);
+        for len in [32, 33, 47, 64, 100, 150] {
+            for other in [1, 16, 40, len / 2 + 1, len] {
+                let a = random_limbs(&mut rng, len);
+                let b = random_limbs(&mut rng, other);
+                if a.is_empty() || b.is_empty() {
+                    continue;
+                }
+                assert_eq!(karatsuba(&a, &b), schoolbook(&a, &b), "{len} x {other}");
+            }
+        }
+    }
+
+    #[test]
+    fn division_reconstructs_the_dividend() {
+        let mut rng = Rng::new(0xda3e_39cb_94b9_5bdb);
+        for _ in 0..300 {
+            let (a_len, b_len) = (1 + rng.next_u64() % 40, 1 + rng.next_u64() % 20);
+            let a = random_limbs(&mut rng, a_len as usize);
+            let b = random_limbs(&mut rng, b_len as usize);
+            if b.is_empty() {
+                continue;
+            }
+            let (q, r) = div_rem(&a, &b);
+            assert_eq!(cmp(&r, &b), Ordering::Less);
+            assert_eq!(add(&mul(&q, &b), &r), a);
+        }
+    }
+
+    #[test]
+    fn division_needs_add_back() {
+        // 3桁目を見た補正のあとでも商の見積もりが1大きく、足し戻しが必要になる入力
+        //   (2^192 - 2^128) ÷ (2^128 + 1)
+        let a = [0, 0, u64::MAX];
+        let b = [1, 0, 1];
+        let (q, r) = div_rem(&a, &b);
+        assert_eq!(add(&mul(&q, &b), &r), a.to_vec());
+        assert_eq!(cmp(&r, &b), Ordering::Less);
+    }
+}
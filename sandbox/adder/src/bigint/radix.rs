@@ -0,0 +1,82 @@
+// ====================================================================
+// 文字列との変換（2〜36 進数）
+// ====================================================================
+//
+// 1桁ずつ掛け算すると遅いので、u64 に収まるだけの桁（10進なら19桁）をまとめて扱う
+//   読む:   値 = 値 · radix^k + (k 桁分の数)
+//   書く:   値を radix^k で割った余りを k 桁（0 埋め）で書く、を繰り返す
+
+use super::magnitude;
+
+// radix^k が u64 に収まる最大の k と radix^k
+fn chunk(radix: u32) -> (usize, u64) {
+    let radix = u64::from(radix);
+    let mut digits = 1;
+    let mut power = radix;
+    while let Some(next) = power.checked_mul(radix) {
+        power = next;
+        digits += 1;
+    }
+    (digits, power)
+}
+
+// 符号・接頭辞を除いた数字の並び → 絶対値。数字でない文字があればその位置（バイト）を返す
+pub fn parse(digits: &str, radix: u32) -> Result<Vec<u64>, usize> {
+    let (size, _) = chunk(radix);
+    let mut value = Vec::new();
+    let bytes = digits.as_bytes();
+    let first = bytes.len() % size;
+    let mut start = 0;
+    let mut end = if first == 0 { size } else { first };
+    while start < bytes.len() {
+        let mut part = 0u64;
+        for (i, &b) in bytes[start..end].iter().enumerate() {
+            let digit = char::from(b).to_digit(radix).ok_or(start + i)?;
+            part = part * u64::from(radix) + u64::from(digit);
+        }
+        // 最初のかたまりは短いことがある → その桁数ぶんだけずらす
+        let factor = u64::from(radix).pow((end - start) as u32);
+        magnitude::mul_small_add(&mut value, factor, part);
+        start = end;
+        end += size;
+    }
+    Ok(magnitude::normalize(value))
+}
+
+pub fn format(value: &[u64], radix: u32, upper: bool) -> String {
+    if value.is_empty() {
+        return "0".to_string();
+    }
+    let (size, power) = chunk(radix);
+    let mut parts = Vec::new();
+    let mut rest = value.to_vec();
+    while !rest.is_empty() {
+        let (q, r) = magnitude::div_rem_small(&rest, power);
+        parts.push(r);
+        rest = q;
+    }
+
+    let mut out = String::with_capacity(parts.len() * size);
+    for (i, &part) in parts.iter().rev().enumerate() {
+        let digits = to_digits(part, radix, upper);
+        // 最上位のかたまり以外は 0 埋めする
+        if i > 0 {
+            out.extend(std::iter::repeat_n('0', size - digits.len()));
+        }
+        out.push_str(&digits);
+    }
+    out
+}
+
+fn to_digits(mut n: u64, radix: u32, upper: bool) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    let mut digits = Vec::new();
+    while n > 0 {
+        let d = char::from_digit((n % u64::from(radix)) as u32, radix).unwrap();
+        digits.push(if upper { d.to_ascii_uppercase() } else { d });
+        n /= u64::from(radix);
+    }
+    digits.iter().rev().collect()
+}
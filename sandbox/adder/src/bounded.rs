@@ -0,0 +1,422 @@
+// ====================================================================
+// 範囲つき整数 — Bounded<T, MIN, MAX>
+// ====================================================================
+//
+// 9.3 の Guess は「1〜100 の i32」を new の中の if で守っていた
+// 範囲を const ジェネリクスにすれば、同じ仕組みをどんな整数・どんな範囲にも使える
+//
+//   Bounded<u8, 0, 10>          — 0〜10 の u8
+//   Bounded<i32, 1, 100, L>     — ラベル L はエラーメッセージの主語（省略すると「値」）
+//   type Guess = Bounded<i32, 1, 100, GuessValue>  — lib.rs の Guess はこの別名
+//
+// 作り方:
+//   try_new(v)  — 範囲外なら Err(RangeError)
+//   new(v)      — 範囲外なら panic!（メッセージは RangeError の Display と同じ）
+//   "42".parse() — 数字でなければ ParseBoundedError::Invalid、範囲外なら OutOfRange
+//
+// 計算（checked_add / checked_sub / checked_mul）は結果が範囲外・T のオーバーフローなら None
+//   → Bounded が存在する時点で値は必ず MIN〜MAX
+//
+// MIN・MAX の型について:
+//   const ジェネリクスの型に T は使えない（const MIN: T とは書けない）ので i128 で受け取る
+//   MIN > MAX や、T に収まらない MIN・MAX はコンパイル時（使ったところの単相化）にエラーになる
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+// ====================================================================
+// 整数型の抽象化
+// ====================================================================
+
+// Bounded の中身に使える整数型（i8〜i128, u8〜u128, isize, usize）
+// arithmetic の checked_add / saturating_add / wrapping_add もこの trait で書く
+pub trait Integer: Copy + Ord + fmt::Debug + fmt::Display + FromStr<Err = ParseIntError> {
+    const MIN: Self;
+    const MAX: Self;
+    const ZERO: Self;
+    // T の最小値・最大値を i128 で表したもの（u128::MAX は i128::MAX に切り詰める）
+    const LOWEST: i128;
+    const HIGHEST: i128;
+
+    // i128 に収まらない（u128 の大きな値）なら None
+    fn to_i128(self) -> Option<i128>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    // 結果と「あふれたか」の組
+    fn overflowing_add(self, rhs: Self) -> (Self, bool);
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+                const ZERO: Self = 0;
+                const LOWEST: i128 = <$t>::MIN as i128;
+                const HIGHEST: i128 = if <$t>::MAX as u128 > i128::MAX as u128 {
+                    i128::MAX
+                } else {
+                    <$t>::MAX as i128
+                };
+
+                fn to_i128(self) -> Option<i128> {
+                    i128::try_from(self).ok()
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+                    <$t>::overflowing_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+// ====================================================================
+// ラベル — エラーメッセージで何の値かを示す
+// ====================================================================
+
+pub trait Label {
+    const NAME: &'static str;
+}
+
+// 既定のラベル（値を作らないので中身のない enum）
+pub enum Value {}
+
+impl Label for Value {
+    const NAME: &'static str = "値";
+}
+
+// ====================================================================
+// エラー
+// ====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError<T> {
+    TooSmall {
+        value: T,
+        min: i128,
+        name: &'static str,
+    },
+    TooLarge {
+        value: T,
+        max: i128,
+        name: &'static str,
+    },
+}
+
+impl<T> RangeError<T> {
+    // 範囲外だった値
+    pub fn value(&self) -> &T {
+        match self {
+            RangeError::TooSmall { value, .. } | RangeError::TooLarge { value, .. } => value,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for RangeError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::TooSmall { value, min, name } => {
+                write!(
+                    f,
+                    "{name}は{min}以上でなければなりませんが、{value}でした。"
+                )
+            }
+            RangeError::TooLarge { value, max, name } => {
+                write!(
+                    f,
+                    "{name}は{max}以下でなければなりませんが、{value}でした。"
+                )
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for RangeError<T> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoundedError<T> {
+    Invalid(ParseIntError), // 整数として読めない
+    OutOfRange(RangeError<T>),
+}
+
+impl<T: fmt::Display> fmt::Display for ParseBoundedError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoundedError::Invalid(e) => write!(f, "整数として読めません: {e}"),
+            ParseBoundedError::OutOfRange(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for ParseBoundedError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseBoundedError::Invalid(e) => Some(e),
+            ParseBoundedError::OutOfRange(_) => None,
+        }
+    }
+}
+
+// ====================================================================
+// Bounded 本体
+// ====================================================================
+
+// フィールドは非公開 → try_new / new / parse を通さないと作れない
+//   PhantomData<fn() -> L> は L を持たない印（L が Send でなくても Bounded は Send のまま）
+pub struct Bounded<T, const MIN: i128, const MAX: i128, L = Value> {
+    value: T,
+    label: PhantomData<fn() -> L>,
+}
+
+impl<T: Integer, const MIN: i128, const MAX: i128, L: Label> Bounded<T, MIN, MAX, L> {
+    // 範囲の正しさをコンパイル時に確かめる（参照されたときに評価される）
+    const VALID_RANGE: () = assert!(
+        T::LOWEST <= MIN && MIN <= MAX && MAX <= T::HIGHEST,
+        "Bounded の範囲は MIN <= MAX で、どちらも T に収まらなければなりません"
+    );
+
+    pub fn try_new(value: T) -> Result<Self, RangeError<T>> {
+        let () = Self::VALID_RANGE;
+        match value.to_i128() {
+            Some(v) if v < MIN => Err(RangeError::TooSmall {
+                value,
+                min: MIN,
+                name: L::NAME,
+            }),
+            // i128 に収まらない u128 は MAX より必ず大きい
+            Some(v) if v <= MAX => Ok(Bounded {
+                value,
+                label: PhantomData,
+            }),
+            _ => Err(RangeError::TooLarge {
+                value,
+                max: MAX,
+                name: L::NAME,
+            }),
+        }
+    }
+
+    pub fn new(value: T) -> Self {
+        match Self::try_new(value) {
+            Ok(bounded) => bounded,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    // ゲッター（フィールドが非公開なので、これが唯一のアクセス手段）
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    // 計算結果が T であふれるか、範囲外になったら None
+    pub fn checked_add(self, rhs: T) -> Option<Self> {
+        Self::try_new(self.value.checked_add(rhs)?).ok()
+    }
+
+    pub fn checked_sub(self, rhs: T) -> Option<Self> {
+        Self::try_new(self.value.checked_sub(rhs)?).ok()
+    }
+
+    pub fn checked_mul(self, rhs: T) -> Option<Self> {
+        Self::try_new(self.value.checked_mul(rhs)?).ok()
+    }
+}
+
+impl<T: Integer, const MIN: i128, const MAX: i128, L: Label> FromStr for Bounded<T, MIN, MAX, L> {
+    type Err = ParseBoundedError<T>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.parse::<T>().map_err(ParseBoundedError::Invalid)?;
+        Self::try_new(value).map_err(ParseBoundedError::OutOfRange)
+    }
+}
+
+impl<T: fmt::Display, const MIN: i128, const MAX: i128, L> fmt::Display
+    for Bounded<T, MIN, MAX, L>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+// --------------------------------------------------------------------
+// 標準トレイト — derive だと L にも Clone などを要求してしまうので手で書く
+// --------------------------------------------------------------------
+
+impl<T: fmt::Debug, const MIN: i128, const MAX: i128, L> fmt::Debug for Bounded<T, MIN, MAX, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bounded")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T: Clone, const MIN: i128, const MAX: i128, L> Clone for Bounded<T, MIN, MAX, L> {
+    fn clone(&self) -> Self {
+        Bounded {
+            value: self.value.clone(),
+            label: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, const MIN: i128, const MAX: i128, L> Copy for Bounded<T, MIN, MAX, L> {}
+
+impl<T: PartialEq, const MIN: i128, const MAX: i128, L> PartialEq for Bounded<T, MIN, MAX, L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, const MIN: i128, const MAX: i128, L> Eq for Bounded<T, MIN, MAX, L> {}
+
+impl<T: PartialOrd, const MIN: i128, const MAX: i128, L> PartialOrd for Bounded<T, MIN, MAX, L> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord, const MIN: i128, const MAX: i128, L> Ord for Bounded<T, MIN, MAX, L> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash, const MIN: i128, const MAX: i128, L> Hash for Bounded<T, MIN, MAX, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Small = Bounded<i8, -5, 5>;
+
+    #[test]
+    fn try_new_accepts_exactly_the_range() {
+        // i8 の全部の値で確かめる
+        for v in i8::MIN..=i8::MAX {
+            let result = Small::try_new(v);
+            if (-5..=5).contains(&v) {
+                assert_eq!(result.unwrap().value(), v);
+            } else if v < -5 {
+                assert_eq!(
+                    result,
+                    Err(RangeError::TooSmall {
+                        value: v,
+                        min: -5,
+                        name: "値"
+                    })
+                );
+            } else {
+                assert!(matches!(result, Err(RangeError::TooLarge { max: 5, .. })));
+            }
+        }
+    }
+
+    #[test]
+    fn errors_name_the_label() {
+        enum Percent {}
+        impl Label for Percent {
+            const NAME: &'static str = "割合";
+        }
+        let err = Bounded::<u8, 0, 100, Percent>::try_new(101).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "割合は100以下でなければなりませんが、101でした。"
+        );
+        assert_eq!(*err.value(), 101);
+    }
+
+    #[test]
+    #[should_panic(expected = "値は-5以上でなければなりませんが、-6でした。")]
+    fn new_panics_with_the_range_error() {
+        Small::new(-6);
+    }
+
+    #[test]
+    fn checked_arithmetic_stays_in_range() {
+        let three = Small::new(3);
+        assert_eq!(three.checked_add(2), Some(Small::new(5)));
+        assert_eq!(three.checked_add(3), None);
+        assert_eq!(three.checked_sub(8), Some(Small::new(-5)));
+        assert_eq!(three.checked_sub(9), None);
+        assert_eq!(three.checked_mul(-1), Some(Small::new(-3)));
+        assert_eq!(three.checked_mul(2), None);
+        // T 自体のオーバーフローも None（パニックしない）
+        assert_eq!(three.checked_mul(i8::MAX), None);
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        let parsed: Small = "-4".parse().unwrap();
+        assert_eq!(parsed.to_string(), "-4");
+        assert_eq!(format!("{parsed:?}"), "Bounded { value: -4 }");
+
+        assert!(matches!(
+            "abc".parse::<Small>(),
+            Err(ParseBoundedError::Invalid(_))
+        ));
+        // i8 に収まらない数字は範囲外ではなく Invalid（T として読めない）
+        assert!(matches!(
+            "300".parse::<Small>(),
+            Err(ParseBoundedError::Invalid(_))
+        ));
+        let err = "6".parse::<Small>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "値は5以下でなければなりませんが、6でした。"
+        );
+    }
+
+    #[test]
+    fn full_width_types_at_their_edges() {
+        // u128 の i128 に収まらない値も TooLarge になる
+        type Wide = Bounded<u128, 0, { i128::MAX }>;
+        assert!(Wide::try_new(i128::MAX as u128).is_ok());
+        assert!(matches!(
+            Wide::try_new(u128::MAX),
+            Err(RangeError::TooLarge { .. })
+        ));
+
+        type Whole = Bounded<u8, 0, 255>;
+        for v in 0..=u8::MAX {
+            assert_eq!(Whole::new(v).value(), v);
+        }
+        assert_eq!(Whole::new(255).checked_add(1), None);
+    }
+
+    #[test]
+    fn ordering_follows_the_value() {
+        let mut values: Vec<Small> = [3, -2, 5, 0].into_iter().map(Small::new).collect();
+        values.sort();
+        let sorted: Vec<i8> = values.iter().map(Small::value).collect();
+        assert_eq!(sorted, [-2, 0, 3, 5]);
+    }
+}
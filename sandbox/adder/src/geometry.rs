@@ -0,0 +1,329 @@
+// ====================================================================
+// 幾何モジュール — Rectangle を配置つきの長方形に広げる
+// ====================================================================
+//
+// 11章の Rectangle は「幅と高さ」だけで、can_hold も両辺が厳密に大きいかしか見ない
+// レイアウト計算には「どこにあるか」が必要なので、位置つきの Rect を用意する
+//
+//   Point     — 座標 (x, y)。左上が原点、右・下に向かって増える
+//   Rect      — 位置 (x, y) + 大きさ (width, height)
+//               範囲は半開区間 [x, x + width) × [y, y + height)
+//               → 辺が接しているだけの2つの Rect は重ならない
+//   Rotation  — 90° 回転を許すかどうか
+//
+// オーバーフロー対策（u32）:
+//   Rect は「右端 x + width・下端 y + height が u32 に収まる」ものしか作れない（Rect::new が None を返す）
+//   → right() / bottom() / union_bounds はオーバーフローしない
+//   → 面積は u32 × u32 なので u64 で返す
+
+use crate::Rectangle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Fixed,   // そのままの向きだけ
+    Allowed, // 90° 回してもよい
+}
+
+// ====================================================================
+// Rectangle（大きさだけ）への追加
+// ====================================================================
+
+impl Rectangle {
+    pub fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    // 90° 回転 = 幅と高さの入れ替え
+    pub fn rotated(&self) -> Rectangle {
+        Rectangle {
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    // can_hold と同じく両辺が厳密に大きいか（Rotation::Allowed なら other を回してもよい）
+    pub fn can_hold_with(&self, other: &Rectangle, rotation: Rotation) -> bool {
+        self.can_hold(other) || (rotation == Rotation::Allowed && self.can_hold(&other.rotated()))
+    }
+
+    // 配置用: other がぴったりでも入るなら、入る向きの other を返す（回さずに入るならそのまま）
+    pub fn fits(&self, other: &Rectangle, rotation: Rotation) -> Option<Rectangle> {
+        let fits = |r: &Rectangle| r.width <= self.width && r.height <= self.height;
+        if fits(other) {
+            Some(*other)
+        } else if rotation == Rotation::Allowed && fits(&other.rotated()) {
+            Some(other.rotated())
+        } else {
+            None
+        }
+    }
+}
+
+// ====================================================================
+// Rect（位置つきの長方形）
+// ====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    // 右端・下端が u32 を超えるなら None
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Option<Rect> {
+        x.checked_add(width)?;
+        y.checked_add(height)?;
+        Some(Rect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    pub fn at(origin: Point, size: &Rectangle) -> Option<Rect> {
+        Rect::new(origin.x, origin.y, size.width, size.height)
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // new で確かめているので足し算はあふれない
+    pub fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    pub fn origin(&self) -> Point {
+        Point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    pub fn size(&self) -> Rectangle {
+        Rectangle {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn area(&self) -> u64 {
+        self.size().area()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // 半開区間なので、右端・下端ちょうどの点は含まない
+    pub fn contains_point(&self, point: Point) -> bool {
+        (self.x..self.right()).contains(&point.x) && (self.y..self.bottom()).contains(&point.y)
+    }
+
+    // other がすっぽり入っているか（空の Rect は位置が範囲内なら入っているとみなす）
+    pub fn contains(&self, other: &Rect) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    // 面積が正の部分を共有しているか（辺が接しているだけなら false）
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if x < right && y < bottom {
+            Rect::new(x, y, right - x, bottom - y)
+        } else {
+            None
+        }
+    }
+
+    // 両方を囲む最小の Rect
+    // 右端は両方とも u32 に収まるので、その最大値 - 最小の x も u32 に収まる
+    pub fn union_bounds(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::prop::Rng;
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> Rect {
+        Rect::new(x, y, width, height).unwrap()
+    }
+
+    #[test]
+    fn rectangle_area_and_rotation() {
+        let tall = Rectangle {
+            width: 2,
+            height: 9,
+        };
+        let wide = Rectangle {
+            width: 10,
+            height: 3,
+        };
+        assert_eq!(tall.area(), 18);
+        assert!(!wide.can_hold(&tall));
+        assert!(wide.can_hold_with(&tall, Rotation::Allowed));
+        assert!(!wide.can_hold_with(&tall, Rotation::Fixed));
+
+        // fits は境界ぴったりも許す
+        let exact = Rectangle {
+            width: 3,
+            height: 10,
+        };
+        assert!(wide.fits(&exact, Rotation::Fixed).is_none());
+        let placed = wide.fits(&exact, Rotation::Allowed).unwrap();
+        assert_eq!(placed, wide);
+
+        let max = Rectangle {
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        assert_eq!(max.area(), u64::from(u32::MAX) * u64::from(u32::MAX));
+    }
+
+    #[test]
+    fn rect_rejects_overflowing_edges() {
+        assert!(Rect::new(u32::MAX, 0, 1, 1).is_none());
+        assert!(Rect::new(0, u32::MAX - 1, 1, 2).is_none());
+        let edge = rect(u32::MAX - 1, 0, 1, 1);
+        assert_eq!(edge.right(), u32::MAX);
+    }
+
+    #[test]
+    fn points_and_overlaps() {
+        let a = rect(0, 0, 10, 10);
+        let b = rect(5, 5, 10, 10);
+        let touching = rect(10, 0, 5, 5);
+
+        assert!(a.contains_point(Point { x: 0, y: 9 }));
+        assert!(!a.contains_point(Point { x: 10, y: 0 }));
+
+        assert_eq!(a.intersection(&b), Some(rect(5, 5, 5, 5)));
+        assert!(!a.intersects(&touching));
+        assert_eq!(a.union_bounds(&b), rect(0, 0, 15, 15));
+        assert!(a.union_bounds(&b).contains(&a));
+    }
+
+    // ----------------------------------------------------------------
+    // 代数的な性質をランダムな Rect で確かめる（プロパティテスト）
+    // ----------------------------------------------------------------
+
+    // 乱数は test_support の xorshift — シード固定なので失敗しても同じ入力で再現できる
+
+    // 小さい座標（重なりやすい）と u32::MAX 付近（オーバーフローしやすい）を混ぜる
+    fn random_coord(rng: &mut Rng) -> u32 {
+        match rng.next_u32() % 3 {
+            0 => rng.next_u32() % 32,
+            1 => u32::MAX - rng.next_u32() % 32,
+            _ => rng.next_u32(),
+        }
+    }
+
+    fn random_rect(rng: &mut Rng) -> Rect {
+        let x = random_coord(rng);
+        let y = random_coord(rng);
+        let width = rng.next_u32() % (u32::MAX - x).min(40).saturating_add(1);
+        let height = rng.next_u32() % (u32::MAX - y).min(40).saturating_add(1);
+        rect(x, y, width, height)
+    }
+
+    fn random_point_near(rng: &mut Rng, r: &Rect) -> Point {
+        Point {
+            x: r.x().saturating_add(rng.next_u32() % 50).saturating_sub(5),
+            y: r.y().saturating_add(rng.next_u32() % 50).saturating_sub(5),
+        }
+    }
+
+    #[test]
+    fn algebraic_laws_hold() {
+        let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..10_000 {
+            let (a, b, c) = (
+                random_rect(&mut rng),
+                random_rect(&mut rng),
+                random_rect(&mut rng),
+            );
+
+            // 交わり: 可換・冪等、結果はどちらにも含まれる
+            assert_eq!(a.intersection(&b), b.intersection(&a));
+            assert_eq!(a.intersects(&b), a.intersection(&b).is_some());
+            if !a.is_empty() {
+                assert_eq!(a.intersection(&a), Some(a));
+            }
+            if let Some(i) = a.intersection(&b) {
+                assert!(a.contains(&i) && b.contains(&i));
+                assert!(i.area() <= a.area().min(b.area()));
+            }
+
+            // 外接: 可換・結合的・冪等、両方を含む
+            assert_eq!(a.union_bounds(&b), b.union_bounds(&a));
+            assert_eq!(
+                a.union_bounds(&b).union_bounds(&c),
+                a.union_bounds(&b.union_bounds(&c))
+            );
+            assert_eq!(a.union_bounds(&a), a);
+            let u = a.union_bounds(&b);
+            assert!(u.contains(&a) && u.contains(&b));
+
+            // 点が交わりに入る ⇔ 両方に入る
+            let p = random_point_near(&mut rng, &a);
+            let in_both = a.contains_point(p) && b.contains_point(p);
+            assert_eq!(
+                a.intersection(&b).is_some_and(|i| i.contains_point(p)),
+                in_both
+            );
+            assert!(!a.contains_point(p) || u.contains_point(p));
+
+            // 回転しても面積は同じ、2回回すと元に戻る
+            let s = a.size();
+            assert_eq!(s.rotated().area(), s.area());
+            assert_eq!(s.rotated().rotated(), s);
+        }
+    }
+}
@@ -1,10 +1,29 @@
 // ====================================================================
 // 11章: テストの記述（学習用ライブラリ）
 // ====================================================================
+//
+// 拡張:
+//   - geometry: 位置つきの長方形（Rect）と Rectangle の面積・回転
+//   - packing: Rectangle をシートに詰めるビンパッキング（benches/packing.rs で比較）
+//   - arithmetic: オーバーフローの扱いを選べる足し算（checked / saturating / wrapping）と任意長の足し算
+//   - bigint: 任意精度の整数 BigInt（u64・u128 を超える合計や積）
+//   - bounded: 範囲つき整数 Bounded<T, MIN, MAX>（Guess はその別名）
+//   - locale: ロケールに応じたあいさつ（locales/*.txt のメッセージカタログ）
+//
+// テストの書き方は下の 11.1〜11.3 のコメントと tests/ を参照
+
+pub mod arithmetic;
+pub mod bigint;
+pub mod bounded;
+pub mod geometry;
+pub mod locale;
+pub mod packing;
+
+use bounded::{Bounded, Label};
 
 // --- テスト対象のコード ---
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rectangle {
     pub width: u32,
     pub height: u32,
@@ -16,6 +35,7 @@ impl Rectangle {
     }
 }
 
+// あふれると debug では panic、release では折り返す → 明示したいなら arithmetic::checked_add など
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -24,30 +44,19 @@ pub fn add_two(a: i32) -> i32 {
     a + 2
 }
 
+// 環境変数（LANG など）のロケールであいさつする。言語や敬称を選ぶなら locale::Greeter
 pub fn greeting(name: &str) -> String {
-    format!("Hello {}!", name)
+    locale::Greeter::from_env().greeting(name)
 }
 
-pub struct Guess {
-    #[allow(dead_code)]
-    value: i32,
-}
+// 1〜100 の予想値（範囲外なら new が panic、try_new なら Err）
+pub type Guess = Bounded<i32, 1, 100, GuessValue>;
 
-impl Guess {
-    pub fn new(value: i32) -> Guess {
-        if value < 1 {
-            panic!(
-                "予想値は1以上でなければなりませんが、{}でした。",
-                value
-            );
-        } else if value > 100 {
-            panic!(
-                "予想値は100以下でなければなりませんが、{}でした。",
-                value
-            );
-        }
-        Guess { value }
-    }
+// Guess のエラーメッセージの主語
+pub enum GuessValue {}
+
+impl Label for GuessValue {
+    const NAME: &'static str = "予想値";
 }
 
 // 非公開関数 — ユニットテストからはテストできる（11.3）
@@ -0,0 +1,368 @@
+// ====================================================================
+// ロケールに応じたあいさつ — メッセージカタログ
+// ====================================================================
+//
+// greeting は "Hello {}!" 固定だったので、言語ごとの文をファイルに分けて持つ
+//
+//   locales/en.txt, locales/ja.txt — 「キー = テンプレート」の行を並べたファイル
+//     greeting = こんにちは、{name}！
+//     name.san = {name}さん
+//   → include_str! でバイナリに埋め込む（Catalog::builtin）。Catalog::add で実行時に足すこともできる
+//
+// 敬称（Honorific）:
+//   名前はまず name.plain / name.san / name.sama のテンプレートで包んでから文に入れる
+//   英語には「さん」にあたるものがないので、en では3つとも名前だけ
+//   すでに「田中さん」のように敬称が付いていれば二重には付けない
+//
+// フォールバック:
+//   "ja-JP" → "ja" → "en" の順に探し、最初に見つかったテンプレートを使う
+//   → 地域別のファイルは違うところだけ書けばよく、知らない言語なら英語になる
+//
+// 既定のロケールは環境変数から決める（POSIX と同じく LC_ALL > LC_MESSAGES > LANG）
+//   "ja_JP.UTF-8" → "ja-JP"、"C" / "POSIX" / 未設定 → "en"
+
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+const BUILTIN: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.txt")),
+    ("ja", include_str!("../locales/ja.txt")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Honorific {
+    #[default]
+    Plain, // 名前だけ
+    San,  // さん
+    Sama, // 様
+}
+
+impl Honorific {
+    fn key(self) -> &'static str {
+        match self {
+            Honorific::Plain => "name.plain",
+            Honorific::San => "name.san",
+            Honorific::Sama => "name.sama",
+        }
+    }
+}
+
+// ====================================================================
+// カタログ
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogError {
+    pub locale: String,
+    pub line: usize, // 1 始まり
+    pub message: String,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.locale, self.line, self.message)
+    }
+}
+
+impl Error for CatalogError {}
+
+// ロケール → (キー → テンプレート)
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    // locales/ の組み込みファイル（壊れていたらテストで分かるので expect）
+    pub fn builtin() -> Catalog {
+        let mut catalog = Catalog::default();
+        for (locale, source) in BUILTIN {
+            catalog
+                .add(locale, source)
+                .expect("built-in locale files must be valid");
+        }
+        catalog
+    }
+
+    // source を読んで locale に足す。すでにあるキーは上書きする
+    pub fn add(&mut self, locale: &str, source: &str) -> Result<(), CatalogError> {
+        let locale = normalize(locale);
+        let parsed = parse(&locale, source)?;
+        self.messages.entry(locale).or_default().extend(parsed);
+        Ok(())
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.messages.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    pub fn keys(&self, locale: &str) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .messages
+            .get(&normalize(locale))
+            .map(|m| m.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        keys.sort_unstable();
+        keys
+    }
+
+    // フォールバックせずに locale だけを見る
+    pub fn get(&self, locale: &str, key: &str) -> Option<&str> {
+        self.messages
+            .get(&normalize(locale))?
+            .get(key)
+            .map(String::as_str)
+    }
+}
+
+fn parse(locale: &str, source: &str) -> Result<HashMap<String, String>, CatalogError> {
+    let mut messages = HashMap::new();
+    for (i, line) in source.lines().enumerate() {
+        let error = |message: String| CatalogError {
+            locale: locale.to_string(),
+            line: i + 1,
+            message,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, template)) = line.split_once('=') else {
+            return Err(error(format!("expected `key = template`, got `{line}`")));
+        };
+        let (key, template) = (key.trim(), template.trim());
+        if key.is_empty() {
+            return Err(error("empty key".to_string()));
+        }
+        // 使える置き換えは {name} だけ
+        if template.replace("{name}", "").contains(['{', '}']) {
+            return Err(error(format!("unknown placeholder in `{template}`")));
+        }
+        if messages
+            .insert(key.to_string(), template.to_string())
+            .is_some()
+        {
+            return Err(error(format!("duplicate key `{key}`")));
+        }
+    }
+    Ok(messages)
+}
+
+// ====================================================================
+// ロケール名
+// ====================================================================
+
+// "ja_JP.UTF-8@euro" → "ja-JP"（言語は小文字、地域は大文字）
+fn normalize(locale: &str) -> String {
+    let tag = locale.split(['.', '@']).next().unwrap_or("");
+    let mut parts = tag.split(['_', '-']).filter(|p| !p.is_empty());
+    let language = parts.next().unwrap_or("").to_ascii_lowercase();
+    match parts.next() {
+        Some(region) => format!("{language}-{}", region.to_ascii_uppercase()),
+        None => language,
+    }
+}
+
+// "ja-JP" → ["ja-JP", "ja", "en"]
+pub fn fallback_chain(locale: &str) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut tag = normalize(locale);
+    while !tag.is_empty() {
+        chain.push(tag.clone());
+        match tag.rfind('-') {
+            Some(i) => tag.truncate(i),
+            None => break,
+        }
+    }
+    if !chain.iter().any(|l| l == DEFAULT_LOCALE) {
+        chain.push(DEFAULT_LOCALE.to_string());
+    }
+    chain
+}
+
+pub fn locale_from_env() -> String {
+    let var = |name| env::var(name).ok();
+    pick_locale(&[var("LC_ALL"), var("LC_MESSAGES"), var("LANG")])
+}
+
+// 空でない最初の値。"C" / "POSIX" は「ロケールなし」なので既定値にする
+fn pick_locale(candidates: &[Option<String>]) -> String {
+    match candidates.iter().flatten().find(|v| !v.is_empty()) {
+        Some(v) if v != "C" && v != "POSIX" && !v.starts_with("C.") => normalize(v),
+        _ => DEFAULT_LOCALE.to_string(),
+    }
+}
+
+// ====================================================================
+// Greeter
+// ====================================================================
+
+#[derive(Debug, Clone)]
+pub struct Greeter {
+    catalog: Catalog,
+    chain: Vec<String>,
+    honorific: Honorific,
+}
+
+impl Greeter {
+    pub fn new(locale: &str) -> Greeter {
+        Greeter::with_catalog(Catalog::builtin(), locale)
+    }
+
+    pub fn from_env() -> Greeter {
+        Greeter::new(&locale_from_env())
+    }
+
+    pub fn with_catalog(catalog: Catalog, locale: &str) -> Greeter {
+        Greeter {
+            catalog,
+            chain: fallback_chain(locale),
+            honorific: Honorific::default(),
+        }
+    }
+
+    pub fn honorific(mut self, honorific: Honorific) -> Greeter {
+        self.honorific = honorific;
+        self
+    }
+
+    // 実際に使われるロケール（フォールバックの中でカタログにある最初のもの）
+    pub fn locale(&self) -> &str {
+        self.chain
+            .iter()
+            .find(|l| self.catalog.messages.contains_key(*l))
+            .map_or(DEFAULT_LOCALE, String::as_str)
+    }
+
+    pub fn greeting(&self, name: &str) -> String {
+        self.render("greeting", name)
+    }
+
+    pub fn farewell(&self, name: &str) -> String {
+        self.render("farewell", name)
+    }
+
+    // どのロケールにもなければキーそのもの（訳し忘れが画面で分かる）
+    fn template(&self, key: &'static str) -> &str {
+        self.chain
+            .iter()
+            .find_map(|locale| self.catalog.get(locale, key))
+            .unwrap_or(key)
+    }
+
+    fn render(&self, key: &'static str, name: &str) -> String {
+        let name = self.with_honorific(name);
+        self.template(key).replace("{name}", &name)
+    }
+
+    fn with_honorific(&self, name: &str) -> String {
+        let template = self.template(self.honorific.key());
+        // テンプレートが "{name}さん" なら、"さん" で終わる名前にはもう付けない
+        if let Some(suffix) = template.strip_prefix("{name}")
+            && !suffix.is_empty()
+            && name.ends_with(suffix)
+        {
+            return name.to_string();
+        }
+        template.replace("{name}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_has_every_key() {
+        let catalog = Catalog::builtin();
+        let reference = catalog.keys(DEFAULT_LOCALE);
+        assert!(!reference.is_empty());
+        for locale in catalog.locales() {
+            assert_eq!(catalog.keys(locale), reference, "locale {locale}");
+        }
+        // Greeter が使うキーが全部ある
+        for key in ["greeting", "farewell"]
+            .into_iter()
+            .chain([Honorific::Plain, Honorific::San, Honorific::Sama].map(Honorific::key))
+        {
+            assert!(reference.contains(&key), "missing {key}");
+        }
+    }
+
+    #[test]
+    fn greets_in_each_language() {
+        assert_eq!(Greeter::new("en").greeting("Carol"), "Hello Carol!");
+        assert_eq!(Greeter::new("ja").greeting("Carol"), "こんにちは、Carol！");
+        assert_eq!(Greeter::new("ja").farewell("太郎"), "さようなら、太郎！");
+    }
+
+    #[test]
+    fn honorifics() {
+        let ja = Greeter::new("ja_JP.UTF-8").honorific(Honorific::San);
+        assert_eq!(ja.greeting("田中"), "こんにちは、田中さん！");
+        // 二重に付けない
+        assert_eq!(ja.greeting("田中さん"), "こんにちは、田中さん！");
+        let sama = Greeter::new("ja").honorific(Honorific::Sama);
+        assert_eq!(sama.farewell("佐藤"), "さようなら、佐藤様！");
+        // 英語では名前だけ
+        let en = Greeter::new("en").honorific(Honorific::San);
+        assert_eq!(en.greeting("Tanaka"), "Hello Tanaka!");
+    }
+
+    #[test]
+    fn falls_back_through_the_chain() {
+        assert_eq!(fallback_chain("ja_JP.UTF-8"), ["ja-JP", "ja", "en"]);
+        assert_eq!(fallback_chain("en-GB"), ["en-GB", "en"]);
+        assert_eq!(fallback_chain(""), ["en"]);
+
+        assert_eq!(Greeter::new("ja-JP").locale(), "ja");
+        assert_eq!(Greeter::new("fr").locale(), "en");
+        assert_eq!(Greeter::new("fr").greeting("Amélie"), "Hello Amélie!");
+
+        // 地域別のファイルは違うキーだけ書けばよい
+        let mut catalog = Catalog::builtin();
+        catalog.add("ja-KS", "greeting = まいど、{name}！").unwrap();
+        let kansai = Greeter::with_catalog(catalog, "ja_KS");
+        assert_eq!(kansai.locale(), "ja-KS");
+        assert_eq!(kansai.greeting("太郎"), "まいど、太郎！");
+        assert_eq!(kansai.farewell("太郎"), "さようなら、太郎！");
+
+        // どこにもないキーはキーそのもの
+        let empty = Greeter::with_catalog(Catalog::default(), "ja");
+        assert_eq!(empty.greeting("x"), "greeting");
+    }
+
+    #[test]
+    fn locale_from_environment_values() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(pick_locale(&[None, None, some("ja_JP.UTF-8")]), "ja-JP");
+        assert_eq!(pick_locale(&[some("en_US"), None, some("ja_JP")]), "en-US");
+        assert_eq!(pick_locale(&[some(""), some("ja"), some("en")]), "ja");
+        assert_eq!(pick_locale(&[None, None, some("C.UTF-8")]), "en");
+        assert_eq!(pick_locale(&[None, None, some("POSIX")]), "en");
+        assert_eq!(pick_locale(&[None, None, None]), "en");
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let mut catalog = Catalog::default();
+        let err = catalog
+            .add("xx", "# ok\n\ngreeting = hi\nfarewell")
+            .unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(
+            err.to_string(),
+            "xx:4: expected `key = template`, got `farewell`"
+        );
+        assert!(catalog.add("xx", "a = 1\na = 2").is_err());
+        assert!(catalog.add("xx", "= 1").is_err());
+        assert!(catalog.add("xx", "greeting = hi {nmae}").is_err());
+        assert!(catalog.locales().is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+// ====================================================================
+// 2次元ビンパッキング — 小さい長方形をシートに詰める
+// ====================================================================
+//
+// ラベルやスプライトなど、たくさんの Rectangle を決まった大きさのシートに並べる
+//   pack(items, sheet, options) → 各 item の配置（何枚目のどこか）とシート枚数
+//
+// 戦略（Strategy）— 1枚のシートの中で「どこに置くか」の決め方:
+//   Shelf      — 棚（横一列）を上から積む。速いが隙間が多い
+//   Guillotine — 空き領域を「置いた残りを縦か横に一刀両断」して管理する
+//   MaxRects   — 空き領域を重なりを許した「極大の長方形」の集合で管理する。一番よく詰まる
+//
+// 共通の流れ:
+//   1. items を大きい順に並べる（同じ大きさなら元の順番）→ 結果が毎回同じになる
+//   2. 1つずつ、開いているシートを1枚目から順に試し、入らなければ新しいシートを開く
+//
+// Rotation::Allowed なら 90° 回して置いてもよい（Placement::rotated で分かる）
+//
+// 速さ（benches/packing.rs）: Shelf < Guillotine < MaxRects
+//   MaxRects は空き領域の整理が O(空き領域の数²) なので、小さい item が数千あると目に見えて遅い
+//   大小が混ざった入力では MaxRects・Guillotine の方がシート枚数が少なくなりやすい
+
+mod guillotine;
+mod maxrects;
+mod shelf;
+
+use std::error::Error;
+use std::fmt;
+
+use crate::Rectangle;
+use crate::geometry::{Rect, Rotation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Shelf,
+    Guillotine,
+    MaxRects,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Shelf, Strategy::Guillotine, Strategy::MaxRects];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackOptions {
+    pub strategy: Strategy,
+    pub rotation: Rotation,
+}
+
+impl Default for PackOptions {
+    fn default() -> PackOptions {
+        PackOptions {
+            strategy: Strategy::MaxRects,
+            rotation: Rotation::Fixed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub index: usize, // items の何番目か
+    pub sheet: usize, // 何枚目のシートか（0 始まり）
+    pub rect: Rect,   // シートの中の位置と、置いた向きの大きさ
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packing {
+    pub placements: Vec<Placement>, // items と同じ順番
+    pub sheets: usize,
+    pub sheet: Rectangle,
+}
+
+impl Packing {
+    // 使ったシートの面積のうち、item が占める割合（0.0〜1.0）
+    pub fn utilization(&self) -> f64 {
+        if self.sheets == 0 {
+            return 0.0;
+        }
+        let used: u64 = self.placements.iter().map(|p| p.rect.area()).sum();
+        used as f64 / (self.sheet.area() as f64 * self.sheets as f64)
+    }
+
+    pub fn sheet_utilization(&self, sheet: usize) -> f64 {
+        let used: u64 = self
+            .placements
+            .iter()
+            .filter(|p| p.sheet == sheet)
+            .map(|p| p.rect.area())
+            .sum();
+        used as f64 / self.sheet.area() as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackError {
+    EmptySheet,                 // シートの幅か高さが 0
+    EmptyItem { index: usize }, // item の幅か高さが 0
+    TooLarge { index: usize },  // どの向きでもシートに入らない
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::EmptySheet => write!(f, "sheet must have a non-zero width and height"),
+            PackError::EmptyItem { index } => {
+                write!(f, "item {index} must have a non-zero width and height")
+            }
+            PackError::TooLarge { index } => write!(f, "item {index} does not fit on a sheet"),
+        }
+    }
+}
+
+impl Error for PackError {}
+
+// 1枚のシートの空き領域を管理する
+//   insert は置けたら「置いた場所（向きを含む）」を返す
+trait Bin {
+    fn insert(&mut self, item: &Rectangle, rotation: Rotation) -> Option<Rect>;
+}
+
+fn new_bin(strategy: Strategy, sheet: &Rectangle) -> Box<dyn Bin> {
+    match strategy {
+        Strategy::Shelf => Box::new(shelf::ShelfBin::new(sheet)),
+        Strategy::Guillotine => Box::new(guillotine::GuillotineBin::new(sheet)),
+        Strategy::MaxRects => Box::new(maxrects::MaxRectsBin::new(sheet)),
+    }
+}
+
+// item を置ける向きの候補（回転してよく、正方形でなければ2通り）
+fn orientations(item: &Rectangle, rotation: Rotation) -> Vec<Rectangle> {
+    let mut candidates = vec![*item];
+    if rotation == Rotation::Allowed && item.width != item.height {
+        candidates.push(item.rotated());
+    }
+    candidates
+}
+
+pub fn pack(
+    items: &[Rectangle],
+    sheet: &Rectangle,
+    options: PackOptions,
+) -> Result<Packing, PackError> {
+    if sheet.width == 0 || sheet.height == 0 {
+        return Err(PackError::EmptySheet);
+    }
+    for (index, item) in items.iter().enumerate() {
+        if item.width == 0 || item.height == 0 {
+            return Err(PackError::EmptyItem { index });
+        }
+        if sheet.fits(item, options.rotation).is_none() {
+            return Err(PackError::TooLarge { index });
+        }
+    }
+
+    // 大きいものから置くと隙間が埋まりやすい（First Fit Decreasing）
+    //   回転できるなら長い辺、できないなら高さを優先して比べる
+    //   sort_by_key は安定ソートなので、同じ大きさなら元の順番 → 結果が決定的
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| {
+        let item = &items[i];
+        let key = match options.rotation {
+            Rotation::Allowed => (item.width.max(item.height), item.width.min(item.height)),
+            Rotation::Fixed => (item.height, item.width),
+        };
+        std::cmp::Reverse(key)
+    });
+
+    let mut bins: Vec<Box<dyn Bin>> = Vec::new();
+    let mut placements: Vec<Option<Placement>> = vec![None; items.len()];
+
+    for index in order {
+        let item = &items[index];
+        let placed = bins
+            .iter_mut()
+            .enumerate()
+            .find_map(|(sheet, bin)| Some((sheet, bin.insert(item, options.rotation)?)));
+        let (sheet_index, rect) = match placed {
+            Some(found) => found,
+            None => {
+                let mut bin = new_bin(options.strategy, sheet);
+                // 空のシートには必ず入る（TooLarge は最初に確かめた）
+                let rect = bin
+                    .insert(item, options.rotation)
+                    .expect("an item that fits the sheet must fit an empty bin");
+                bins.push(bin);
+                (bins.len() - 1, rect)
+            }
+        };
+        placements[index] = Some(Placement {
+            index,
+            sheet: sheet_index,
+            rect,
+            rotated: item.width != item.height && rect.width() != item.width,
+        });
+    }
+
+    Ok(Packing {
+        placements: placements.into_iter().map(Option::unwrap).collect(),
+        sheets: bins.len(),
+        sheet: *sheet,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::prop::Rng;
+
+    fn size(width: u32, height: u32) -> Rectangle {
+        Rectangle { width, height }
+    }
+
+    // 配置が正しいか: シートの中にあり、同じシートで重ならず、向きが合っている
+    fn assert_valid(items: &[Rectangle], packing: &Packing) {
+        let bounds = Rect::new(0, 0, packing.sheet.width, packing.sheet.height).unwrap();
+        assert_eq!(packing.placements.len(), items.len());
+        for (i, p) in packing.placements.iter().enumerate() {
+            assert_eq!(p.index, i);
+            assert!(p.sheet < packing.sheets);
+            assert!(bounds.contains(&p.rect), "{p:?} is outside the sheet");
+            let item = &items[i];
+            let expected = if p.rotated { item.rotated() } else { *item };
+            assert_eq!(p.rect.size(), expected);
+        }
+        for (i, a) in packing.placements.iter().enumerate() {
+            for b in &packing.placements[i + 1..] {
+                assert!(
+                    a.sheet != b.sheet || !a.rect.intersects(&b.rect),
+                    "{a:?} overlaps {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packs_exact_grid_on_one_sheet() {
+        let items: Vec<Rectangle> = (0..16).map(|_| size(25, 25)).collect();
+        for strategy in Strategy::ALL {
+            let options = PackOptions {
+                strategy,
+                rotation: Rotation::Fixed,
+            };
+            let packing = pack(&items, &size(100, 100), options).unwrap();
+            assert_valid(&items, &packing);
+            assert_eq!(packing.sheets, 1, "{strategy:?}");
+            assert!((packing.utilization() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn rotation_can_save_a_sheet() {
+        // 縦長の item は回さないと 10x4 のシートに入らない
+        let items = [size(10, 2), size(2, 10), size(10, 2)];
+        let sheet = size(10, 6);
+        for strategy in Strategy::ALL {
+            let fixed = PackOptions {
+                strategy,
+                rotation: Rotation::Fixed,
+            };
+            assert_eq!(
+                pack(&items, &size(10, 4), fixed),
+                Err(PackError::TooLarge { index: 1 })
+            );
+
+            let rotating = PackOptions {
+                strategy,
+                rotation: Rotation::Allowed,
+            };
+            let packing = pack(&items, &sheet, rotating).unwrap();
+            assert_valid(&items, &packing);
+            assert_eq!(packing.sheets, 1, "{strategy:?}");
+            assert!(packing.placements[1].rotated);
+        }
+    }
+
+    #[test]
+    fn overflow_opens_new_sheets() {
+        let items: Vec<Rectangle> = (0..5).map(|_| size(6, 6)).collect();
+        let packing = pack(&items, &size(10, 10), PackOptions::default()).unwrap();
+        assert_valid(&items, &packing);
+        assert_eq!(packing.sheets, 5);
+        assert!((packing.sheet_utilization(0) - 0.36).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let options = PackOptions::default();
+        assert_eq!(pack(&[], &size(0, 5), options), Err(PackError::EmptySheet));
+        assert_eq!(
+            pack(&[size(1, 1), size(0, 1)], &size(5, 5), options),
+            Err(PackError::EmptyItem { index: 1 })
+        );
+        assert_eq!(pack(&[], &size(5, 5), options).unwrap().sheets, 0);
+    }
+
+    // ----------------------------------------------------------------
+    // ランダムな入力で、どの戦略でも配置が正しく、毎回同じになることを確かめる
+    // ----------------------------------------------------------------
+
+    #[test]
+    fn random_packings_are_valid_and_deterministic() {
+        let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+        for round in 0..60 {
+            let sheet = size(40 + rng.next_u32() % 80, 40 + rng.next_u32() % 80);
+            let count = rng.next_u32() % 60;
+            let items: Vec<Rectangle> = (0..count)
+                .map(|_| {
+                    size(
+                        1 + rng.next_u32() % sheet.width.min(sheet.height),
+                        1 + rng.next_u32() % 30,
+                    )
+                })
+                .collect();
+            for strategy in Strategy::ALL {
+                for rotation in [Rotation::Fixed, Rotation::Allowed] {
+                    let options = PackOptions { strategy, rotation };
+                    let packing = pack(&items, &sheet, options).unwrap();
+                    assert_valid(&items, &packing);
+                    assert_eq!(
+                        packing,
+                        pack(&items, &sheet, options).unwrap(),
+                        "round {round}"
+                    );
+
+                    // 面積の下限より少ないシート枚数にはならない
+                    let total: u64 = items.iter().map(Rectangle::area).sum();
+                    assert!(packing.sheets as u64 * sheet.area() >= total);
+                }
+            }
+        }
+    }
+}
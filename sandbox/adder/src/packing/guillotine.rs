@@ -0,0 +1,79 @@
+// ====================================================================
+// Guillotine（ギロチン）戦略
+// ====================================================================
+//
+// 空き領域を重ならない長方形のリストで持つ
+// item を空き領域の左上に置いたら、残りを「端から端まで一直線に切って」2つに分ける
+//
+//   ┌─────┬───────┐        ┌─────┬───────┐
+//   │ item │   右    │   or   │ item │       │
+//   ├─────┴───────┤        ├─────┤  右   │
+//   │     下         │        │ 下   │       │
+//   └─────────────┘        └─────┴───────┘
+//
+// 空き領域の選び方: 置いたあと余る面積が一番小さいもの（Best Area Fit）
+// 切り方: 余りの短い辺の方向で切る（Shorter Leftover Axis）→ 大きな空き領域が残りやすい
+
+use super::{Bin, orientations};
+use crate::Rectangle;
+use crate::geometry::{Rect, Rotation};
+
+pub struct GuillotineBin {
+    free: Vec<Rect>,
+}
+
+impl GuillotineBin {
+    pub fn new(sheet: &Rectangle) -> GuillotineBin {
+        GuillotineBin {
+            free: Rect::new(0, 0, sheet.width, sheet.height)
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+impl Bin for GuillotineBin {
+    fn insert(&mut self, item: &Rectangle, rotation: Rotation) -> Option<Rect> {
+        let candidates = orientations(item, rotation);
+
+        // (余る面積, 空き領域の番号, 向き) が最小のもの
+        let (_, f, o) = self
+            .free
+            .iter()
+            .enumerate()
+            .flat_map(|(f, free)| {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, c)| c.width <= free.width() && c.height <= free.height())
+                    .map(move |(o, c)| (free.area() - c.area(), f, o))
+            })
+            .min()?;
+
+        let free = self.free.remove(f);
+        let size = &candidates[o];
+        let placed = Rect::at(free.origin(), size)?;
+
+        let right_width = free.width() - size.width;
+        let bottom_height = free.height() - size.height;
+        // 余りの横幅の方が短い → 縦に切る（右の領域は item の高さだけ、下の領域は全幅）
+        let (right, bottom) = if right_width < bottom_height {
+            (
+                Rect::new(placed.right(), free.y(), right_width, size.height),
+                Rect::new(free.x(), placed.bottom(), free.width(), bottom_height),
+            )
+        } else {
+            (
+                Rect::new(placed.right(), free.y(), right_width, free.height()),
+                Rect::new(free.x(), placed.bottom(), size.width, bottom_height),
+            )
+        };
+        self.free.extend(
+            [right, bottom]
+                .into_iter()
+                .flatten()
+                .filter(|r| !r.is_empty()),
+        );
+        Some(placed)
+    }
+}
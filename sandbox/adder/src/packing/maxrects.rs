@@ -0,0 +1,113 @@
+// ====================================================================
+// MaxRects 戦略
+// ====================================================================
+//
+// 空き領域を「これ以上広げられない長方形（極大長方形）」の集合で持つ
+//   → Guillotine と違って空き領域同士が重なってよいので、切り方で損をしない
+//
+// item を置いたら:
+//   1. 置いた場所と重なる空き領域を、重ならない部分（上・下・左・右の最大4つ）に分ける
+//   2. ほかの空き領域にすっぽり含まれる空き領域を消す（極大でないもの）
+//
+// 置き場所の選び方: 余る短い辺が一番小さいもの（Best Short Side Fit）
+//   同点なら余る長い辺 → 上 → 左 の順に比べる（決定的にするため）
+
+use super::{Bin, orientations};
+use crate::Rectangle;
+use crate::geometry::{Rect, Rotation};
+
+pub struct MaxRectsBin {
+    free: Vec<Rect>,
+}
+
+impl MaxRectsBin {
+    pub fn new(sheet: &Rectangle) -> MaxRectsBin {
+        MaxRectsBin {
+            free: Rect::new(0, 0, sheet.width, sheet.height)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn split(&mut self, placed: &Rect) {
+        let mut next = Vec::with_capacity(self.free.len() + 4);
+        for free in &self.free {
+            if !free.intersects(placed) {
+                next.push(*free);
+                continue;
+            }
+            // placed の外側にはみ出している部分を、それぞれ free の端まで伸ばして残す
+            let pieces = [
+                // 上
+                Rect::new(
+                    free.x(),
+                    free.y(),
+                    free.width(),
+                    placed.y().saturating_sub(free.y()),
+                ),
+                // 下
+                Rect::new(
+                    free.x(),
+                    placed.bottom(),
+                    free.width(),
+                    free.bottom().saturating_sub(placed.bottom()),
+                ),
+                // 左
+                Rect::new(
+                    free.x(),
+                    free.y(),
+                    placed.x().saturating_sub(free.x()),
+                    free.height(),
+                ),
+                // 右
+                Rect::new(
+                    placed.right(),
+                    free.y(),
+                    free.right().saturating_sub(placed.right()),
+                    free.height(),
+                ),
+            ];
+            next.extend(pieces.into_iter().flatten().filter(|r| !r.is_empty()));
+        }
+
+        // 他に含まれるものを消す（同じものが2つあれば前の方を残す）
+        let keep: Vec<bool> = (0..next.len())
+            .map(|i| {
+                !next.iter().enumerate().any(|(j, other)| {
+                    i != j && other.contains(&next[i]) && (next[i] != *other || j < i)
+                })
+            })
+            .collect();
+        self.free = next
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(r, keep)| keep.then_some(r))
+            .collect();
+    }
+}
+
+impl Bin for MaxRectsBin {
+    fn insert(&mut self, item: &Rectangle, rotation: Rotation) -> Option<Rect> {
+        let candidates = orientations(item, rotation);
+
+        let (_, placed) = self
+            .free
+            .iter()
+            .flat_map(|free| {
+                candidates
+                    .iter()
+                    .filter(move |c| c.width <= free.width() && c.height <= free.height())
+                    .map(move |c| {
+                        let dw = free.width() - c.width;
+                        let dh = free.height() - c.height;
+                        let score = (dw.min(dh), dw.max(dh), free.y(), free.x());
+                        (score, Rect::at(free.origin(), c))
+                    })
+            })
+            .min_by_key(|(score, _)| *score)?;
+        let placed = placed?;
+
+        self.split(&placed);
+        Some(placed)
+    }
+}
@@ -0,0 +1,88 @@
+// ====================================================================
+// Shelf（棚）戦略
+// ====================================================================
+//
+// シートを上から「棚」に区切り、棚の中は左から詰める
+//   ┌──────────────┐
+//   │ A │ B │ C │  │ ← 棚 0（高さは最初に置いた item の高さ）
+//   │ D   │ E │    │ ← 棚 1
+//   │                │ ← まだ棚のない領域
+//   └──────────────┘
+//
+// 既存の棚のうち、入る棚の中で「余る高さが一番小さい」ところに置く（Best Height Fit）
+// どの棚にも入らなければ、下に新しい棚を作る（回転できるなら低い向きで置いて棚を低く保つ）
+
+use super::{Bin, orientations};
+use crate::Rectangle;
+use crate::geometry::{Rect, Rotation};
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    used_width: u32,
+}
+
+pub struct ShelfBin {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+}
+
+impl ShelfBin {
+    pub fn new(sheet: &Rectangle) -> ShelfBin {
+        ShelfBin {
+            width: sheet.width,
+            height: sheet.height,
+            shelves: Vec::new(),
+        }
+    }
+
+    fn next_y(&self) -> u32 {
+        self.shelves.last().map_or(0, |s| s.y + s.height)
+    }
+}
+
+impl Bin for ShelfBin {
+    fn insert(&mut self, item: &Rectangle, rotation: Rotation) -> Option<Rect> {
+        let candidates = orientations(item, rotation);
+        let width = self.width;
+
+        // (余る高さ, 棚の番号, 向き) が最小のもの — 同点なら上の棚・回さない向きが優先
+        let best = self
+            .shelves
+            .iter()
+            .enumerate()
+            .flat_map(|(i, shelf)| {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, c)| {
+                        c.height <= shelf.height && c.width <= width - shelf.used_width
+                    })
+                    .map(move |(o, c)| (shelf.height - c.height, i, o))
+            })
+            .min();
+
+        if let Some((_, i, o)) = best {
+            let shelf = &mut self.shelves[i];
+            let size = &candidates[o];
+            let rect = Rect::new(shelf.used_width, shelf.y, size.width, size.height)?;
+            shelf.used_width += size.width;
+            return Some(rect);
+        }
+
+        // 新しい棚: 低い向きを優先する
+        let y = self.next_y();
+        let size = candidates
+            .iter()
+            .filter(|c| c.width <= self.width && c.height <= self.height - y)
+            .min_by_key(|c| c.height)?;
+        let rect = Rect::new(0, y, size.width, size.height)?;
+        self.shelves.push(Shelf {
+            y,
+            height: size.height,
+            used_width: size.width,
+        });
+        Some(rect)
+    }
+}
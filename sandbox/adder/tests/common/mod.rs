@@ -4,9 +4,25 @@
 // なぜ tests/common.rs ではなく tests/common/mod.rs なのか？
 // → tests/ 直下の .rs ファイルはそれぞれ独立したテストクレートとして
 //   コンパイルされてしまう。common/mod.rs にすればテストとして扱われない。
+//
+// 一時ディレクトリや環境変数ガードなど、クレートをまたいで使う部品は
+// test-support クレートにある（[dev-dependencies] で追加済み）
+
+pub use test_support::{EnvGuard, TempDir};
 
-pub fn setup() {
-    // テスト用の共通セットアップ処理
-    // （実際のプロジェクトではDB接続やテストデータ作成などを行う）
-    println!("[setup] テスト環境を準備しました");
+// テスト用の共通セットアップ処理
+//   追加のメッセージカタログを置いた一時ディレクトリを返す（drop で消える）
+//     locales/fr.txt  組み込みにない言語
+//     locales/ja.txt  組み込みの ja とは別の言い回し
+pub fn setup() -> TempDir {
+    TempDir::with_tree(
+        "
+        -- locales/fr.txt --
+        # Français
+        greeting = Bonjour, {name} !
+        farewell = Au revoir, {name} !
+        -- locales/ja.txt --
+        greeting = やあ、{name}！
+        ",
+    )
 }
@@ -10,10 +10,16 @@ use adder;
 
 mod common;
 
+use std::fs;
+
+use adder::locale::{Catalog, Greeter, Honorific};
+use common::EnvGuard;
+use test_support::assert_debug_snapshot;
+
 #[test]
 fn it_adds_two() {
-    // 共通セットアップを呼ぶ
-    common::setup();
+    // 共通セットアップを呼ぶ（戻り値の一時ディレクトリはこのテストの終わりで消える）
+    let _fixture = common::setup();
     assert_eq!(4, adder::add_two(2));
 }
 
@@ -24,6 +30,8 @@ fn it_adds() {
 
 #[test]
 fn greeting_works() {
+    // greeting は LANG などを読むので、書き換えるテストと同時に走らないようにする
+    let _env = EnvGuard::lock();
     let result = adder::greeting("World");
     assert!(result.contains("World"));
 }
@@ -40,3 +48,60 @@ fn rectangle_can_hold() {
     };
     assert!(larger.can_hold(&smaller));
 }
+
+// {:#?} の出力は tests/snapshots/ のファイルと比べる
+//   （変わったら差分が出る。意図した変更なら UPDATE_SNAPSHOTS=1 cargo test）
+#[test]
+fn rectangle_debug_output() {
+    let rect = adder::Rectangle {
+        width: 30,
+        height: 50,
+    };
+    assert_debug_snapshot!(rect);
+    assert_debug_snapshot!([
+        rect,
+        adder::Rectangle {
+            width: 0,
+            height: 0
+        }
+    ]);
+}
+
+#[test]
+fn positioned_rects_overlap() {
+    use adder::geometry::{Point, Rect};
+
+    let a = Rect::new(0, 0, 4, 4).unwrap();
+    let b = Rect::new(2, 2, 4, 4).unwrap();
+    assert_eq!(a.intersection(&b), Rect::new(2, 2, 2, 2));
+    assert!(a.union_bounds(&b).contains_point(Point { x: 5, y: 5 }));
+}
+
+#[test]
+fn catalogs_load_from_files() {
+    let fixture = common::setup();
+    let mut catalog = Catalog::builtin();
+    for entry in fs::read_dir(fixture.join("locales")).unwrap() {
+        let path = entry.unwrap().path();
+        let locale = path.file_stem().unwrap().to_str().unwrap().to_string();
+        catalog
+            .add(&locale, &fs::read_to_string(&path).unwrap())
+            .unwrap();
+    }
+
+    let fr = Greeter::with_catalog(catalog.clone(), "fr-FR");
+    assert_eq!(fr.greeting("Ferris"), "Bonjour, Ferris !");
+    // 上書きしたキーだけが変わり、ほかは組み込みのまま
+    let ja = Greeter::with_catalog(catalog, "ja").honorific(Honorific::San);
+    assert_eq!(ja.greeting("フェリス"), "やあ、フェリスさん！");
+    assert_eq!(ja.farewell("フェリス"), "さようなら、フェリスさん！");
+}
+
+#[test]
+fn greeting_follows_the_environment() {
+    let _env = EnvGuard::lock()
+        .remove("LC_ALL")
+        .remove("LC_MESSAGES")
+        .set("LANG", "ja_JP.UTF-8");
+    assert_eq!(adder::greeting("フェリス"), "こんにちは、フェリス！");
+}
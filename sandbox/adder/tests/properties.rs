@@ -0,0 +1,137 @@
+// ====================================================================
+// プロパティテスト: どんな入力でも成り立つ性質を確かめる
+// ====================================================================
+//
+// integration_test.rs は例で確かめる。ここでは test-support の prop で入力をランダムに作り、
+// 失敗したら一番単純な入力まで縮めて表示する（PROP_SEED=... で再現できる）
+//
+//   arithmetic  checked / saturating / wrapping が i128 で計算した結果と合う
+//   bigint      足し算・掛け算・割り算の恒等式、文字列との往復
+//   bounded     try_new が成功するのは範囲内のときだけ
+//   locale      あいさつには必ず名前が入る
+
+use adder::arithmetic::{checked_add, saturating_add, wrapping_add};
+use adder::bigint::BigInt;
+use adder::bounded::Bounded;
+use adder::locale::{Greeter, Honorific};
+use test_support::prop::{self, Strategy, ascii, bools, chars, ints, japanese, select, strings};
+
+// 桁数のばらばらな BigInt（10進の文字列から作る → 縮めると桁が減っていく）
+fn bigints() -> impl Strategy<Value = BigInt> {
+    (bools(), strings(chars(&['0'..='9']), 1..=80)).convert(
+        |(negative, digits)| {
+            let n: BigInt = digits.parse().unwrap();
+            if negative { -n } else { n }
+        },
+        |n| (n.is_negative(), n.abs().to_string()),
+    )
+}
+
+#[test]
+fn arithmetic_matches_wider_integers() {
+    prop::check(
+        "checked/saturating/wrapping add agree with i128",
+        (ints::<i64>(..), ints::<i64>(..)),
+        |(a, b)| {
+            let exact = i128::from(a) + i128::from(b);
+            assert_eq!(
+                checked_add(a, b).ok().map(i128::from),
+                i64::try_from(exact).ok().map(i128::from)
+            );
+            assert_eq!(
+                i128::from(saturating_add(a, b)),
+                exact.clamp(i64::MIN.into(), i64::MAX.into())
+            );
+            assert_eq!(wrapping_add(a, b), exact as i64);
+        },
+    );
+}
+
+#[test]
+fn bigint_matches_i128_for_small_values() {
+    prop::check(
+        "BigInt +, -, * agree with i128",
+        (ints::<i64>(..), ints::<i64>(..)),
+        |(a, b)| {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            let (a, b) = (i128::from(a), i128::from(b));
+            assert_eq!(&x + &y, BigInt::from(a + b));
+            assert_eq!(&x - &y, BigInt::from(a - b));
+            assert_eq!(&x * &y, BigInt::from(a * b));
+        },
+    );
+}
+
+#[test]
+fn bigint_identities() {
+    prop::check(
+        "(a + b) - b == a and a * (b + c) == a * b + a * c",
+        (bigints(), bigints(), bigints()),
+        |(a, b, c)| {
+            assert_eq!(&(&a + &b) - &b, a);
+            assert_eq!(&a * &(&b + &c), &(&a * &b) + &(&a * &c));
+        },
+    );
+}
+
+#[test]
+fn bigint_division_reconstructs_the_dividend() {
+    prop::check(
+        "q * b + r == a, |r| < |b|, r has the sign of a",
+        (bigints(), bigints()),
+        |(a, b)| {
+            let Some((q, r)) = a.checked_div_rem(&b) else {
+                return b.is_zero();
+            };
+            &(&q * &b) + &r == a
+                && r.abs() < b.abs()
+                && (r.is_zero() || r.is_negative() == a.is_negative())
+        },
+    );
+}
+
+#[test]
+fn bigint_round_trips_through_strings() {
+    prop::check(
+        "to_str_radix / from_str_radix round trip",
+        (bigints(), ints(2u32..=36)),
+        |(n, radix)| {
+            assert_eq!(n.to_string().parse::<BigInt>().unwrap(), n);
+            assert_eq!(
+                BigInt::from_str_radix(&n.to_str_radix(radix), radix).unwrap(),
+                n
+            );
+        },
+    );
+}
+
+#[test]
+fn bounded_accepts_exactly_the_range() {
+    prop::check(
+        "Bounded::<i32, -10, 10>::try_new(v).is_ok() == (-10..=10).contains(v)",
+        ints::<i32>(-1000..=1000),
+        |v| {
+            let bounded = Bounded::<i32, -10, 10>::try_new(v);
+            assert_eq!(bounded.is_ok(), (-10..=10).contains(&v));
+            if let Ok(b) = bounded {
+                assert_eq!(b.value(), v);
+            }
+        },
+    );
+}
+
+#[test]
+fn greetings_always_contain_the_name() {
+    prop::check(
+        "greeting and farewell contain the name",
+        (
+            strings(ascii().or(japanese()), 1..=10),
+            select(&["en", "ja", "ja-JP", "fr"]),
+            select(&[Honorific::Plain, Honorific::San, Honorific::Sama]),
+        ),
+        |(name, locale, honorific)| {
+            let greeter = Greeter::new(locale).honorific(honorific);
+            greeter.greeting(&name).contains(&name) && greeter.farewell(&name).contains(&name)
+        },
+    );
+}
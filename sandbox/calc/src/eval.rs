@@ -0,0 +1,227 @@
+// ====================================================================
+// 評価 — 構文木を計算して Value にする
+// ====================================================================
+//
+// 値は整数（adder の BigInt）と小数（f64）の2種類
+//   整数どうし    → 整数（+ - * はあふれない。/ % は Rust の整数と同じく 0 に向かって切り捨て）
+//   どちらか小数  → 小数
+//   整数 ^ 負の整数 → 小数（2 ^ -1 = 0.5）
+//
+// 0 で割ると（小数でも）DivisionByZero。エラーの位置は割り算の式全体
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use adder::bigint::BigInt;
+
+use crate::parser::{BinaryOp, Expr, ExprKind, UnaryOp};
+use crate::{CalcError, ErrorKind};
+
+// 整数の累乗の指数の上限（2 ^ 100000 で約3万桁）
+const MAX_EXPONENT: u32 = 100_000;
+// 整数の累乗の結果の大きさの上限（底のビット数 × 指数で見積もる。約31万桁）
+//   指数だけでは (9 ^ 99999) ^ 99999 のような巨大な底を止められない
+const MAX_RESULT_BITS: u64 = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(BigInt),
+    Float(f64),
+}
+
+impl Value {
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            // 10進の文字列を経由すると、一番近い f64 に丸められる
+            Value::Int(n) => n.to_string().parse().unwrap_or(f64::NAN),
+            Value::Float(x) => *x,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            // {:?} は 2.0 を "2.0" と書く（{} だと "2" で整数と区別できない）
+            Value::Float(x) => write!(f, "{x:?}"),
+        }
+    }
+}
+
+pub fn eval(expr: &Expr, vars: &HashMap<String, Value>) -> Result<Value, CalcError> {
+    let fail = |kind| Err(CalcError::new(kind, expr.span));
+    match &expr.kind {
+        ExprKind::Int(n) => Ok(Value::Int(n.clone())),
+        ExprKind::Float(x) => Ok(Value::Float(*x)),
+        ExprKind::Var(name) => match vars.get(name) {
+            Some(v) => Ok(v.clone()),
+            None => fail(ErrorKind::UnknownVariable(name.clone())),
+        },
+        ExprKind::Unary(op, operand) => {
+            let v = eval(operand, vars)?;
+            Ok(match (op, v) {
+                (UnaryOp::Pos, v) => v,
+                (UnaryOp::Neg, Value::Int(n)) => Value::Int(-n),
+                (UnaryOp::Neg, Value::Float(x)) => Value::Float(-x),
+            })
+        }
+        ExprKind::Binary(op, lhs, rhs) => {
+            let (l, r) = (eval(lhs, vars)?, eval(rhs, vars)?);
+            binary(*op, l, r).map_err(|kind| CalcError::new(kind, expr.span))
+        }
+        ExprKind::Call(name, args) => {
+            let values = args
+                .iter()
+                .map(|a| eval(a, vars))
+                .collect::<Result<Vec<_>, _>>()?;
+            call(name, values).map_err(|kind| CalcError::new(kind, expr.span))
+        }
+    }
+}
+
+fn binary(op: BinaryOp, l: Value, r: Value) -> Result<Value, ErrorKind> {
+    if let (Value::Int(a), Value::Int(b)) = (&l, &r) {
+        return match op {
+            BinaryOp::Add => Ok(Value::Int(a + b)),
+            BinaryOp::Sub => Ok(Value::Int(a - b)),
+            BinaryOp::Mul => Ok(Value::Int(a * b)),
+            BinaryOp::Div | BinaryOp::Rem => {
+                let (q, r) = a.checked_div_rem(b).ok_or(ErrorKind::DivisionByZero)?;
+                Ok(Value::Int(if op == BinaryOp::Div { q } else { r }))
+            }
+            BinaryOp::Pow if b.is_negative() => Ok(Value::Float(l.to_f64().powf(r.to_f64()))),
+            BinaryOp::Pow => {
+                let exp = u32::try_from(b)
+                    .ok()
+                    .filter(|&e| e <= MAX_EXPONENT)
+                    .filter(|&e| a.bits().saturating_mul(u64::from(e)) <= MAX_RESULT_BITS)
+                    .ok_or(ErrorKind::ExponentTooLarge)?;
+                Ok(Value::Int(pow(a, exp)))
+            }
+        };
+    }
+
+    let (a, b) = (l.to_f64(), r.to_f64());
+    let x = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div | BinaryOp::Rem if b == 0.0 => return Err(ErrorKind::DivisionByZero),
+        BinaryOp::Div => a / b,
+        BinaryOp::Rem => a % b,
+        BinaryOp::Pow => a.powf(b),
+    };
+    Ok(Value::Float(x))
+}
+
+// 繰り返し2乗法
+fn pow(base: &BigInt, mut exp: u32) -> BigInt {
+    let mut result = BigInt::from(1);
+    let mut square = base.clone();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = &result * &square;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            square = &square * &square;
+        }
+    }
+    result
+}
+
+// ====================================================================
+// 組み込み関数
+// ====================================================================
+
+pub const FUNCTIONS: [&str; 14] = [
+    "abs", "min", "max", "sqrt", "exp", "ln", "log10", "sin", "cos", "tan", "floor", "ceil",
+    "round", "trunc",
+];
+
+fn call(name: &str, args: Vec<Value>) -> Result<Value, ErrorKind> {
+    let arity = |expected: &'static str, ok: bool| {
+        if ok {
+            Ok(())
+        } else {
+            Err(ErrorKind::WrongArity {
+                name: name.to_string(),
+                expected,
+                found: args.len(),
+            })
+        }
+    };
+
+    match name {
+        "min" | "max" => {
+            arity("at least 1", !args.is_empty())?;
+            // 同じ大きさなら先の引数を残す
+            let wanted = if name == "min" {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+            let pick = |a: Value, b: Value| {
+                let order = match (&a, &b) {
+                    (Value::Int(x), Value::Int(y)) => y.cmp(x),
+                    _ => b
+                        .to_f64()
+                        .partial_cmp(&a.to_f64())
+                        .unwrap_or(Ordering::Equal),
+                };
+                if order == wanted { b } else { a }
+            };
+            let mut args = args.into_iter();
+            let first = args.next().unwrap();
+            return Ok(args.fold(first, pick));
+        }
+        _ if !FUNCTIONS.contains(&name) => {
+            return Err(ErrorKind::UnknownFunction(name.to_string()));
+        }
+        _ => arity("1", args.len() == 1)?,
+    }
+
+    let arg = args.into_iter().next().unwrap();
+    let x = arg.to_f64();
+    let invalid = |why: &str| Err(ErrorKind::InvalidArgument(format!("{name}: {why}")));
+    let result = match (name, &arg) {
+        ("abs", Value::Int(n)) => return Ok(Value::Int(n.abs())),
+        ("abs", _) => x.abs(),
+        // 整数を丸めても同じ整数
+        ("floor" | "ceil" | "round" | "trunc", Value::Int(_)) => return Ok(arg),
+        ("floor" | "ceil" | "round" | "trunc", _) => {
+            let rounded = match name {
+                "floor" => x.floor(),
+                "ceil" => x.ceil(),
+                "round" => x.round(),
+                _ => x.trunc(),
+            };
+            if !rounded.is_finite() {
+                return invalid("cannot convert a non-finite number to an integer");
+            }
+            // 整数になった f64 は小数点以下なしの10進表記で BigInt にできる
+            let n: BigInt = format!("{rounded:.0}").parse().expect("an integral float");
+            return Ok(Value::Int(n));
+        }
+        ("sqrt", _) if x < 0.0 => return invalid("negative argument"),
+        ("ln" | "log10", _) if x <= 0.0 => return invalid("argument must be positive"),
+        ("sqrt", _) => x.sqrt(),
+        ("exp", _) => x.exp(),
+        ("ln", _) => x.ln(),
+        ("log10", _) => x.log10(),
+        ("sin", _) => x.sin(),
+        ("cos", _) => x.cos(),
+        _ => x.tan(),
+    };
+    Ok(Value::Float(result))
+}
+
+// 最初から使える変数
+pub fn constants() -> HashMap<String, Value> {
+    HashMap::from([
+        ("pi".to_string(), Value::Float(std::f64::consts::PI)),
+        ("e".to_string(), Value::Float(std::f64::consts::E)),
+    ])
+}
@@ -0,0 +1,205 @@
+// ====================================================================
+// 字句解析 — 入力文字列をトークンの列にする
+// ====================================================================
+//
+//   "x = 2 * (y + 1.5)" → Ident(x) Assign Int(2) Star LParen Ident(y) Plus Float(1.5) RParen
+//
+// 数値:
+//   整数   123, 1_000_000            → Int（BigInt なので桁数の制限なし）
+//   小数   1.5, .5, 1e3, 2.5e-3      → Float（f64）
+// 名前:   英字か _ で始まり、英数字と _ が続く
+//
+// 各トークンは元の文字列の中の位置（Span、バイト単位）を持つ → エラーで ^ を出すため
+
+use adder::bigint::BigInt;
+
+use crate::{CalcError, ErrorKind, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Int(BigInt),
+    Float(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+    Assign,
+}
+
+impl Token {
+    // エラーメッセージ用の表記
+    pub fn describe(&self) -> String {
+        match self {
+            Token::Int(n) => format!("number `{n}`"),
+            Token::Float(x) => format!("number `{x:?}`"),
+            Token::Ident(name) => format!("`{name}`"),
+            Token::Plus => "`+`".to_string(),
+            Token::Minus => "`-`".to_string(),
+            Token::Star => "`*`".to_string(),
+            Token::Slash => "`/`".to_string(),
+            Token::Percent => "`%`".to_string(),
+            Token::Caret => "`^`".to_string(),
+            Token::LParen => "`(`".to_string(),
+            Token::RParen => "`)`".to_string(),
+            Token::Comma => "`,`".to_string(),
+            Token::Assign => "`=`".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn tokenize(src: &str) -> Result<Vec<Spanned>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let token = if c.is_ascii_digit() || c == '.' {
+            let end = scan(src, start, is_number_char);
+            // 1e-3 の - は数値の一部（直前が e のときだけ）
+            let end = match (
+                src[start..end].ends_with(['e', 'E']),
+                src[end..].chars().next(),
+            ) {
+                (true, Some('+' | '-')) => scan(src, end + 1, is_number_char),
+                _ => end,
+            };
+            while chars.peek().is_some_and(|&(i, _)| i < end) {
+                chars.next();
+            }
+            let span = Span::new(start, end);
+            number(&src[start..end]).ok_or(CalcError::new(ErrorKind::InvalidNumber, span))?
+        } else if c.is_alphabetic() || c == '_' {
+            let end = scan(src, start, |c| c.is_alphanumeric() || c == '_');
+            while chars.peek().is_some_and(|&(i, _)| i < end) {
+                chars.next();
+            }
+            Token::Ident(src[start..end].to_string())
+        } else {
+            chars.next();
+            match c {
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '*' => Token::Star,
+                '/' => Token::Slash,
+                '%' => Token::Percent,
+                '^' => Token::Caret,
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                '=' => Token::Assign,
+                _ => {
+                    let span = Span::new(start, start + c.len_utf8());
+                    return Err(CalcError::new(ErrorKind::UnexpectedChar(c), span));
+                }
+            }
+        };
+
+        let end = chars.peek().map_or(src.len(), |&(i, _)| i);
+        tokens.push(Spanned {
+            token,
+            span: Span::new(start, end),
+        });
+    }
+    Ok(tokens)
+}
+
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '.' || c == '_'
+}
+
+// start から条件を満たす文字が続く最後の位置
+fn scan(src: &str, start: usize, accept: impl Fn(char) -> bool) -> usize {
+    src[start..]
+        .char_indices()
+        .find(|&(_, c)| !accept(c))
+        .map_or(src.len(), |(i, _)| start + i)
+}
+
+fn number(text: &str) -> Option<Token> {
+    let digits = text.replace('_', "");
+    // _ は数字と数字の間だけ（"_1" は名前、"1_" や "1__0" は書き間違い）
+    if text.ends_with('_') || text.contains("__") {
+        return None;
+    }
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        return digits.parse().ok().map(Token::Int);
+    }
+    // f64 の parse は "inf" や "nan" も受け付けるので、数字で始まる形だけにする
+    let valid = digits
+        .bytes()
+        .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'+' | b'-'));
+    if !valid || !digits.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().map(Token::Float)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str) -> Vec<Token> {
+        tokenize(src)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.token)
+            .collect()
+    }
+
+    #[test]
+    fn numbers_and_names() {
+        assert_eq!(
+            kinds("1_000 + .5e1 * x_1"),
+            [
+                Token::Int(BigInt::from(1000)),
+                Token::Plus,
+                Token::Float(5.0),
+                Token::Star,
+                Token::Ident("x_1".to_string()),
+            ]
+        );
+        assert_eq!(kinds("2e-3-1")[0], Token::Float(0.002));
+        assert_eq!(kinds("2e-3-1")[1], Token::Minus);
+        // 全角の名前も使える
+        assert_eq!(kinds("税率")[0], Token::Ident("税率".to_string()));
+    }
+
+    #[test]
+    fn spans_cover_the_source_text() {
+        let tokens = tokenize(" 12 +  abc").unwrap();
+        let spans: Vec<(usize, usize)> =
+            tokens.iter().map(|t| (t.span.start, t.span.end)).collect();
+        assert_eq!(spans, [(1, 3), (4, 5), (7, 10)]);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let err = tokenize("1 + 2x").unwrap_err();
+        assert_eq!(
+            (err.kind, err.span),
+            (ErrorKind::InvalidNumber, Span::new(4, 6))
+        );
+        let err = tokenize("1 # 2").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::UnexpectedChar('#'));
+        assert_eq!(err.span, Span::new(2, 3));
+        for bad in ["1.2.3", "1e", "1_", "1__0", "."] {
+            assert!(tokenize(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,384 @@
+// ====================================================================
+// 電卓 — 式を読んで計算する（adder の BigInt を使う）
+// ====================================================================
+//
+// adder には add / add_two のような「決まった計算をする関数」が増えていくだけだったので、
+// 計算そのものを文字列の式として書けるようにする
+//
+//   let mut calc = Calculator::new();
+//   calc.eval_line("x = 2 ^ 100")?;          // 変数への代入（値も返る）
+//   calc.eval_line("x + 1")?;                // → 1267650600228229401496703205377
+//
+// 処理の流れ:
+//   lexer.rs   文字列 → トークン列
+//   parser.rs  トークン列 → 構文木（Pratt パーサ）
+//   eval.rs    構文木 → Value（整数は BigInt、小数は f64）
+//
+// エラーは CalcError（種類 + 入力の中の位置）で返る
+//   render で入力の下に ^^^ を付けて表示できる:
+//     1 + foo * 2
+//         ^^^ unknown variable `foo`
+
+mod eval;
+mod lexer;
+mod parser;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+pub use eval::{FUNCTIONS, Value};
+
+// 入力の中の位置（バイト単位の半開区間 [start, end)）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    // self から other までを覆う Span
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+// ====================================================================
+// エラー
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    InvalidNumber,
+    UnexpectedToken {
+        found: String,
+        expected: &'static str,
+    },
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    UnclosedParen,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArity {
+        name: String,
+        expected: &'static str,
+        found: usize,
+    },
+    DivisionByZero,
+    ExponentTooLarge,
+    NestingTooDeep,
+    InvalidArgument(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::UnexpectedToken { found, expected } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            ErrorKind::UnexpectedEnd { expected } => {
+                write!(f, "expected {expected}, found end of input")
+            }
+            ErrorKind::UnclosedParen => write!(f, "unclosed parenthesis"),
+            ErrorKind::UnknownVariable(name) => write!(f, "unknown variable `{name}`"),
+            ErrorKind::UnknownFunction(name) => write!(f, "unknown function `{name}`"),
+            ErrorKind::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(f, "`{name}` takes {expected} argument(s), got {found}"),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::ExponentTooLarge => write!(f, "exponent is too large"),
+            ErrorKind::NestingTooDeep => write!(f, "expression is nested too deeply"),
+            ErrorKind::InvalidArgument(why) => write!(f, "invalid argument to {why}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcError {
+    pub kind: ErrorKind,
+    pub span: Span,
+}
+
+impl CalcError {
+    pub fn new(kind: ErrorKind, span: Span) -> CalcError {
+        CalcError { kind, span }
+    }
+
+    // 入力の1行と、その下にエラーの位置を指す ^ を並べる
+    //   位置は文字数で数える（全角文字も1文字 = 1桁とみなす）
+    pub fn render(&self, src: &str) -> String {
+        let start = src[..self.span.start.min(src.len())].chars().count();
+        let width = src
+            .get(self.span.start..self.span.end)
+            .map_or(0, |s| s.chars().count())
+            .max(1);
+        format!(
+            "{src}\n{}{} {}",
+            " ".repeat(start),
+            "^".repeat(width),
+            self.kind
+        )
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (at {}..{})",
+            self.kind, self.span.start, self.span.end
+        )
+    }
+}
+
+impl Error for CalcError {}
+
+// ====================================================================
+// Calculator — 変数を覚えておく
+// ====================================================================
+
+pub struct Calculator {
+    vars: HashMap<String, Value>,
+}
+
+impl Default for Calculator {
+    fn default() -> Calculator {
+        Calculator::new()
+    }
+}
+
+impl Calculator {
+    // pi と e は最初から使える
+    pub fn new() -> Calculator {
+        Calculator {
+            vars: eval::constants(),
+        }
+    }
+
+    // 1行を計算する。代入なら代入した値を返す
+    pub fn eval_line(&mut self, src: &str) -> Result<Value, CalcError> {
+        let tokens = lexer::tokenize(src)?;
+        match parser::parse(&tokens, src.len())? {
+            parser::Stmt::Assign(name, expr) => {
+                let value = eval::eval(&expr, &self.vars)?;
+                self.vars.insert(name, value.clone());
+                Ok(value)
+            }
+            parser::Stmt::Expr(expr) => eval::eval(&expr, &self.vars),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.vars.get(name)
+    }
+
+    // 名前順の変数一覧（REPL の :vars 用）
+    pub fn vars(&self) -> Vec<(&str, &Value)> {
+        let mut vars: Vec<(&str, &Value)> =
+            self.vars.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        vars.sort_by_key(|(k, _)| *k);
+        vars
+    }
+}
+
+// 1回だけ計算する（変数は pi と e だけ）
+pub fn evaluate(src: &str) -> Result<Value, CalcError> {
+    Calculator::new().eval_line(src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ----------------------------------------------------------------
+    // 表駆動テスト: (入力, 期待する表示)
+    // ----------------------------------------------------------------
+
+    #[test]
+    fn evaluates_table() {
+        let cases = [
+            ("1 + 2 * 3", "7"),
+            ("(1 + 2) * 3", "9"),
+            ("7 / 2", "3"),
+            ("-7 / 2", "-3"),
+            ("-7 % 3", "-1"),
+            ("7.0 / 2", "3.5"),
+            ("1 + 0.5", "1.5"),
+            ("2 ^ 10", "1024"),
+            ("2 ^ 100", "1267650600228229401496703205376"),
+            ("2 ^ 3 ^ 2", "512"),
+            ("-2 ^ 2", "-4"),
+            ("(-2) ^ 2", "4"),
+            ("2 ^ -1", "0.5"),
+            ("18446744073709551615 + 1", "18446744073709551616"),
+            ("1e3", "1000.0"),
+            ("--3", "3"),
+            ("+4", "4"),
+            ("abs(-5)", "5"),
+            ("abs(-2.5)", "2.5"),
+            ("min(3, 1, 2)", "1"),
+            ("max(1, 2.5, 2)", "2.5"),
+            ("sqrt(16)", "4.0"),
+            ("floor(2.7)", "2"),
+            ("ceil(-2.7)", "-2"),
+            ("round(2.5)", "3"),
+            ("trunc(-2.7)", "-2"),
+            ("round(1e20)", "100000000000000000000"),
+            ("floor(pi)", "3"),
+            ("ln(e)", "1.0"),
+            ("x = 4", "4"),
+        ];
+        for (src, expected) in cases {
+            match evaluate(src) {
+                Ok(v) => assert_eq!(v.to_string(), expected, "{src}"),
+                Err(e) => panic!("{src}: {}", e.render(src)),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_errors_with_spans() {
+        let cases = [
+            (
+                "1 +",
+                ErrorKind::UnexpectedEnd {
+                    expected: "an expression",
+                },
+                (3, 3),
+            ),
+            ("1 $ 2", ErrorKind::UnexpectedChar('$'), (2, 3)),
+            ("(1 + 2", ErrorKind::UnclosedParen, (0, 1)),
+            ("max(1, 2", ErrorKind::UnclosedParen, (3, 4)),
+            ("1 / 0", ErrorKind::DivisionByZero, (0, 5)),
+            ("2 * (1.5 % 0.0)", ErrorKind::DivisionByZero, (4, 15)),
+            (
+                "1 + foo * 2",
+                ErrorKind::UnknownVariable("foo".into()),
+                (4, 7),
+            ),
+            ("nope(1)", ErrorKind::UnknownFunction("nope".into()), (0, 7)),
+            (
+                "sqrt(-4)",
+                ErrorKind::InvalidArgument("sqrt: negative argument".into()),
+                (0, 8),
+            ),
+            ("2 ^ 1000000", ErrorKind::ExponentTooLarge, (0, 11)),
+            ("(9 ^ 99999) ^ 99999", ErrorKind::ExponentTooLarge, (0, 19)),
+            ("(2 ^ 64) ^ 50000", ErrorKind::ExponentTooLarge, (0, 16)),
+            (
+                "1 2",
+                ErrorKind::UnexpectedToken {
+                    found: "number `2`".into(),
+                    expected: "an operator or end of input",
+                },
+                (2, 3),
+            ),
+            (
+                "* 2",
+                ErrorKind::UnexpectedToken {
+                    found: "`*`".into(),
+                    expected: "an expression",
+                },
+                (0, 1),
+            ),
+            (
+                "min()",
+                ErrorKind::WrongArity {
+                    name: "min".into(),
+                    expected: "at least 1",
+                    found: 0,
+                },
+                (0, 5),
+            ),
+            (
+                "abs(1, 2)",
+                ErrorKind::WrongArity {
+                    name: "abs".into(),
+                    expected: "1",
+                    found: 2,
+                },
+                (0, 9),
+            ),
+        ];
+        for (src, kind, (start, end)) in cases {
+            assert_eq!(
+                evaluate(src),
+                Err(CalcError::new(kind, Span::new(start, end))),
+                "{src}"
+            );
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_stack_overflow() {
+        let deep = [
+            format!("{}1{}", "(".repeat(20000), ")".repeat(20000)),
+            format!("{}1", "-".repeat(20000)),
+            format!("2{}", " ^ 2".repeat(20000)),
+            format!("1{}", " + 1".repeat(20000)),
+            format!("{}1{}", "abs(".repeat(20000), ")".repeat(20000)),
+        ];
+        for src in &deep {
+            let err = evaluate(src).unwrap_err();
+            assert_eq!(err.kind, ErrorKind::NestingTooDeep, "{}", &src[..10]);
+        }
+        // 上限より浅ければ計算できる（評価の再帰もスタックに収まる）
+        let shallow = [
+            format!("{}1{}", "(".repeat(200), ")".repeat(200)),
+            format!("{}1", "-".repeat(200)),
+            format!("1{}", " + 1".repeat(200)),
+            format!("{}-1{}", "abs(".repeat(200), ")".repeat(200)),
+        ];
+        for src in &shallow {
+            assert!(evaluate(src).is_ok(), "{}", &src[..10]);
+        }
+        // エラーは上限を超えたところの括弧を指す
+        let err = evaluate(&deep[0]).unwrap_err();
+        assert_eq!(err.span, Span::new(256, 257));
+    }
+
+    #[test]
+    fn renders_a_caret_under_the_error() {
+        let src = "1 + foo * 2";
+        let err = evaluate(src).unwrap_err();
+        assert_eq!(
+            err.render(src),
+            "1 + foo * 2\n    ^^^ unknown variable `foo`"
+        );
+        // 全角文字の後ろでも文字数で位置を合わせる
+        let src = "税率 * 2";
+        let err = evaluate(src).unwrap_err();
+        assert_eq!(err.render(src), "税率 * 2\n^^ unknown variable `税率`");
+        // 入力の終わりを指すときも ^ を1つ出す
+        assert_eq!(
+            evaluate("1 +").unwrap_err().render("1 +"),
+            "1 +\n   ^ expected an expression, found end of input"
+        );
+    }
+
+    #[test]
+    fn variables_persist_between_lines() {
+        let mut calc = Calculator::new();
+        calc.eval_line("rate = 0.1").unwrap();
+        calc.eval_line("price = 1200").unwrap();
+        assert_eq!(
+            calc.eval_line("price * (1 + rate)").unwrap().to_string(),
+            "1320.0"
+        );
+        // 失敗した代入は変数を変えない
+        assert!(calc.eval_line("price = price / 0").is_err());
+        assert_eq!(calc.get("price"), Some(&Value::Int(1200.into())));
+        let names: Vec<&str> = calc.vars().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(names, ["e", "pi", "price", "rate"]);
+    }
+}
@@ -0,0 +1,76 @@
+// ====================================================================
+// 電卓の REPL（Read-Eval-Print Loop）
+// ====================================================================
+//
+// 使い方:
+//   cargo run                      （対話モード。1行ずつ計算する）
+//   cargo run -- "2 ^ 64 + 1"      （引数の式を1回だけ計算して終わる）
+//   echo "1 + 2" | cargo run       （標準入力の各行を計算する）
+//
+// 対話モードのコマンド:
+//   :vars   変数の一覧
+//   :funcs  組み込み関数の一覧
+//   :quit   終了（Ctrl-D でも終わる）
+//
+// エラーは stderr に、式の下の ^ で位置を示して出す
+
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process;
+
+use calc::{Calculator, FUNCTIONS};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        let src = args.join(" ");
+        match calc::evaluate(&src) {
+            Ok(value) => println!("{value}"),
+            Err(e) => {
+                eprintln!("{}", e.render(&src));
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // 端末のときだけプロンプトを出す（パイプで渡したときは結果だけ）
+    let interactive = io::stdin().is_terminal();
+    let mut calc = Calculator::new();
+    let mut failed = false;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let line = line.trim();
+        match line {
+            "" => continue,
+            ":quit" | ":q" => break,
+            ":vars" => {
+                for (name, value) in calc.vars() {
+                    println!("{name} = {value}");
+                }
+            }
+            ":funcs" => println!("{}", FUNCTIONS.join(" ")),
+            _ => match calc.eval_line(line) {
+                Ok(value) => println!("{value}"),
+                Err(e) => {
+                    eprintln!("{}", e.render(line));
+                    failed = true;
+                }
+            },
+        }
+    }
+
+    // パイプで使ったとき、どこかの行が失敗していれば終了コード 1
+    if failed && !interactive {
+        process::exit(1);
+    }
+}
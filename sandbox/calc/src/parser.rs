@@ -0,0 +1,339 @@
+// ====================================================================
+// 構文解析 — Pratt パーサでトークン列から構文木（AST）を作る
+// ====================================================================
+//
+// 文:   名前 = 式   （変数への代入）
+//       式
+//
+// 演算子の強さ（binding power）— 大きいほど先に結びつく
+//   + -        10（左結合）
+//   * / %      20（左結合）
+//   -x +x      30（前置）   → -2 ^ 2 は -(2 ^ 2) = -4
+//   ^          40（右結合） → 2 ^ 3 ^ 2 は 2 ^ (3 ^ 2) = 512
+//
+// Pratt パーサの考え方:
+//   expr(min) は「左辺を1つ読み、次の演算子の左の強さが min 以上なら右辺を読んで結ぶ」を繰り返す
+//   右辺は演算子の右の強さで読む → 左結合は (左, 右) = (n, n + 1)、右結合は (n + 1, n)
+//
+// 構文木の深さは MAX_DEPTH 段まで（読むのも評価するのも再帰なので、
+// "((((…" や "- - - …"、長い "1 + 1 + …" でスタックをあふれさせない）
+//   括弧・前置演算子・二項演算子を1つ結ぶたびに1段と数える（実際の深さ以上になる見積もり）
+
+use adder::bigint::BigInt;
+
+use crate::lexer::{Spanned, Token};
+use crate::{CalcError, ErrorKind, Span};
+
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Pos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    Int(BigInt),
+    Float(f64),
+    Var(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span, // 式全体の位置
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Assign(String, Expr),
+    Expr(Expr),
+}
+
+pub fn parse(tokens: &[Spanned], src_len: usize) -> Result<Stmt, CalcError> {
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        src_len,
+        depth: 0,
+    };
+    let stmt = match tokens {
+        [
+            Spanned {
+                token: Token::Ident(name),
+                ..
+            },
+            Spanned {
+                token: Token::Assign,
+                ..
+            },
+            ..,
+        ] => {
+            parser.pos = 2;
+            Stmt::Assign(name.clone(), parser.expr(0)?)
+        }
+        _ => Stmt::Expr(parser.expr(0)?),
+    };
+    // 式のあとに余計なものが残っていたらエラー
+    if let Some(extra) = parser.peek() {
+        return Err(CalcError::new(
+            ErrorKind::UnexpectedToken {
+                found: extra.token.describe(),
+                expected: "an operator or end of input",
+            },
+            extra.span,
+        ));
+    }
+    Ok(stmt)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Spanned],
+    pos: usize,
+    src_len: usize, // 入力が途中で終わったときのエラー位置
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Spanned> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Spanned> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn end_span(&self) -> Span {
+        Span::new(self.src_len, self.src_len)
+    }
+
+    // 1段深くする。上限を超えたら、次に読むトークンの位置でエラー
+    fn descend(&mut self) -> Result<(), CalcError> {
+        if self.depth == MAX_DEPTH {
+            let span = self.peek().map_or(self.end_span(), |t| t.span);
+            return Err(CalcError::new(ErrorKind::NestingTooDeep, span));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn expr(&mut self, min_bp: u8) -> Result<Expr, CalcError> {
+        let outer = self.depth;
+        self.descend()?;
+        let mut lhs = self.prefix()?;
+        while let Some(next) = self.peek() {
+            let op = match next.token {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Sub,
+                Token::Star => BinaryOp::Mul,
+                Token::Slash => BinaryOp::Div,
+                Token::Percent => BinaryOp::Rem,
+                Token::Caret => BinaryOp::Pow,
+                _ => break,
+            };
+            let (left_bp, right_bp) = match op {
+                BinaryOp::Add | BinaryOp::Sub => (10, 11),
+                BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => (20, 21),
+                BinaryOp::Pow => (41, 40),
+            };
+            if left_bp < min_bp {
+                break;
+            }
+            self.descend()?;
+            self.pos += 1;
+            let rhs = self.expr(right_bp)?;
+            let span = lhs.span.to(rhs.span);
+            lhs = Expr {
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                span,
+            };
+        }
+        self.depth = outer;
+        Ok(lhs)
+    }
+
+    // 数値・変数・関数呼び出し・( 式 )・前置の + -
+    fn prefix(&mut self) -> Result<Expr, CalcError> {
+        let end = self.end_span();
+        let Some(Spanned { token, span }) = self.next().cloned() else {
+            return Err(CalcError::new(
+                ErrorKind::UnexpectedEnd {
+                    expected: "an expression",
+                },
+                end,
+            ));
+        };
+        let kind = match token {
+            Token::Int(n) => ExprKind::Int(n),
+            Token::Float(x) => ExprKind::Float(x),
+            Token::Ident(name) => {
+                if self.peek().is_some_and(|t| t.token == Token::LParen) {
+                    return self.call(name, span);
+                }
+                ExprKind::Var(name)
+            }
+            Token::Minus | Token::Plus => {
+                let op = if token == Token::Minus {
+                    UnaryOp::Neg
+                } else {
+                    UnaryOp::Pos
+                };
+                let operand = self.expr(30)?;
+                return Ok(Expr {
+                    span: span.to(operand.span),
+                    kind: ExprKind::Unary(op, Box::new(operand)),
+                });
+            }
+            Token::LParen => {
+                let inner = self.expr(0)?;
+                let close = self.expect_close(span)?;
+                // 括弧の位置も含めておくと、エラーが括弧ごと指される
+                return Ok(Expr {
+                    kind: inner.kind,
+                    span: span.to(close),
+                });
+            }
+            other => {
+                return Err(CalcError::new(
+                    ErrorKind::UnexpectedToken {
+                        found: other.describe(),
+                        expected: "an expression",
+                    },
+                    span,
+                ));
+            }
+        };
+        Ok(Expr { kind, span })
+    }
+
+    // 名前 ( 引数, ... )
+    fn call(&mut self, name: String, name_span: Span) -> Result<Expr, CalcError> {
+        let open = self.next().map(|t| t.span).unwrap_or(name_span);
+        let mut args = Vec::new();
+        if self.peek().is_some_and(|t| t.token == Token::RParen) {
+            let close = self.next().unwrap().span;
+            return Ok(Expr {
+                kind: ExprKind::Call(name, args),
+                span: name_span.to(close),
+            });
+        }
+        loop {
+            args.push(self.expr(0)?);
+            match self.peek() {
+                Some(Spanned {
+                    token: Token::Comma,
+                    ..
+                }) => self.pos += 1,
+                _ => break,
+            }
+        }
+        let close = self.expect_close(open)?;
+        Ok(Expr {
+            kind: ExprKind::Call(name, args),
+            span: name_span.to(close),
+        })
+    }
+
+    // 閉じ括弧がなければ、対応する開き括弧の位置でエラーにする
+    fn expect_close(&mut self, open: Span) -> Result<Span, CalcError> {
+        match self.peek() {
+            Some(Spanned {
+                token: Token::RParen,
+                span,
+            }) => {
+                let span = *span;
+                self.pos += 1;
+                Ok(span)
+            }
+            Some(other) => Err(CalcError::new(
+                ErrorKind::UnexpectedToken {
+                    found: other.token.describe(),
+                    expected: "`)`",
+                },
+                other.span,
+            )),
+            None => Err(CalcError::new(ErrorKind::UnclosedParen, open)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    // 構文木を括弧つきの文字列にする（結合の仕方を見るため）
+    fn show(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Int(n) => n.to_string(),
+            ExprKind::Float(x) => format!("{x:?}"),
+            ExprKind::Var(name) => name.clone(),
+            ExprKind::Unary(UnaryOp::Neg, e) => format!("(-{})", show(e)),
+            ExprKind::Unary(UnaryOp::Pos, e) => format!("(+{})", show(e)),
+            ExprKind::Binary(op, l, r) => {
+                let op = match op {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Sub => "-",
+                    BinaryOp::Mul => "*",
+                    BinaryOp::Div => "/",
+                    BinaryOp::Rem => "%",
+                    BinaryOp::Pow => "^",
+                };
+                format!("({} {op} {})", show(l), show(r))
+            }
+            ExprKind::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(show).collect();
+                format!("{name}({})", args.join(", "))
+            }
+        }
+    }
+
+    fn parse_str(src: &str) -> Result<Stmt, CalcError> {
+        parse(&tokenize(src)?, src.len())
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let cases = [
+            ("1 + 2 * 3", "(1 + (2 * 3))"),
+            ("1 - 2 - 3", "((1 - 2) - 3)"),
+            ("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))"),
+            ("-2 ^ 2", "(-(2 ^ 2))"),
+            ("2 ^ -1", "(2 ^ (-1))"),
+            ("(1 + 2) * 3", "((1 + 2) * 3)"),
+            ("max(1, 2 + 3) % 4", "(max(1, (2 + 3)) % 4)"),
+            ("f()", "f()"),
+        ];
+        for (src, expected) in cases {
+            match parse_str(src).unwrap() {
+                Stmt::Expr(e) => assert_eq!(show(&e), expected, "{src}"),
+                other => panic!("{src}: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn assignment_and_spans() {
+        let Stmt::Assign(name, value) = parse_str("x = (1 + 2) * y").unwrap() else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(name, "x");
+        assert_eq!(value.span, Span::new(4, 15));
+    }
+}
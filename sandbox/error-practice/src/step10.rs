@@ -1,28 +1,17 @@
 // Step 10: 検証のために独自の型を作る — Guess 型
 // 型システムで不正な値の存在を防ぐパターン
-// 1〜100 の範囲を保証する Guess 構造体を作成
+// 1〜100 の範囲を保証する Guess 型を作成（範囲つき整数 Bounded の別名）
 
-pub struct Guess {
-    // フィールドを非公開にして、new() を通さないと作れないようにする
-    value: i32,
-}
-
-impl Guess {
-    pub fn new(value: i32) -> Guess {
-        if value < 1 || value > 100 {
-            panic!(
-                "Guess の値は 1〜100 でなければなりません。受け取った値: {}",
-                value
-            );
-        }
-        Guess { value }
-    }
+// 範囲のチェックは adder::bounded::Bounded に任せる
+//   Bounded<i32, 1, 100, _> = 「1〜100 の i32」という型
+//   フィールドは非公開なので、new() / try_new() / parse() を通さないと作れない
+//   panic のメッセージも Bounded のもの（adder の Guess と同じ）になる
+//     以前: 「Guess の値は 1〜100 でなければなりません。受け取った値: 0」
+//     いま: 「予想値は1以上でなければなりませんが、0でした。」
+pub use adder::GuessValue;
+use adder::bounded::Bounded;
 
-    // ゲッターメソッド（フィールドが非公開なので、これが唯一のアクセス手段）
-    pub fn value(&self) -> i32 {
-        self.value
-    }
-}
+pub type Guess = Bounded<i32, 1, 100, GuessValue>;
 
 pub fn run() {
     println!("=== Guess 型: 型システムで値を検証 ===\n");
@@ -41,8 +30,18 @@ pub fn run() {
     println!("→ つまり Guess が存在する時点で 1〜100 が保証される");
 
     // 範囲外の値（コメントを外すと panic する）
-    // Guess::new(0);   // panic: 1未満
-    // Guess::new(101); // panic: 100超
+    // Guess::new(0);   // panic: 予想値は1以上でなければなりませんが、0でした。
+    // Guess::new(101); // panic: 予想値は100以下でなければなりませんが、101でした。
+
+    // panic させたくないときは try_new / parse で Result を受け取る
+    match Guess::try_new(101) {
+        Ok(g) => println!("Guess::try_new(101) → {}", g),
+        Err(e) => println!("Guess::try_new(101) → Err: {}", e),
+    }
+    match "abc".parse::<Guess>() {
+        Ok(g) => println!("\"abc\".parse() → {}", g),
+        Err(e) => println!("\"abc\".parse() → Err: {}", e),
+    }
 
     // 実用例: Guess を受け取る関数は範囲チェック不要
     fn print_guess(guess: &Guess) {
@@ -56,7 +55,7 @@ pub fn run() {
     println!("\n--- まとめ ---");
     println!("独自型で値を検証するパターン:");
     println!("  1. フィールドを非公開にする");
-    println!("  2. new() で検証し、不正な値なら panic!");
+    println!("  2. new() で検証し、不正な値なら panic!（try_new() なら Err）");
     println!("  3. ゲッター (value()) で読み取り専用アクセスを提供");
     println!("  4. 型が存在する = 値が有効 → 以降のチェックが不要に");
 }
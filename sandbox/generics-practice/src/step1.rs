@@ -33,6 +33,7 @@ fn largest_char(list: &[char]) -> &char {
 //     }
 //     largest
 // }
+// （tests/compile-fail/largest_without_partial_ord.rs で確かめている）
 // → Step 7 で `fn largest<T: PartialOrd>(list: &[T]) -> &T` として解決する
 
 pub fn run() {
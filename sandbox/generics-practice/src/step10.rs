@@ -3,6 +3,8 @@
 // コンパイラは参照が無効なメモリを指さないことを保証する
 
 // --- ダングリング参照の例（コンパイルエラーになる）---
+// （tests/compile-fail/ の dangling_reference.rs・longest_without_lifetime.rs・
+//   result_outlives_string2.rs で確かめている）
 // fn dangling() -> &String {
 //     let s = String::from("hello");
 //     &s  // s はこの関数の終わりで解放される → 参照が無効に！
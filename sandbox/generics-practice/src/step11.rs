@@ -73,6 +73,7 @@ pub fn run() {
     //     excerpt = ImportantExcerpt { part: &novel };  // novel はスコープ終了で解放
     // }
     // println!("{:?}", excerpt); // エラー！novel は解放済み
+    //   → tests/compile-fail/excerpt_outlives_novel.rs
 
     println!();
     println!("--- ライフタイム省略規則まとめ ---");
@@ -22,6 +22,7 @@ pub fn run() {
 
     // これはコンパイルエラーになる（x: i32, y: f64 は Point<T> に合わない）
     // let wont_work = Point { x: 5, y: 4.0 };
+    //   → tests/compile-fail/point_mixed_types.rs
 
     // PointMixed<T, U> — 異なる型もOK
     let mixed = PointMixed { x: 5, y: 4.0 };
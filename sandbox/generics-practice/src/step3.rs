@@ -51,6 +51,7 @@ pub fn run() {
     // Point<i32> には distance_from_origin は呼べない
     // let p_int = Point { x: 3, y: 4 };
     // p_int.distance_from_origin(); // エラー！
+    //   → tests/compile-fail/method_only_for_f32.rs
 
     // mixup: 異なる型の Point を組み合わせ
     let p1 = PointMixed { x: 5, y: 10.4 };
@@ -38,7 +38,7 @@ fn create_tweet() -> impl Summary {
     }
 }
 
-// これはコンパイルエラーになる:
+// これはコンパイルエラーになる（tests/compile-fail/impl_trait_two_types.rs）:
 // fn create_summarizable(switch: bool) -> impl Summary {
 //     if switch {
 //         Tweet { username: String::from("a"), content: String::from("b") }
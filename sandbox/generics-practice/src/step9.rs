@@ -71,6 +71,7 @@ pub fn run() {
     // let u1 = User { name: String::from("A"), age: 1 };
     // let u2 = u1; // ムーブ
     // println!("{:?}", u1); // エラー！u1 はもう使えない
+    //   → tests/compile-fail/moved_user.rs
 
     println!();
     println!("--- derive トレイト早見表 ---");
@@ -0,0 +1,8 @@
+// expect: E0106
+// generics-practice/src/step10.rs「ダングリング参照の例」
+//   借用元のない参照を返そうとすると、ライフタイムを決められない
+
+pub fn dangling() -> &String {
+    let s = String::from("hello");
+    &s
+}
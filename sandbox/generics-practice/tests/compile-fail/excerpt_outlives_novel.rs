@@ -0,0 +1,16 @@
+// expect: E0597
+// generics-practice/src/step11.rs: 構造体の参照は、参照先のデータより長く生存できない
+
+#[derive(Debug)]
+struct ImportantExcerpt<'a> {
+    part: &'a str,
+}
+
+pub fn excerpt_outlives_novel() {
+    let excerpt;
+    {
+        let novel = String::from("短い文");
+        excerpt = ImportantExcerpt { part: &novel };
+    }
+    println!("{:?}", excerpt);
+}
@@ -0,0 +1,40 @@
+// expect: E0308
+// generics-practice/src/step8.rs: 戻り値の impl Trait は1つの具体型しか返せない
+
+pub trait Summary {
+    fn summarize(&self) -> String;
+}
+
+pub struct Tweet {
+    pub username: String,
+    pub content: String,
+}
+
+impl Summary for Tweet {
+    fn summarize(&self) -> String {
+        format!("{}: {}", self.username, self.content)
+    }
+}
+
+pub struct NewsArticle {
+    pub headline: String,
+}
+
+impl Summary for NewsArticle {
+    fn summarize(&self) -> String {
+        self.headline.clone()
+    }
+}
+
+pub fn create_summarizable(switch: bool) -> impl Summary {
+    if switch {
+        Tweet {
+            username: String::from("a"),
+            content: String::from("b"),
+        }
+    } else {
+        NewsArticle {
+            headline: String::from("c"),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+// expect: E0369
+// generics-practice/src/step1.rs「ジェネリック版 — まだコンパイルできない！」
+//   T に > を使うには PartialOrd トレイト境界が必要
+
+pub fn largest<T>(list: &[T]) -> &T {
+    let mut largest = &list[0];
+    for item in list {
+        if item > largest {
+            largest = item;
+        }
+    }
+    largest
+}
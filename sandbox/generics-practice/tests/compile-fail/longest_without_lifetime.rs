@@ -0,0 +1,6 @@
+// expect: E0106
+// generics-practice/src/step10.rs: 戻り値の参照が x と y のどちらのものか分からない
+
+pub fn longest(x: &str, y: &str) -> &str {
+    if x.len() > y.len() { x } else { y }
+}
@@ -0,0 +1,18 @@
+// expect: E0599
+// generics-practice/src/step3.rs: impl Point<f32> のメソッドは Point<i32> には呼べない
+
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+impl Point<f32> {
+    fn distance_from_origin(&self) -> f32 {
+        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    }
+}
+
+pub fn method_only_for_f32() {
+    let p_int = Point { x: 3, y: 4 };
+    p_int.distance_from_origin();
+}
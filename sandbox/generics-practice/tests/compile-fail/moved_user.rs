@@ -0,0 +1,18 @@
+// expect: E0382
+// generics-practice/src/step9.rs: String を持つ User は Copy ではないのでムーブする
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+struct User {
+    name: String,
+    age: u32,
+}
+
+pub fn moved_user() {
+    let u1 = User {
+        name: String::from("A"),
+        age: 1,
+    };
+    let u2 = u1;
+    println!("{:?}", u1); // u1 はもう使えない
+    println!("{:?}", u2);
+}
@@ -0,0 +1,12 @@
+// expect: E0308
+// generics-practice/src/step2.rs: x: i32, y: f64 は Point<T> に合わない
+
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+pub fn point_mixed_types() {
+    let wont_work = Point { x: 5, y: 4.0 };
+    let _ = (wont_work.x, wont_work.y);
+}
@@ -0,0 +1,16 @@
+// expect: E0597
+// generics-practice/src/step10.rs「ケース 3」: string2 は解放済み
+
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() >= y.len() { x } else { y }
+}
+
+pub fn result_outlives_string2() {
+    let string1 = String::from("長い文字列です");
+    let result;
+    {
+        let string2 = String::from("xyz");
+        result = longest(string1.as_str(), string2.as_str());
+    }
+    println!("{}", result);
+}
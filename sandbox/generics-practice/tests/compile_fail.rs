@@ -0,0 +1,16 @@
+// ====================================================================
+// コンパイルエラーになるはずの例を、実際に rustc に渡して確かめる
+// ====================================================================
+//
+// src/ でコメントアウトしている「エラーになる例」を tests/compile-fail/ に1つずつ置いている
+//   各ファイルの先頭の「// expect: E0382」が期待するエラーコード
+//   コンパイルできてしまったり、違うエラーになったりしたらこのテストが失敗する
+//   （ツールチェーンを上げたときに、教材の説明が古くなっていないか分かる）
+
+#[test]
+fn commented_out_examples_fail_to_compile() {
+    test_support::compile_fail::check_dir(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/compile-fail"
+    ));
+}
@@ -0,0 +1,526 @@
+// ====================================================================
+// 靴のカタログ — 条件・並べ替え・ページ分けで探す
+// ====================================================================
+//
+// shoes_in_size はサイズがちょうど一致する靴しか選べない
+// → 商品（Shoe + ブランド + 価格）を Catalog に入れて、複数の条件で問い合わせる
+//
+//   let page = catalog.search("size >= 10 and style in (boot, sneaker) order by price limit 20")?;
+//   let page = catalog.query(
+//       &Query::new()
+//           .filter(Filter::size(10..).and(Filter::style_in(["boot", "sneaker"])))
+//           .order_by(Field::Price, Order::Asc)
+//           .limit(20),
+//   );
+//
+// 構成:
+//   filter.rs  条件式 Filter と、1つの商品が条件に合うかの判定
+//   dsl.rs     問い合わせの文字列 → Query
+//   file.rs    CSV / JSON での読み込み・書き出し（Catalog::load / save）
+//
+// 索引:
+//   全件に filter をかけると、商品が多いときに遅い
+//   → 項目ごとに「値 → 商品番号の一覧」を持っておき、条件から候補を先に絞る
+//       size / price   BTreeMap（範囲で引ける: size >= 10）
+//       style / brand  HashMap（小文字にした値で引く: style = boot。小文字化は cmp_text と同じ fold_text）
+//   候補を絞れない条件（!= や not）だけのときは全件を見る
+//   絞った候補にも Filter::matches をかけ直すので、索引は「速くする」だけで結果は変えない
+
+mod dsl;
+mod file;
+mod filter;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::ops::Bound;
+
+use crate::Shoe;
+
+pub use dsl::{QueryError, QueryErrorKind};
+pub use file::{FileError, Format, ImportError, ImportErrorKind, ImportErrors};
+pub use filter::{Field, Filter, Op, Value};
+
+// カタログの1商品
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    pub shoe: Shoe,
+    pub brand: String,
+    pub price: u32, // 円
+}
+
+impl Listing {
+    pub fn new(shoe: Shoe, brand: &str, price: u32) -> Listing {
+        Listing {
+            shoe,
+            brand: brand.to_string(),
+            price,
+        }
+    }
+
+    // 構造体としては作れても、カタログに載せられない値
+    //   （コードで作るときは insert の前に、ファイルから読むときは行ごとに確かめる）
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.shoe.size == 0 {
+            Err(ValidationError::ZeroSize)
+        } else if self.shoe.style.trim().is_empty() {
+            Err(ValidationError::EmptyStyle)
+        } else if self.brand.trim().is_empty() {
+            Err(ValidationError::EmptyBrand)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    ZeroSize,
+    EmptyStyle,
+    EmptyBrand,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::ZeroSize => write!(f, "size must be positive"),
+            ValidationError::EmptyStyle => write!(f, "style must not be empty"),
+            ValidationError::EmptyBrand => write!(f, "brand must not be empty"),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+// ====================================================================
+// Query — 条件 + 並べ替え + ページ
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Query {
+    pub filter: Filter,
+    // 先にあるものほど優先。同じ値どうしは入れた順のまま
+    pub order: Vec<(Field, Order)>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl Query {
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    pub fn parse(src: &str) -> Result<Query, QueryError> {
+        dsl::parse(src)
+    }
+
+    // 何度呼んでも and でつながる
+    pub fn filter(mut self, filter: Filter) -> Query {
+        self.filter = std::mem::take(&mut self.filter).and(filter);
+        self
+    }
+
+    pub fn order_by(mut self, field: Field, order: Order) -> Query {
+        self.order.push((field, order));
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Query {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Query {
+        self.limit = Some(limit);
+        self
+    }
+
+    // page は 1 から数える
+    pub fn page(self, page: usize, per_page: usize) -> Query {
+        self.offset(page.saturating_sub(1).saturating_mul(per_page))
+            .limit(per_page)
+    }
+}
+
+// 問い合わせの結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<'a> {
+    pub items: Vec<&'a Listing>,
+    // ページに分ける前の件数
+    pub total: usize,
+    // Filter::matches をかけた商品の数（索引が効いていれば全件より少ない）
+    pub scanned: usize,
+}
+
+impl Page<'_> {
+    pub fn shoes(&self) -> Vec<&Shoe> {
+        self.items.iter().map(|l| &l.shoe).collect()
+    }
+}
+
+// ====================================================================
+// Catalog
+// ====================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    listings: Vec<Listing>,
+    by_size: BTreeMap<u32, Vec<usize>>,
+    by_price: BTreeMap<u32, Vec<usize>>,
+    by_style: HashMap<String, Vec<usize>>,
+    by_brand: HashMap<String, Vec<usize>>,
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    // 商品番号（入れた順に 0, 1, 2, ...）を返す
+    pub fn insert(&mut self, listing: Listing) -> usize {
+        let id = self.listings.len();
+        self.by_size.entry(listing.shoe.size).or_default().push(id);
+        self.by_price.entry(listing.price).or_default().push(id);
+        self.by_style
+            .entry(filter::fold_text(&listing.shoe.style))
+            .or_default()
+            .push(id);
+        self.by_brand
+            .entry(filter::fold_text(&listing.brand))
+            .or_default()
+            .push(id);
+        self.listings.push(listing);
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Listing> {
+        self.listings.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.listings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Listing> {
+        self.listings.iter()
+    }
+
+    pub fn query(&self, query: &Query) -> Page<'_> {
+        let candidates: Vec<usize> = match self.candidates(&query.filter) {
+            Some(ids) => ids.into_iter().collect(),
+            None => (0..self.listings.len()).collect(),
+        };
+        let scanned = candidates.len();
+
+        let mut items: Vec<&Listing> = candidates
+            .into_iter()
+            .map(|id| &self.listings[id])
+            .filter(|l| query.filter.matches(l))
+            .collect();
+        let total = items.len();
+
+        // sort_by は安定ソートなので、同じ値どうしは入れた順のまま
+        items.sort_by(|a, b| {
+            query
+                .order
+                .iter()
+                .map(|&(field, order)| {
+                    let ordering = match field {
+                        Field::Size => a.shoe.size.cmp(&b.shoe.size),
+                        Field::Price => a.price.cmp(&b.price),
+                        Field::Style => filter::cmp_text(&a.shoe.style, &b.shoe.style),
+                        Field::Brand => filter::cmp_text(&a.brand, &b.brand),
+                    };
+                    match order {
+                        Order::Asc => ordering,
+                        Order::Desc => ordering.reverse(),
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let items = items
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect();
+        Page {
+            items,
+            total,
+            scanned,
+        }
+    }
+
+    pub fn search(&self, src: &str) -> Result<Page<'_>, QueryError> {
+        Ok(self.query(&Query::parse(src)?))
+    }
+
+    // shoes_in_size と同じ into_iter().filter() で、条件に合う商品を取り出す
+    pub fn into_matching(self, filter: &Filter) -> Vec<Listing> {
+        self.listings
+            .into_iter()
+            .filter(|l| filter.matches(l))
+            .collect()
+    }
+
+    // 索引で絞った候補の商品番号。None は「索引では絞れない → 全件」
+    fn candidates(&self, filter: &Filter) -> Option<BTreeSet<usize>> {
+        match filter {
+            Filter::All | Filter::Not(_) => None,
+            Filter::Cmp(field, op, value) => self.lookup(*field, *op, value),
+            Filter::In(field, values) => values
+                .iter()
+                .map(|v| self.lookup(*field, Op::Eq, v))
+                .try_fold(BTreeSet::new(), |mut all, ids| {
+                    all.extend(ids?);
+                    Some(all)
+                }),
+            Filter::And(a, b) => match (self.candidates(a), self.candidates(b)) {
+                (Some(x), Some(y)) => Some(x.intersection(&y).copied().collect()),
+                (Some(x), None) | (None, Some(x)) => Some(x),
+                (None, None) => None,
+            },
+            Filter::Or(a, b) => {
+                let mut x = self.candidates(a)?;
+                x.extend(self.candidates(b)?);
+                Some(x)
+            }
+        }
+    }
+
+    fn lookup(&self, field: Field, op: Op, value: &Value) -> Option<BTreeSet<usize>> {
+        let collect = |lists: &mut dyn Iterator<Item = &Vec<usize>>| {
+            Some(lists.flatten().copied().collect::<BTreeSet<usize>>())
+        };
+        match (field, value) {
+            (Field::Size | Field::Price, Value::Num(n)) => {
+                let index = if field == Field::Size {
+                    &self.by_size
+                } else {
+                    &self.by_price
+                };
+                let n = *n;
+                let range = match op {
+                    Op::Eq => (Bound::Included(n), Bound::Included(n)),
+                    Op::Lt => (Bound::Unbounded, Bound::Excluded(n)),
+                    Op::Le => (Bound::Unbounded, Bound::Included(n)),
+                    Op::Gt => (Bound::Excluded(n), Bound::Unbounded),
+                    Op::Ge => (Bound::Included(n), Bound::Unbounded),
+                    Op::Ne => return None,
+                };
+                collect(&mut index.range(range).map(|(_, ids)| ids))
+            }
+            (Field::Style | Field::Brand, Value::Text(s)) if op == Op::Eq => {
+                let index = if field == Field::Style {
+                    &self.by_style
+                } else {
+                    &self.by_brand
+                };
+                collect(&mut index.get(&filter::fold_text(s)).into_iter())
+            }
+            // 型の合わない比較はどの商品にも当てはまらない
+            (Field::Size | Field::Price, Value::Text(_))
+            | (Field::Style | Field::Brand, Value::Num(_)) => Some(BTreeSet::new()),
+            _ => None,
+        }
+    }
+}
+
+impl FromIterator<Listing> for Catalog {
+    fn from_iter<I: IntoIterator<Item = Listing>>(iter: I) -> Catalog {
+        let mut catalog = Catalog::new();
+        catalog.extend(iter);
+        catalog
+    }
+}
+
+impl Extend<Listing> for Catalog {
+    fn extend<I: IntoIterator<Item = Listing>>(&mut self, iter: I) {
+        for listing in iter {
+            self.insert(listing);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Catalog {
+    type Item = &'a Listing;
+    type IntoIter = std::slice::Iter<'a, Listing>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::prop::{self, ints, select, vecs};
+
+    fn listing(size: u32, style: &str, brand: &str, price: u32) -> Listing {
+        Listing::new(
+            Shoe {
+                size,
+                style: style.to_string(),
+            },
+            brand,
+            price,
+        )
+    }
+
+    fn sample() -> Catalog {
+        [
+            listing(10, "sneaker", "Acme", 8000),
+            listing(13, "sandal", "Acme", 3000),
+            listing(10, "boot", "Zeta", 15000),
+            listing(11, "Boot", "Acme", 12000),
+            listing(9, "sneaker", "Zeta", 6000),
+            listing(12, "sneaker", "New Balance", 9000),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn styles_and_sizes(page: &Page) -> Vec<(u32, String)> {
+        page.shoes()
+            .iter()
+            .map(|s| (s.size, s.style.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn searches_with_the_dsl() {
+        let catalog = sample();
+        let page = catalog
+            .search("size >= 10 and style in (boot, sneaker) order by price desc")
+            .unwrap();
+        assert_eq!(
+            styles_and_sizes(&page),
+            [
+                (10, "boot".to_string()),
+                (11, "Boot".to_string()),
+                (12, "sneaker".to_string()),
+                (10, "sneaker".to_string()),
+            ]
+        );
+        assert_eq!(page.total, 4);
+
+        let page = catalog
+            .search("brand = \"new balance\" or price < 5000")
+            .unwrap();
+        assert_eq!(page.shoes().len(), 2);
+        assert!(catalog.search("size = large").is_err());
+    }
+
+    #[test]
+    fn sorts_stably_and_pages() {
+        let catalog = sample();
+        let query = Query::new()
+            .filter(Filter::brand_in(["acme", "zeta"]))
+            .order_by(Field::Style, Order::Asc)
+            .order_by(Field::Size, Order::Desc);
+        let all = catalog.query(&query);
+        assert_eq!(
+            styles_and_sizes(&all),
+            [
+                (11, "Boot".to_string()),
+                (10, "boot".to_string()),
+                (13, "sandal".to_string()),
+                (10, "sneaker".to_string()),
+                (9, "sneaker".to_string()),
+            ]
+        );
+
+        let page2 = catalog.query(&query.clone().page(2, 2));
+        assert_eq!(page2.items, all.items[2..4]);
+        assert_eq!(page2.total, 5);
+        assert!(catalog.query(&query.clone().page(4, 2)).items.is_empty());
+        assert!(catalog.query(&query.page(usize::MAX, 2)).items.is_empty());
+    }
+
+    #[test]
+    fn indexes_avoid_full_scans() {
+        let catalog: Catalog = (0..1000)
+            .map(|i| {
+                listing(
+                    5 + i % 10,
+                    ["boot", "sneaker", "sandal"][i as usize % 3],
+                    "Acme",
+                    i * 10,
+                )
+            })
+            .collect();
+
+        let page = catalog.search("size = 7 and style = boot").unwrap();
+        assert_eq!(page.scanned, page.total);
+        assert_eq!(page.total, 33);
+
+        let page = catalog.search("price >= 9900 or style = sandal").unwrap();
+        assert_eq!(page.scanned, page.total);
+
+        // != や not は索引で絞れない → 全件を見る
+        assert_eq!(catalog.search("size != 7").unwrap().scanned, 1000);
+        assert_eq!(
+            catalog
+                .search("not size = 7 and price < 100")
+                .unwrap()
+                .scanned,
+            10
+        );
+    }
+
+    #[test]
+    fn into_matching_works_like_shoes_in_size() {
+        let shoes = sample().into_matching(&Filter::size(10..=10));
+        let styles: Vec<&str> = shoes.iter().map(|l| l.shoe.style.as_str()).collect();
+        assert_eq!(styles, ["sneaker", "boot"]);
+    }
+
+    // 索引を使った結果は、全件に filter をかけた結果と同じ
+    #[test]
+    fn indexed_queries_agree_with_full_scans() {
+        let listings = vecs(
+            (
+                ints(8u32..=12),
+                // ΟΔΟΣ: str::to_lowercase だと語末の Σ が ς になり、1文字ずつの小文字化と違う
+                select(&["boot", "Boot", "sneaker", "sandal", "ΟΔΟΣ"]),
+                select(&["Acme", "Zeta"]),
+                ints(0u32..=20),
+            ),
+            0..=30,
+        );
+        let queries = select(&[
+            "size >= 10 and style in (boot, sneaker)",
+            "price < 10 or brand = zeta",
+            "(size = 9 or size = 11) and not style = sandal",
+            "style not in (boot) and price >= 5",
+            "size != 10 or price <= 3",
+            "style = οδοσ",
+            "style in (οδος, boot) or brand = acme",
+        ]);
+        prop::check(
+            "Catalog::query == into_iter().filter(matches)",
+            (listings, queries),
+            |(listings, src)| {
+                let catalog: Catalog = listings
+                    .iter()
+                    .map(|&(size, style, brand, price)| listing(size, style, brand, price))
+                    .collect();
+                let query = Query::parse(src).unwrap();
+                let expected: Vec<&Listing> =
+                    catalog.iter().filter(|l| query.filter.matches(l)).collect();
+                assert_eq!(catalog.query(&query).items, expected);
+            },
+        );
+    }
+}
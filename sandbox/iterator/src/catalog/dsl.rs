@@ -0,0 +1,521 @@
+// ====================================================================
+// 問い合わせの文字列 → Query
+// ====================================================================
+//
+//   size >= 10 and style in (boot, sneaker) order by price desc limit 20 offset 40
+//
+// 文法（キーワードと項目名は大文字小文字を区別しない）:
+//   query  := [or] ["order" "by" key ("," key)*] ["limit" 数] ["offset" 数]
+//   or     := and ("or" and)*
+//   and    := unary ("and" unary)*
+//   unary  := "not" unary | "(" or ")" | cond
+//   cond   := 項目 比較 値 | 項目 ["not"] "in" "(" 値 ("," 値)* ")"
+//   key    := 項目 ["asc" | "desc"]
+//
+//   項目: size style brand price     比較: = == != < <= > >=
+//   値:   数 / 単語（英数字・_・-）/ "引用符つき文字列"（空白を含むとき: "New Balance"）
+//
+// size / price に文字列を渡すなど、型の合わない条件はここでエラーにする
+//
+// 入れ子は MAX_DEPTH 段まで（パースも Filter の判定も再帰なので、
+// "not not not …" や "((((…"、長い "… and … and …" でスタックをあふれさせない）
+//   not・括弧・and / or を1つ結ぶたびに1段と数える
+
+use std::error::Error;
+use std::fmt;
+
+use super::filter::{Field, Filter, Op, Value};
+use super::{Order, Query};
+
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryErrorKind {
+    UnexpectedChar(char),
+    UnclosedString,
+    NumberTooLarge,
+    UnknownField(String),
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+    TypeMismatch {
+        field: Field,
+        found: String,
+    },
+    NestingTooDeep,
+}
+
+impl fmt::Display for QueryErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryErrorKind::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            QueryErrorKind::UnclosedString => write!(f, "unclosed string"),
+            QueryErrorKind::NumberTooLarge => write!(f, "number is too large"),
+            QueryErrorKind::UnknownField(name) => write!(
+                f,
+                "unknown field `{name}` (expected size, style, brand or price)"
+            ),
+            QueryErrorKind::Expected { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            QueryErrorKind::TypeMismatch { field, found } => {
+                let kind = if field.is_numeric() {
+                    "a number"
+                } else {
+                    "text"
+                };
+                write!(f, "`{field}` takes {kind}, found {found}")
+            }
+            QueryErrorKind::NestingTooDeep => write!(f, "query is nested too deeply"),
+        }
+    }
+}
+
+// at は入力の中のバイト位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    pub kind: QueryErrorKind,
+    pub at: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.kind, self.at)
+    }
+}
+
+impl Error for QueryError {}
+
+pub fn parse(src: &str) -> Result<Query, QueryError> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: src.len(),
+        depth: 0,
+    };
+    parser.query()
+}
+
+// ====================================================================
+// 字句解析
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Num(u32),
+    Str(String),
+    Op(Op),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(w) => format!("`{w}`"),
+            Token::Num(n) => format!("number `{n}`"),
+            Token::Str(s) => format!("string {s:?}"),
+            Token::Op(op) => format!("`{}`", op.symbol()),
+            Token::LParen => "`(`".to_string(),
+            Token::RParen => "`)`".to_string(),
+            Token::Comma => "`,`".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let error = |kind, at| Err(QueryError { kind, at });
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+
+    while let Some(&(at, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let token = match c {
+            '(' | ')' | ',' => {
+                chars.next();
+                match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                }
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let eq = chars.next_if(|&(_, c)| c == '=').is_some();
+                Token::Op(match (c, eq) {
+                    ('=', _) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    _ => return error(QueryErrorKind::UnexpectedChar('!'), at),
+                })
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, 'n')) => text.push('\n'),
+                            Some((_, 't')) => text.push('\t'),
+                            Some((_, c)) => text.push(c),
+                            None => return error(QueryErrorKind::UnclosedString, at),
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return error(QueryErrorKind::UnclosedString, at),
+                    }
+                }
+                Token::Str(text)
+            }
+            c if is_word_char(c) => {
+                let mut word = String::new();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| is_word_char(c)) {
+                    word.push(c);
+                }
+                if word.bytes().all(|b| b.is_ascii_digit()) {
+                    match word.parse() {
+                        Ok(n) => Token::Num(n),
+                        Err(_) => return error(QueryErrorKind::NumberTooLarge, at),
+                    }
+                } else {
+                    Token::Word(word)
+                }
+            }
+            c => return error(QueryErrorKind::UnexpectedChar(c), at),
+        };
+        tokens.push((token, at));
+    }
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+// ====================================================================
+// 構文解析
+// ====================================================================
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn at(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |&(_, at)| at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|t| t.is_keyword(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expected<T>(&self, expected: &'static str) -> Result<T, QueryError> {
+        let found = self
+            .peek()
+            .map_or_else(|| "end of query".to_string(), Token::describe);
+        Err(QueryError {
+            kind: QueryErrorKind::Expected { expected, found },
+            at: self.at(),
+        })
+    }
+
+    // 1段深くする。上限を超えたら、次のトークンの位置でエラー
+    fn descend(&mut self) -> Result<(), QueryError> {
+        if self.depth == MAX_DEPTH {
+            return Err(QueryError {
+                kind: QueryErrorKind::NestingTooDeep,
+                at: self.at(),
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), QueryError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.expected(expected)
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, QueryError> {
+        let mut query = Query::new();
+        let clause_starts =
+            |t: &Token| ["order", "limit", "offset"].iter().any(|k| t.is_keyword(k));
+        if self.peek().is_some_and(|t| !clause_starts(t)) {
+            query = query.filter(self.or()?);
+        }
+
+        if self.eat_keyword("order") {
+            if !self.eat_keyword("by") {
+                return self.expected("`by`");
+            }
+            loop {
+                let field = self.field()?;
+                let order = if self.eat_keyword("desc") {
+                    Order::Desc
+                } else {
+                    self.eat_keyword("asc");
+                    Order::Asc
+                };
+                query = query.order_by(field, order);
+                if self.peek() != Some(&Token::Comma) {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+        if self.eat_keyword("limit") {
+            query = query.limit(self.count()?);
+        }
+        if self.eat_keyword("offset") {
+            query = query.offset(self.count()?);
+        }
+
+        match self.peek() {
+            None => Ok(query),
+            Some(_) => self.expected("`and`, `or`, `order by`, `limit`, `offset` or end of query"),
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, QueryError> {
+        let outer = self.depth;
+        let mut filter = self.and()?;
+        while self.peek().is_some_and(|t| t.is_keyword("or")) {
+            self.descend()?;
+            self.pos += 1;
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        self.depth = outer;
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, QueryError> {
+        let outer = self.depth;
+        let mut filter = self.unary()?;
+        while self.peek().is_some_and(|t| t.is_keyword("and")) {
+            self.descend()?;
+            self.pos += 1;
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        self.depth = outer;
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> Result<Filter, QueryError> {
+        let nested = self
+            .peek()
+            .is_some_and(|t| t.is_keyword("not") || *t == Token::LParen);
+        if !nested {
+            return self.cond();
+        }
+        let outer = self.depth;
+        self.descend()?;
+        let filter = if self.eat_keyword("not") {
+            Filter::Not(Box::new(self.unary()?))
+        } else {
+            self.pos += 1;
+            let filter = self.or()?;
+            self.expect(Token::RParen, "`)`")?;
+            filter
+        };
+        self.depth = outer;
+        Ok(filter)
+    }
+
+    fn cond(&mut self) -> Result<Filter, QueryError> {
+        let field = self.field()?;
+        let negated = self.eat_keyword("not");
+        if self.eat_keyword("in") {
+            self.expect(Token::LParen, "`(`")?;
+            let mut values = vec![self.value(field)?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                values.push(self.value(field)?);
+            }
+            self.expect(Token::RParen, "`,` or `)`")?;
+            let filter = Filter::In(field, values);
+            return Ok(if negated { !filter } else { filter });
+        }
+        if negated {
+            return self.expected("`in`");
+        }
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            return self.expected("a comparison or `in`");
+        };
+        self.pos += 1;
+        Ok(Filter::Cmp(field, op, self.value(field)?))
+    }
+
+    fn field(&mut self) -> Result<Field, QueryError> {
+        let at = self.at();
+        match self.peek() {
+            Some(Token::Word(name)) => {
+                let field = Field::from_name(name).ok_or_else(|| QueryError {
+                    kind: QueryErrorKind::UnknownField(name.clone()),
+                    at,
+                })?;
+                self.pos += 1;
+                Ok(field)
+            }
+            _ => self.expected("a field"),
+        }
+    }
+
+    fn value(&mut self, field: Field) -> Result<Value, QueryError> {
+        let at = self.at();
+        let value = match self.next() {
+            Some(Token::Num(n)) if field.is_numeric() => Value::Num(n),
+            Some(Token::Num(n)) => Value::Text(n.to_string()),
+            Some(Token::Word(w) | Token::Str(w)) if !field.is_numeric() => Value::Text(w),
+            Some(token @ (Token::Word(_) | Token::Str(_))) => {
+                return Err(QueryError {
+                    kind: QueryErrorKind::TypeMismatch {
+                        field,
+                        found: token.describe(),
+                    },
+                    at,
+                });
+            }
+            _ => {
+                self.pos -= 1;
+                return self.expected("a value");
+            }
+        };
+        Ok(value)
+    }
+
+    fn count(&mut self) -> Result<usize, QueryError> {
+        match self.peek() {
+            Some(&Token::Num(n)) => {
+                self.pos += 1;
+                Ok(n as usize)
+            }
+            _ => self.expected("a number"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_filters_with_precedence() {
+        let query =
+            parse("size>=10 and style in (boot, \"high top\") or not brand = Acme").unwrap();
+        assert_eq!(
+            query.filter,
+            Filter::Or(
+                Box::new(Filter::And(
+                    Box::new(Filter::cmp(Field::Size, Op::Ge, 10)),
+                    Box::new(Filter::style_in(["boot", "high top"])),
+                )),
+                Box::new(!Filter::brand("Acme")),
+            )
+        );
+        // 表示した文字列をもう一度パースすると同じ条件に戻る
+        assert_eq!(
+            parse(&query.filter.to_string()).unwrap().filter,
+            query.filter
+        );
+    }
+
+    #[test]
+    fn parses_order_and_paging() {
+        let query = parse("ORDER BY price desc, size limit 5 offset 10").unwrap();
+        assert_eq!(query.filter, Filter::All);
+        assert_eq!(
+            query.order,
+            [(Field::Price, Order::Desc), (Field::Size, Order::Asc)]
+        );
+        assert_eq!((query.limit, query.offset), (Some(5), 10));
+        assert_eq!(parse("").unwrap(), Query::new());
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let error = |src: &str| parse(src).unwrap_err();
+        assert_eq!(
+            error("colour = red").kind,
+            QueryErrorKind::UnknownField("colour".into())
+        );
+        assert_eq!(
+            error("size >= big"),
+            QueryError {
+                kind: QueryErrorKind::TypeMismatch {
+                    field: Field::Size,
+                    found: "`big`".into()
+                },
+                at: 8,
+            }
+        );
+        assert_eq!(
+            error("style in (boot").to_string(),
+            "expected `,` or `)`, found end of query (at 14)"
+        );
+        assert_eq!(error("size ! 3").kind, QueryErrorKind::UnexpectedChar('!'));
+        assert_eq!(error("brand = \"Acme").kind, QueryErrorKind::UnclosedString);
+        assert!(matches!(
+            error("size = 10 style = boot").kind,
+            QueryErrorKind::Expected { .. }
+        ));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let deep = [
+            format!("{}size = 1", "not ".repeat(20000)),
+            format!("{}size = 1{}", "(".repeat(20000), ")".repeat(20000)),
+            format!("size = 1{}", " and size = 1".repeat(20000)),
+            format!("size = 1{}", " or size = 1".repeat(20000)),
+        ];
+        for src in &deep {
+            assert_eq!(parse(src).unwrap_err().kind, QueryErrorKind::NestingTooDeep);
+        }
+        // 上限を超えたところの not を指す
+        assert_eq!(parse(&deep[0]).unwrap_err().at, 4 * MAX_DEPTH);
+
+        let shallow = [
+            format!("{}size = 1", "not ".repeat(MAX_DEPTH)),
+            format!("{}size = 1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH)),
+            format!("size = 1{}", " and size = 1".repeat(MAX_DEPTH)),
+        ];
+        for src in &shallow {
+            assert!(parse(src).is_ok(), "{}", &src[..12]);
+        }
+    }
+}
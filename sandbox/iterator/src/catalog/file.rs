@@ -0,0 +1,419 @@
+// ====================================================================
+// カタログの読み込み・書き出し — CSV と JSON
+// ====================================================================
+//
+// CSV（1行目はヘッダ。列の順番は自由）:
+//   size,style,brand,price
+//   10,sneaker,Acme,8000
+//   11,"high top, white",New Balance,12000
+//
+// JSON（商品のオブジェクトの配列。キーの順番は自由）:
+//   [
+//     {"size": 10, "style": "sneaker", "brand": "Acme", "price": 8000}
+//   ]
+//
+// 読み込みは「行ごと」に確かめ、だめな行をすべて集めて返す（行番号つき）
+//   line 3: `size` must be a whole number, found `ten`
+//   line 5: style must not be empty
+//   → 1行でもだめならカタログは作らない（一部だけ読めたカタログは使わせない）
+// CSV / JSON の書き方自体が壊れているとき（閉じていない引用符など）は、そこで止める
+//
+// 書き出したものを読み込むと、同じ商品の列に戻る
+
+mod csv;
+mod json;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{Catalog, Field, Listing, ValidationError};
+use crate::Shoe;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    // 拡張子（.csv / .json、大文字小文字は問わない）から
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let ext = path.extension()?.to_str()?;
+        if ext.eq_ignore_ascii_case("csv") {
+            Some(Format::Csv)
+        } else if ext.eq_ignore_ascii_case("json") {
+            Some(Format::Json)
+        } else {
+            None
+        }
+    }
+
+    pub fn read(self, text: &str) -> Result<Vec<Listing>, ImportErrors> {
+        match self {
+            Format::Csv => csv::read(text),
+            Format::Json => json::read(text),
+        }
+    }
+
+    pub fn write<'a>(self, listings: impl IntoIterator<Item = &'a Listing>) -> String {
+        match self {
+            Format::Csv => csv::write(listings),
+            Format::Json => json::write(listings),
+        }
+    }
+}
+
+// ====================================================================
+// エラー
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportErrorKind {
+    // CSV / JSON として読めない
+    Syntax(String),
+    MissingField(Field),
+    UnknownField(String),
+    DuplicateField(Field),
+    FieldCount { expected: usize, found: usize },
+    // 型が違う（size が数でない、style が文字列でない）
+    BadValue { field: Field, found: String },
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for ImportErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportErrorKind::Syntax(message) => write!(f, "{message}"),
+            ImportErrorKind::MissingField(field) => write!(f, "missing `{field}`"),
+            ImportErrorKind::UnknownField(name) => write!(f, "unknown field `{name}`"),
+            ImportErrorKind::DuplicateField(field) => write!(f, "`{field}` appears twice"),
+            ImportErrorKind::FieldCount { expected, found } => {
+                write!(f, "expected {expected} fields, found {found}")
+            }
+            ImportErrorKind::BadValue { field, found } => {
+                let kind = if field.is_numeric() {
+                    "a whole number"
+                } else {
+                    "a string"
+                };
+                write!(f, "`{field}` must be {kind}, found {found}")
+            }
+            ImportErrorKind::Invalid(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+// line は 1 始まり（CSV は行が始まる行、JSON は値が始まる行）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    pub line: usize,
+    pub kind: ImportErrorKind,
+}
+
+impl ImportError {
+    pub fn new(line: usize, kind: ImportErrorKind) -> ImportError {
+        ImportError { line, kind }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl Error for ImportError {}
+
+// 1回の読み込みで見つかったエラー（行の順、1つ以上）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportErrors {
+    pub errors: Vec<ImportError>,
+}
+
+impl From<ImportError> for ImportErrors {
+    fn from(error: ImportError) -> ImportErrors {
+        ImportErrors {
+            errors: vec![error],
+        }
+    }
+}
+
+impl fmt::Display for ImportErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ImportErrors {}
+
+#[derive(Debug)]
+pub enum FileError {
+    Io { path: PathBuf, source: io::Error },
+    UnknownFormat(PathBuf),
+    Import { path: PathBuf, errors: ImportErrors },
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            FileError::UnknownFormat(path) => write!(
+                f,
+                "{}: unknown format (expected a .csv or .json file)",
+                path.display()
+            ),
+            FileError::Import { path, errors } => {
+                for (i, error) in errors.errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}:{}: {}", path.display(), error.line, error.kind)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::Io { source, .. } => Some(source),
+            FileError::UnknownFormat(_) => None,
+            FileError::Import { errors, .. } => Some(errors),
+        }
+    }
+}
+
+// ====================================================================
+// Catalog から使う
+// ====================================================================
+
+impl Catalog {
+    pub fn from_csv(text: &str) -> Result<Catalog, ImportErrors> {
+        Format::Csv.read(text).map(Catalog::from_iter)
+    }
+
+    pub fn from_json(text: &str) -> Result<Catalog, ImportErrors> {
+        Format::Json.read(text).map(Catalog::from_iter)
+    }
+
+    pub fn to_csv(&self) -> String {
+        Format::Csv.write(self)
+    }
+
+    pub fn to_json(&self) -> String {
+        Format::Json.write(self)
+    }
+
+    // 形式は拡張子で決める
+    pub fn load(path: impl AsRef<Path>) -> Result<Catalog, FileError> {
+        let path = path.as_ref();
+        let format =
+            Format::from_path(path).ok_or_else(|| FileError::UnknownFormat(path.into()))?;
+        let text = fs::read_to_string(path).map_err(|source| FileError::Io {
+            path: path.into(),
+            source,
+        })?;
+        match format.read(&text) {
+            Ok(listings) => Ok(listings.into_iter().collect()),
+            Err(errors) => Err(FileError::Import {
+                path: path.into(),
+                errors,
+            }),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FileError> {
+        let path = path.as_ref();
+        let format =
+            Format::from_path(path).ok_or_else(|| FileError::UnknownFormat(path.into()))?;
+        fs::write(path, format.write(self)).map_err(|source| FileError::Io {
+            path: path.into(),
+            source,
+        })
+    }
+}
+
+// ====================================================================
+// 1行分の値 → Listing（CSV・JSON 共通）
+// ====================================================================
+
+#[derive(Default)]
+struct Row {
+    size: Option<u32>,
+    style: Option<String>,
+    brand: Option<String>,
+    price: Option<u32>,
+}
+
+impl Row {
+    // 数の項目は文字列から読む（前後の空白は無視）
+    fn set_text(&mut self, field: Field, text: &str) -> Result<(), ImportErrorKind> {
+        let number = || {
+            text.trim()
+                .parse::<u32>()
+                .map_err(|_| ImportErrorKind::BadValue {
+                    field,
+                    found: format!("`{text}`"),
+                })
+        };
+        match field {
+            Field::Size => self.size = Some(number()?),
+            Field::Price => self.price = Some(number()?),
+            Field::Style => self.style = Some(text.to_string()),
+            Field::Brand => self.brand = Some(text.to_string()),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Listing, ImportErrorKind> {
+        let listing = Listing {
+            shoe: Shoe {
+                size: self
+                    .size
+                    .ok_or(ImportErrorKind::MissingField(Field::Size))?,
+                style: self
+                    .style
+                    .ok_or(ImportErrorKind::MissingField(Field::Style))?,
+            },
+            brand: self
+                .brand
+                .ok_or(ImportErrorKind::MissingField(Field::Brand))?,
+            price: self
+                .price
+                .ok_or(ImportErrorKind::MissingField(Field::Price))?,
+        };
+        listing.validate().map_err(ImportErrorKind::Invalid)?;
+        Ok(listing)
+    }
+}
+
+// 行ごとの結果をまとめる: だめな行が1つでもあればエラーの一覧
+fn collect_rows(rows: Vec<Result<Listing, ImportError>>) -> Result<Vec<Listing>, ImportErrors> {
+    let (listings, errors): (Vec<_>, Vec<_>) = rows.into_iter().partition(Result::is_ok);
+    if errors.is_empty() {
+        Ok(listings.into_iter().map(Result::unwrap).collect())
+    } else {
+        Err(ImportErrors {
+            errors: errors.into_iter().map(Result::unwrap_err).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::TempDir;
+    use test_support::prop::{self, Strategy, chars, ints, strings, vecs};
+
+    // 区切り・引用符・改行・全角・前後の空白など、書き出しで気をつける文字を多めに
+    fn listings() -> impl Strategy<Value = Vec<Listing>> {
+        let text = || {
+            strings(
+                chars(&[
+                    'a'..='c',
+                    'あ'..='う',
+                    ' '..=' ',
+                    ','..=',',
+                    '"'..='"',
+                    '\n'..='\n',
+                    '\\'..='\\',
+                ]),
+                1..=8,
+            )
+        };
+        vecs(
+            (ints(1u32..), text(), text(), ints(0u32..)).convert(
+                |(size, style, brand, price)| Listing::new(Shoe { size, style }, &brand, price),
+                |l| (l.shoe.size, l.shoe.style.clone(), l.brand.clone(), l.price),
+            ),
+            0..=10,
+        )
+    }
+
+    #[test]
+    fn round_trips_through_both_formats() {
+        prop::check(
+            "read(write(listings)) == listings",
+            listings(),
+            |listings| {
+                // 空白だけの style / brand はもともと載せられない
+                if listings.iter().any(|l| l.validate().is_err()) {
+                    return;
+                }
+                for format in [Format::Csv, Format::Json] {
+                    let text = format.write(&listings);
+                    assert_eq!(
+                        format.read(&text),
+                        Ok(listings.clone()),
+                        "{format:?}:\n{text}"
+                    );
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn loads_and_saves_by_extension() {
+        let dir = TempDir::with_tree(
+            "
+            -- shoes.csv --
+            size,style,brand,price
+            10,sneaker,Acme,8000
+            13,sandal,Acme,3000
+            -- shoes.txt --
+            ",
+        );
+        let catalog = Catalog::load(dir.join("shoes.csv")).unwrap();
+        assert_eq!(catalog.len(), 2);
+
+        catalog.save(dir.join("copy.JSON")).unwrap();
+        let copy = Catalog::load(dir.join("copy.JSON")).unwrap();
+        assert_eq!(
+            copy.iter().collect::<Vec<_>>(),
+            catalog.iter().collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            Catalog::load(dir.join("shoes.txt")),
+            Err(FileError::UnknownFormat(_))
+        ));
+        let missing = Catalog::load(dir.join("missing.csv")).unwrap_err();
+        assert!(matches!(missing, FileError::Io { .. }));
+        assert!(missing.source().is_some());
+    }
+
+    #[test]
+    fn reports_every_bad_row_with_its_file_and_line() {
+        let dir = TempDir::with_tree(
+            "
+            -- bad.csv --
+            size,style,brand,price
+            ten,boot,Acme,100
+            10,,Acme,100
+            10,boot,Acme,100
+            0,boot,Acme,100
+            ",
+        );
+        let error = Catalog::load(dir.join("bad.csv")).unwrap_err();
+        let path = dir.join("bad.csv").display().to_string();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{path}:2: `size` must be a whole number, found `ten`\n\
+                 {path}:3: style must not be empty\n\
+                 {path}:5: size must be positive"
+            )
+        );
+    }
+}
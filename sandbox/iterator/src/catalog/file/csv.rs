@@ -0,0 +1,244 @@
+// ====================================================================
+// CSV（手書き）
+// ====================================================================
+//
+//   - "..." で囲んだフィールドには , 引用符（"" と書く）・改行を入れられる
+//   - 行末は \n でも \r\n でもよい。空行は飛ばす
+//   - 書き出すときは、, " 改行を含むか前後に空白があるフィールドだけ "..." で囲む
+
+use super::{ImportError, ImportErrorKind, ImportErrors, Row, collect_rows};
+use crate::catalog::{Field, Listing};
+
+// 分解した1行と、それが始まる行番号
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+}
+
+pub fn read(text: &str) -> Result<Vec<Listing>, ImportErrors> {
+    let mut records = parse(text)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let columns = columns(&header)?;
+
+    collect_rows(
+        records
+            .map(|record| {
+                listing(&columns, &record.fields)
+                    .map_err(|kind| ImportError::new(record.line, kind))
+            })
+            .collect(),
+    )
+}
+
+fn listing(columns: &[Field], fields: &[String]) -> Result<Listing, ImportErrorKind> {
+    if fields.len() != columns.len() {
+        return Err(ImportErrorKind::FieldCount {
+            expected: columns.len(),
+            found: fields.len(),
+        });
+    }
+    let mut row = Row::default();
+    for (&field, text) in columns.iter().zip(fields) {
+        row.set_text(field, text)?;
+    }
+    row.finish()
+}
+
+// ヘッダの列名 → 各列の項目（4つの項目がちょうど1回ずつ）
+fn columns(header: &Record) -> Result<Vec<Field>, ImportError> {
+    let error = |kind| ImportError::new(header.line, kind);
+    let mut columns = Vec::new();
+    for name in &header.fields {
+        let field = Field::from_name(name.trim())
+            .ok_or_else(|| error(ImportErrorKind::UnknownField(name.clone())))?;
+        if columns.contains(&field) {
+            return Err(error(ImportErrorKind::DuplicateField(field)));
+        }
+        columns.push(field);
+    }
+    match Field::ALL.into_iter().find(|f| !columns.contains(f)) {
+        Some(missing) => Err(error(ImportErrorKind::MissingField(missing))),
+        None => Ok(columns),
+    }
+}
+
+fn parse(text: &str) -> Result<Vec<Record>, ImportError> {
+    let syntax =
+        |line, message: &str| ImportError::new(line, ImportErrorKind::Syntax(message.to_string()));
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut in_quotes = false;
+    let mut quoted = false; // このフィールドは "..." だったか
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\n' | '\r' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                fields.push(std::mem::take(&mut field));
+                push_record(
+                    &mut records,
+                    std::mem::take(&mut fields),
+                    record_line,
+                    quoted,
+                );
+                quoted = false;
+                line += 1;
+                record_line = line;
+            }
+            c if quoted => {
+                return Err(syntax(
+                    line,
+                    &format!("unexpected character `{c}` after closing quote"),
+                ));
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(syntax(record_line, "unterminated quoted field"));
+    }
+    if !field.is_empty() || !fields.is_empty() || quoted {
+        fields.push(field);
+        push_record(&mut records, fields, record_line, quoted);
+    }
+    Ok(records)
+}
+
+// 空行（引用符なしの空フィールド1つだけ）はレコードにしない
+fn push_record(records: &mut Vec<Record>, fields: Vec<String>, line: usize, quoted: bool) {
+    if !(fields.len() == 1 && fields[0].is_empty() && !quoted) {
+        records.push(Record { line, fields });
+    }
+}
+
+pub fn write<'a>(listings: impl IntoIterator<Item = &'a Listing>) -> String {
+    let mut out = String::from("size,style,brand,price\n");
+    for listing in listings {
+        out.push_str(&format!(
+            "{},{},{},{}\n",
+            listing.shoe.size,
+            quote(&listing.shoe.style),
+            quote(&listing.brand),
+            listing.price
+        ));
+    }
+    out
+}
+
+fn quote(text: &str) -> String {
+    let needs_quotes = text.contains([',', '"', '\n', '\r']) || text.trim() != text;
+    if needs_quotes {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shoe;
+
+    #[test]
+    fn reads_columns_in_any_order() {
+        let text = "price,brand,size,style\r\n\r\n8000,Acme,10,\"high top, \"\"white\"\"\"\r\n";
+        assert_eq!(
+            read(text),
+            Ok(vec![Listing::new(
+                Shoe {
+                    size: 10,
+                    style: "high top, \"white\"".to_string()
+                },
+                "Acme",
+                8000
+            )])
+        );
+        assert_eq!(read(""), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn reports_header_and_row_errors() {
+        let kinds = |text: &str| -> Vec<(usize, ImportErrorKind)> {
+            read(text)
+                .unwrap_err()
+                .errors
+                .into_iter()
+                .map(|e| (e.line, e.kind))
+                .collect()
+        };
+        assert_eq!(
+            kinds("size,style,brand\n"),
+            [(1, ImportErrorKind::MissingField(Field::Price))]
+        );
+        assert_eq!(
+            kinds("size,style,colour,price\n"),
+            [(1, ImportErrorKind::UnknownField("colour".into()))]
+        );
+        assert_eq!(
+            kinds("size,style,brand,price\n10,\"a\nb\",Acme\n-1,boot,Acme,5\n"),
+            [
+                (
+                    2,
+                    ImportErrorKind::FieldCount {
+                        expected: 4,
+                        found: 3
+                    }
+                ),
+                (
+                    4,
+                    ImportErrorKind::BadValue {
+                        field: Field::Size,
+                        found: "`-1`".into()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            kinds("size,style,brand,price\n10,\"boot,Acme,5\n"),
+            [(
+                2,
+                ImportErrorKind::Syntax("unterminated quoted field".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn quotes_only_when_needed() {
+        assert_eq!(quote("boot"), "boot");
+        assert_eq!(quote("a,b"), "\"a,b\"");
+        assert_eq!(quote(" padded"), "\" padded\"");
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,460 @@
+// ====================================================================
+// JSON（手書き・再帰下降）
+// ====================================================================
+//
+// 読めるのは JSON 全体だが、カタログとして受け付けるのは「オブジェクトの配列」だけ
+// エラーに行番号を付けるため、配列の要素とオブジェクトの値は始まる行を覚えておく
+// 数値は元の文字列のまま持ち、u32 として読めるかは項目ごとに確かめる（10.5 や -1 はだめ）
+// 入れ子は MAX_DEPTH 段まで（再帰で読むので、深すぎる入力でスタックをあふれさせない）
+
+use std::fmt::Write;
+
+use super::{ImportError, ImportErrorKind, ImportErrors, Row, collect_rows};
+use crate::catalog::{Field, Listing};
+
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    // (行, 値)
+    Array(Vec<(usize, Json)>),
+    // (キー, 値の行, 値)
+    Object(Vec<(String, usize, Json)>),
+}
+
+impl Json {
+    // エラーメッセージ用
+    fn describe(&self) -> String {
+        match self {
+            Json::Null => "`null`".to_string(),
+            Json::Bool(b) => format!("`{b}`"),
+            Json::Number(n) => format!("`{n}`"),
+            Json::String(s) => format!("`\"{}\"`", escape(s)),
+            Json::Array(_) => "an array".to_string(),
+            Json::Object(_) => "an object".to_string(),
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 64;
+
+pub fn read(text: &str) -> Result<Vec<Listing>, ImportErrors> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        pos: 0,
+        line: 1,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.bytes.len() {
+        return Err(parser.error("trailing characters after the array").into());
+    }
+
+    let Json::Array(items) = value else {
+        return Err(ImportError::new(
+            1,
+            ImportErrorKind::Syntax(format!("expected an array, found {}", value.describe())),
+        )
+        .into());
+    };
+    collect_rows(
+        items
+            .into_iter()
+            .map(|(line, item)| listing(line, item))
+            .collect(),
+    )
+}
+
+fn listing(line: usize, item: Json) -> Result<Listing, ImportError> {
+    let Json::Object(entries) = item else {
+        return Err(ImportError::new(
+            line,
+            ImportErrorKind::Syntax(format!("expected an object, found {}", item.describe())),
+        ));
+    };
+    let mut row = Row::default();
+    let mut seen = Vec::new();
+    for (key, value_line, value) in entries {
+        let error = |kind| ImportError::new(value_line, kind);
+        let field = Field::ALL
+            .into_iter()
+            .find(|f| f.name() == key)
+            .ok_or_else(|| error(ImportErrorKind::UnknownField(key)))?;
+        if seen.contains(&field) {
+            return Err(error(ImportErrorKind::DuplicateField(field)));
+        }
+        seen.push(field);
+
+        let text = match (&value, field.is_numeric()) {
+            (Json::Number(n), true) | (Json::String(n), false) => n,
+            _ => {
+                return Err(error(ImportErrorKind::BadValue {
+                    field,
+                    found: value.describe(),
+                }));
+            }
+        };
+        row.set_text(field, text).map_err(error)?;
+    }
+    row.finish().map_err(|kind| ImportError::new(line, kind))
+}
+
+// 1商品1行で書き出す
+pub fn write<'a>(listings: impl IntoIterator<Item = &'a Listing>) -> String {
+    let mut out = String::from("[");
+    for (i, listing) in listings.into_iter().enumerate() {
+        let _ = write!(
+            out,
+            "{}\n  {{\"size\": {}, \"style\": \"{}\", \"brand\": \"{}\", \"price\": {}}}",
+            if i > 0 { "," } else { "" },
+            listing.shoe.size,
+            escape(&listing.shoe.style),
+            escape(&listing.brand),
+            listing.price
+        );
+    }
+    if out.len() > 1 {
+        out.push('\n');
+    }
+    out.push_str("]\n");
+    out
+}
+
+// Rust の {:?} とは書き方が違う（\u{1b} ではなく \u001b）ので自分でエスケープする
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ====================================================================
+// パーサ
+// ====================================================================
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    line: usize,
+    // いま開いている [ と { の数
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::new(self.line, ImportErrorKind::Syntax(message.to_string()))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    // 改行を数えるのはここだけ（文字列の中に生の改行は書けない）
+    fn skip_whitespace(&mut self) {
+        while let Some(b @ (b' ' | b'\t' | b'\n' | b'\r')) = self.peek() {
+            if b == b'\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ImportError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, ImportError> {
+        if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, ImportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(open @ (b'{' | b'[')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("nesting too deep"));
+                }
+                self.depth += 1;
+                let value = if open == b'{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, ImportError> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let line = self.line;
+            let value = self.value()?;
+            entries.push((key, line, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, ImportError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            let line = self.line;
+            items.push((line, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, ImportError> {
+        let start = self.pos;
+        while let Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+        // 入力は &str 由来で、ここまでは ASCII しか読んでいない
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        if text.parse::<f64>().is_err() {
+            return Err(self.error("invalid number"));
+        }
+        Ok(Json::Number(text.to_string()))
+    }
+
+    fn string(&mut self) -> Result<String, ImportError> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            // エスケープも引用符もない区間はまとめてコピーする
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let c = self.unicode_escape()?;
+                            out.push(c);
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.pos += 1;
+                    out.push(escaped);
+                }
+                Some(_) => return Err(self.error("control character in string")),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // \uXXXX（サロゲートペアにも対応）
+    fn unicode_escape(&mut self) -> Result<char, ImportError> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.bytes[self.pos..].starts_with(b"\\u") {
+                return Err(self.error("invalid surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("invalid surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, ImportError> {
+        // from_str_radix は先頭の + も読んでしまうので、4桁とも16進数字か先に確かめる
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .filter(|b| b.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .and_then(|s| u32::from_str_radix(s, 16).ok());
+        let n = digits.ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shoe;
+
+    #[test]
+    fn reads_objects_with_keys_in_any_order() {
+        let text = r#"[
+            {"price": 8000, "brand": "Acme", "style": "high \"top\" あ", "size": 10}
+        ]"#;
+        assert_eq!(
+            read(text),
+            Ok(vec![Listing::new(
+                Shoe {
+                    size: 10,
+                    style: "high \"top\" あ".to_string()
+                },
+                "Acme",
+                8000
+            )])
+        );
+        assert_eq!(write(&[]), "[]\n");
+        assert_eq!(read("[]"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn reports_errors_on_the_line_of_the_value() {
+        let text = r#"[
+  {"size": 10, "style": "boot", "brand": "Acme", "price": 100},
+  {"size": 10.5, "style": "boot", "brand": "Acme", "price": 100},
+  {"size": 10, "style": "boot",
+   "brand": 3, "price": 100},
+  {"size": 10, "style": "boot", "price": 100},
+  "boot"
+]"#;
+        let errors: Vec<String> = read(text)
+            .unwrap_err()
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "line 3: `size` must be a whole number, found `10.5`",
+                "line 5: `brand` must be a string, found `3`",
+                "line 6: missing `brand`",
+                "line 7: expected an object, found `\"boot\"`",
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_errors_stop_reading() {
+        let error = read("[\n  {\"size\": 10,\n  }\n]").unwrap_err();
+        assert_eq!(error.errors.len(), 1);
+        assert_eq!(error.errors[0].line, 3);
+        assert!(read("{\"size\": 10}").is_err());
+        assert!(read("[] []").is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_a_syntax_error() {
+        let kind = |text: &str| read(text).unwrap_err().errors.remove(0).kind;
+        let too_deep = ImportErrorKind::Syntax("nesting too deep".to_string());
+        assert_eq!(kind(&"[".repeat(200_000)), too_deep);
+        assert_eq!(kind(&"[{\"a\": ".repeat(100_000)), too_deep);
+        // 上限まではふつうに読める（要素がオブジェクトでないだけ）
+        let nested = format!(
+            "[{}{}]",
+            "[".repeat(MAX_DEPTH - 1),
+            "]".repeat(MAX_DEPTH - 1)
+        );
+        assert_eq!(
+            kind(&nested),
+            ImportErrorKind::Syntax("expected an object, found an array".to_string())
+        );
+    }
+
+    #[test]
+    fn unicode_escapes_need_four_hex_digits() {
+        let parser = |text: &'static str| {
+            Parser {
+                bytes: text.as_bytes(),
+                pos: 0,
+                line: 1,
+                depth: 0,
+            }
+            .string()
+        };
+        assert_eq!(parser(r#""\u3042""#), Ok("あ".to_string()));
+        assert!(parser(r#""\u+123""#).is_err());
+        assert!(parser(r#""\u12""#).is_err());
+    }
+}
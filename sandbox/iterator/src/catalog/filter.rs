@@ -0,0 +1,315 @@
+// ====================================================================
+// Filter — 「どの商品を残すか」の条件式
+// ====================================================================
+//
+// shoes_in_size の `s.size == shoe_size` を、項目・比較・組み合わせに分けて値として持つ
+//
+//   Filter::size(10..).and(Filter::style_in(["boot", "sneaker"]))
+//   ↔ "size >= 10 and style in (boot, sneaker)"（dsl.rs）
+//
+// 文字列の項目（style / brand）は大文字小文字を区別しない（"Boot" = "boot"）
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Bound, Not, RangeBounds};
+
+use super::Listing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Size,
+    Style,
+    Brand,
+    Price,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::Size, Field::Style, Field::Brand, Field::Price];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Size => "size",
+            Field::Style => "style",
+            Field::Brand => "brand",
+            Field::Price => "price",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Field> {
+        Field::ALL
+            .into_iter()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(self, Field::Size | Field::Price)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Num(u32),
+    Text(String),
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Value {
+        Value::Num(n)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Value {
+        Value::Text(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Value {
+        Value::Text(s)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(n) => write!(f, "{n}"),
+            Value::Text(s) => write!(f, "{s:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Filter {
+    // 条件なし（すべて残す）
+    #[default]
+    All,
+    Cmp(Field, Op, Value),
+    In(Field, Vec<Value>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+// ====================================================================
+// 組み立て
+// ====================================================================
+
+impl Filter {
+    pub fn cmp(field: Field, op: Op, value: impl Into<Value>) -> Filter {
+        Filter::Cmp(field, op, value.into())
+    }
+
+    // 10..=12 や 10.. のような範囲を >= / <= の組にする
+    pub fn size(range: impl RangeBounds<u32>) -> Filter {
+        Filter::range(Field::Size, range)
+    }
+
+    pub fn price(range: impl RangeBounds<u32>) -> Filter {
+        Filter::range(Field::Price, range)
+    }
+
+    pub fn style(style: &str) -> Filter {
+        Filter::cmp(Field::Style, Op::Eq, style)
+    }
+
+    pub fn style_in<'a>(styles: impl IntoIterator<Item = &'a str>) -> Filter {
+        Filter::In(Field::Style, styles.into_iter().map(Value::from).collect())
+    }
+
+    pub fn brand(brand: &str) -> Filter {
+        Filter::cmp(Field::Brand, Op::Eq, brand)
+    }
+
+    pub fn brand_in<'a>(brands: impl IntoIterator<Item = &'a str>) -> Filter {
+        Filter::In(Field::Brand, brands.into_iter().map(Value::from).collect())
+    }
+
+    fn range(field: Field, range: impl RangeBounds<u32>) -> Filter {
+        let lower = match range.start_bound() {
+            Bound::Included(&n) => Filter::cmp(field, Op::Ge, n),
+            Bound::Excluded(&n) => Filter::cmp(field, Op::Gt, n),
+            Bound::Unbounded => Filter::All,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(&n) => Filter::cmp(field, Op::Le, n),
+            Bound::Excluded(&n) => Filter::cmp(field, Op::Lt, n),
+            Bound::Unbounded => Filter::All,
+        };
+        lower.and(upper)
+    }
+
+    // All との組み合わせは消しておく（索引を選ぶときに余計な枝がないように）
+    pub fn and(self, other: Filter) -> Filter {
+        match (self, other) {
+            (Filter::All, f) | (f, Filter::All) => f,
+            (a, b) => Filter::And(Box::new(a), Box::new(b)),
+        }
+    }
+
+    pub fn or(self, other: Filter) -> Filter {
+        match (self, other) {
+            (Filter::All, _) | (_, Filter::All) => Filter::All,
+            (a, b) => Filter::Or(Box::new(a), Box::new(b)),
+        }
+    }
+}
+
+impl Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Filter {
+        match self {
+            Filter::Not(inner) => *inner,
+            f => Filter::Not(Box::new(f)),
+        }
+    }
+}
+
+// ====================================================================
+// 判定
+// ====================================================================
+
+impl Filter {
+    pub fn matches(&self, listing: &Listing) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Cmp(field, op, value) => {
+                compare(listing, *field, value).is_some_and(|ord| op.holds(ord))
+            }
+            Filter::In(field, values) => values
+                .iter()
+                .any(|v| compare(listing, *field, v).is_some_and(Ordering::is_eq)),
+            Filter::And(a, b) => a.matches(listing) && b.matches(listing),
+            Filter::Or(a, b) => a.matches(listing) || b.matches(listing),
+            Filter::Not(f) => !f.matches(listing),
+        }
+    }
+}
+
+// 商品の値 と value の比較。型が合わない（size と文字列など）ときは None → 条件に合わない
+fn compare(listing: &Listing, field: Field, value: &Value) -> Option<Ordering> {
+    match (field, value) {
+        (Field::Size, Value::Num(n)) => Some(listing.shoe.size.cmp(n)),
+        (Field::Price, Value::Num(n)) => Some(listing.price.cmp(n)),
+        (Field::Style, Value::Text(s)) => Some(cmp_text(&listing.shoe.style, s)),
+        (Field::Brand, Value::Text(s)) => Some(cmp_text(&listing.brand, s)),
+        _ => None,
+    }
+}
+
+// 大文字小文字を区別しない比較。1文字ずつ小文字にする
+//   str::to_lowercase は語末の Σ を ς にするなど文脈で変わるので使わない
+pub(crate) fn cmp_text(a: &str, b: &str) -> Ordering {
+    fold_chars(a).cmp(fold_chars(b))
+}
+
+// cmp_text と同じ小文字化をした文字列（style / brand の索引のキー）
+pub(crate) fn fold_text(s: &str) -> String {
+    fold_chars(s).collect()
+}
+
+fn fold_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+// 表示は DSL と同じ書き方（Query::parse に戻せる）
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::All => f.write_str("all"),
+            Filter::Cmp(field, op, value) => write!(f, "{field} {} {value}", op.symbol()),
+            Filter::In(field, values) => {
+                let values: Vec<String> = values.iter().map(Value::to_string).collect();
+                write!(f, "{field} in ({})", values.join(", "))
+            }
+            Filter::And(a, b) => write!(f, "({a} and {b})"),
+            Filter::Or(a, b) => write!(f, "({a} or {b})"),
+            Filter::Not(inner) => write!(f, "not {inner}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shoe;
+
+    fn listing(size: u32, style: &str, brand: &str, price: u32) -> Listing {
+        Listing::new(
+            Shoe {
+                size,
+                style: style.to_string(),
+            },
+            brand,
+            price,
+        )
+    }
+
+    #[test]
+    fn ranges_become_comparisons() {
+        assert_eq!(Filter::size(..), Filter::All);
+        assert_eq!(Filter::size(10..), Filter::cmp(Field::Size, Op::Ge, 10));
+        assert_eq!(
+            Filter::price(..=5000).to_string(),
+            "price <= 5000".to_string()
+        );
+        assert_eq!(Filter::size(9..12).to_string(), "(size >= 9 and size < 12)");
+    }
+
+    #[test]
+    fn matches_listings() {
+        let boot = listing(10, "Boot", "Acme", 12000);
+        assert!(Filter::style("boot").matches(&boot));
+        assert!(Filter::style_in(["sneaker", "BOOT"]).matches(&boot));
+        assert!(
+            Filter::size(10..=10)
+                .and(Filter::brand("acme"))
+                .matches(&boot)
+        );
+        assert!(!(!Filter::price(10000..)).matches(&boot));
+        assert!(Filter::size(..10).or(Filter::brand("Acme")).matches(&boot));
+        // 型が合わない比較はどれにも当てはまらない
+        assert!(!Filter::cmp(Field::Size, Op::Ne, "ten").matches(&boot));
+    }
+}
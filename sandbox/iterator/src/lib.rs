@@ -1,16 +1,22 @@
-#[derive(PartialEq, Debug)]
-struct Shoe {
-    size: u32,
-    style: String,
+// 靴の一覧をイテレータで絞り込む（shoes_in_size）
+//   複数の条件・並べ替え・ページ分けで探すときは catalog::Catalog
+
+pub mod catalog;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Shoe {
+    pub size: u32,
+    pub style: String,
 }
 
-fn shoes_in_size(shoes: Vec<Shoe>, shoe_size: u32) -> Vec<Shoe> {
+pub fn shoes_in_size(shoes: Vec<Shoe>, shoe_size: u32) -> Vec<Shoe> {
     shoes.into_iter().filter(|s| s.size == shoe_size).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::prop::{self, Strategy, ascii, ints, strings, vecs};
 
     #[test]
     fn filters_by_size() {
@@ -45,4 +51,26 @@ mod tests {
             ]
         );
     }
+
+    // 独自の構造体は、部品のタプルから作る（convert の2つ目の関数で部品に戻せるので縮められる）
+    fn shoes() -> impl Strategy<Value = Shoe> {
+        (ints(5u32..=15), strings(ascii(), 1..=8)).convert(
+            |(size, style)| Shoe { size, style },
+            |shoe| (shoe.size, shoe.style.clone()),
+        )
+    }
+
+    #[test]
+    fn filters_by_size_for_any_shoes() {
+        prop::check(
+            "shoes_in_size keeps exactly the shoes of that size, in order",
+            (vecs(shoes(), 0..=20), ints(5u32..=15)),
+            |(shoes, size)| {
+                let expected: Vec<Shoe> =
+                    shoes.iter().filter(|s| s.size == size).cloned().collect();
+                let in_size = shoes_in_size(shoes, size);
+                assert_eq!(in_size, expected);
+            },
+        );
+    }
 }
@@ -0,0 +1,270 @@
+// ====================================================================
+// minigrep のベンチマーク（cargo bench）
+// ====================================================================
+//
+// 外部クレート（criterion など）を使わない自前のハーネス
+//   - 生成したコーパスに対して各実装を何回も実行し、中央値を取る
+//   - 実装どうしの結果が一致するかも確認する（速くても間違っていたら意味がない）
+//   - 前回記録したベースラインと比べ、遅くなったものを REGRESSION として報告する
+//
+// 使い方:
+//   cargo bench --bench search                                — 計測してベースラインと比較
+//   cargo bench --bench search -- --save-baseline             — 今回の結果をベースラインとして保存
+//   cargo bench --bench search -- --threshold 10              — 10% 以上遅くなったら失敗（既定 25%）
+//   cargo bench --bench search -- --baseline path/to/file.txt — ベースラインの保存先を変える
+//   cargo bench --bench search -- japanese                    — 名前に japanese を含むものだけ
+//
+// ※ --bench search を付けないと、オプションが libtest のハーネスにも渡されてエラーになる
+//
+// ベースラインはマシンごとに違うので、既定では target/ の下に置く（コミットしない）
+
+use std::collections::HashMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use test_support::prop::Rng;
+
+// ====================================================================
+// コーパスの生成（シード固定の test_support::prop::Rng で毎回同じ内容にする）
+// ====================================================================
+
+fn pick<'a>(rng: &mut Rng, items: &[&'a str]) -> &'a str {
+    items[rng.below(items.len() as u128) as usize]
+}
+
+const ASCII_WORDS: &[&str] = &[
+    "the",
+    "rust",
+    "borrow",
+    "checker",
+    "lifetime",
+    "trait",
+    "generic",
+    "iterator",
+    "closure",
+    "ownership",
+    "memory",
+    "safe",
+    "fast",
+    "productive",
+    "thread",
+    "channel",
+    "mutex",
+];
+
+const JAPANESE_WORDS: &[&str] = &[
+    "所有権",
+    "借用",
+    "ライフタイム",
+    "トレイト",
+    "ジェネリクス",
+    "イテレータ",
+    "クロージャ",
+    "安全",
+    "高速",
+    "並行性",
+    "スレッド",
+    "チャネル",
+    "参照",
+    "スライス",
+    "は",
+    "が",
+    "を",
+];
+
+fn lines_of(rng: &mut Rng, words: &[&str], lines: usize, words_per_line: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..lines {
+        for i in 0..words_per_line {
+            if i > 0 {
+                text.push(' ');
+            }
+            text.push_str(pick(rng, words));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+struct Corpus {
+    name: &'static str,
+    query: &'static str,
+    files: Vec<String>, // 「小さいファイルがたくさん」を表すため複数持てる
+}
+
+fn corpora() -> Vec<Corpus> {
+    let mut rng = Rng::new(0x2024_0b5e_55ed);
+    vec![
+        Corpus {
+            name: "ascii",
+            query: "lifetime trait",
+            files: vec![lines_of(&mut rng, ASCII_WORDS, 50_000, 10)],
+        },
+        Corpus {
+            name: "japanese",
+            query: "ライフタイム トレイト",
+            files: vec![lines_of(&mut rng, JAPANESE_WORDS, 50_000, 10)],
+        },
+        Corpus {
+            name: "long_lines",
+            query: "checker mutex",
+            files: vec![lines_of(&mut rng, ASCII_WORDS, 200, 2_000)],
+        },
+        Corpus {
+            name: "many_small_files",
+            query: "channel safe",
+            files: (0..2_000)
+                .map(|_| lines_of(&mut rng, ASCII_WORDS, 20, 8))
+                .collect(),
+        },
+    ]
+}
+
+// ====================================================================
+// 計測
+// ====================================================================
+
+type SearchFn = for<'a> fn(&str, &'a str) -> Vec<&'a str>;
+
+const IMPLEMENTATIONS: &[(&str, SearchFn)] = &[
+    ("iterator", minigrep::search),
+    ("memchr", minigrep::fast::search),
+    ("case_insensitive", minigrep::search_case_insensitive),
+];
+
+const MIN_RUNS: usize = 5;
+const TARGET_TIME: Duration = Duration::from_millis(500);
+
+// TARGET_TIME に達するまで（最低 MIN_RUNS 回）実行し、1回あたりの中央値を返す
+fn measure(corpus: &Corpus, search: SearchFn) -> Duration {
+    let mut samples = Vec::new();
+    let started = Instant::now();
+    while samples.len() < MIN_RUNS || started.elapsed() < TARGET_TIME {
+        let t = Instant::now();
+        for file in &corpus.files {
+            black_box(search(black_box(corpus.query), black_box(file)));
+        }
+        samples.push(t.elapsed());
+    }
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+struct Options {
+    filter: Option<String>,
+    save: bool,
+    threshold: f64,
+    baseline: PathBuf,
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        filter: None,
+        save: false,
+        threshold: 25.0,
+        baseline: PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("minigrep-bench-baseline.txt"),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // cargo bench が渡してくる
+            "--bench" => {}
+            "--save-baseline" => options.save = true,
+            "--threshold" => {
+                options.threshold = args
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .expect("--threshold requires a percentage");
+            }
+            "--baseline" => {
+                options.baseline = PathBuf::from(args.next().expect("--baseline requires a path"));
+            }
+            filter => options.filter = Some(filter.to_string()),
+        }
+    }
+    options
+}
+
+// ベースラインは "名前 ナノ秒" を1行ずつ並べたテキスト
+fn load_baseline(path: &PathBuf) -> HashMap<String, u128> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (name, nanos) = line.split_once(' ')?;
+            Some((name.to_string(), nanos.parse().ok()?))
+        })
+        .collect()
+}
+
+fn main() {
+    let options = parse_args();
+    let baseline = load_baseline(&options.baseline);
+    let mut recorded = Vec::new();
+    let mut regressions = 0;
+
+    for corpus in corpora() {
+        // 各実装の結果が一致することを先に確かめる（大文字小文字無視は別物なので除く）
+        for file in &corpus.files {
+            assert_eq!(
+                minigrep::search(corpus.query, file),
+                minigrep::fast::search(corpus.query, file),
+                "implementations disagree on corpus {}",
+                corpus.name
+            );
+        }
+
+        let bytes: usize = corpus.files.iter().map(String::len).sum();
+
+        for &(implementation, search) in IMPLEMENTATIONS {
+            let name = format!("{}/{implementation}", corpus.name);
+            if options
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !name.contains(filter.as_str()))
+            {
+                continue;
+            }
+
+            let median = measure(&corpus, search);
+            let throughput = bytes as f64 / median.as_secs_f64() / (1024.0 * 1024.0);
+            let comparison = match baseline.get(&name) {
+                Some(&before) => {
+                    let change = (median.as_nanos() as f64 / before as f64 - 1.0) * 100.0;
+                    let flag = if change > options.threshold {
+                        regressions += 1;
+                        "  REGRESSION"
+                    } else {
+                        ""
+                    };
+                    format!("{change:+6.1}% vs baseline{flag}")
+                }
+                None => String::from("(no baseline)"),
+            };
+
+            println!("{name:<34} {median:>12.2?} {throughput:>9.1} MiB/s  {comparison}");
+            recorded.push(format!("{name} {}", median.as_nanos()));
+        }
+    }
+
+    if options.save {
+        if let Some(dir) = options.baseline.parent() {
+            fs::create_dir_all(dir).expect("failed to create baseline directory");
+        }
+        fs::write(&options.baseline, recorded.join("\n") + "\n").expect("failed to save baseline");
+        println!("\nbaseline saved to {}", options.baseline.display());
+    }
+
+    if regressions > 0 {
+        eprintln!(
+            "\n{regressions} benchmark(s) slower than baseline by more than {}%",
+            options.threshold
+        );
+        process::exit(1);
+    }
+}
@@ -0,0 +1,164 @@
+// ====================================================================
+// キャンセルとタイムアウト
+// ====================================================================
+//
+// 巨大なツリーやファイルの検索を途中で止めるための仕組み
+//
+// CancelToken:
+//   - Arc<AtomicBool> を共有する → clone して別スレッドに渡し、cancel() で止める
+//   - 期限（deadline）を持たせるとタイムアウトとしても働く
+//   - 検索側は interrupted() をときどき確認し、Some なら途中で切り上げる
+//
+// Mutex ではなく AtomicBool なのは、フラグ1つの読み書きにロックは重すぎるため
+// （16章: Arc<Mutex<T>> の代わりにアトミック型を使える場面）
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    // 同じフラグを共有したまま、期限だけを付け足したトークンを返す
+    // （呼び出し側のトークンで cancel() しても止まるし、期限でも止まる）
+    // 期限が Instant で表せないほど先（--timeout に巨大な値）なら、期限なしと同じ
+    pub fn with_timeout(&self, timeout: Duration) -> CancelToken {
+        let deadline = match (self.deadline, Instant::now().checked_add(timeout)) {
+            (Some(d), Some(new)) => Some(d.min(new)),
+            (d, new) => d.or(new),
+        };
+        CancelToken {
+            cancelled: Arc::clone(&self.cancelled),
+            deadline,
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    // 止めるべきなら理由を返す
+    pub fn interrupted(&self) -> Option<Interruption> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Some(Interruption::Cancelled)
+        } else if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            Some(Interruption::TimedOut)
+        } else {
+            None
+        }
+    }
+}
+
+// 検索が最後まで終わらなかった理由
+// run からはエラーとして返し、それまでに出力した結果が途中までであることを伝える
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interruption {
+    Cancelled,
+    TimedOut,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interruption::Cancelled => write!(f, "search cancelled; results above are partial"),
+            Interruption::TimedOut => write!(f, "search timed out; results above are partial"),
+        }
+    }
+}
+
+impl Error for Interruption {}
+
+// "500ms" / "30s" / "2m" / "10"（単位なしは秒）
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let (number, unit) = split_unit(value);
+    let n: u64 = number.parse().ok()?;
+    match unit {
+        "ms" => Some(Duration::from_millis(n)),
+        "" | "s" => Some(Duration::from_secs(n)),
+        "m" => Some(Duration::from_secs(n.checked_mul(60)?)),
+        _ => None,
+    }
+}
+
+// "512" / "64K" / "10M" / "1G"（1024 単位）
+pub fn parse_size(value: &str) -> Option<u64> {
+    let (number, unit) = split_unit(value);
+    let n: u64 = number.parse().ok()?;
+    let scale = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return None,
+    };
+    n.checked_mul(scale)
+}
+
+fn split_unit(value: &str) -> (&str, &str) {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn cancel_from_another_thread() {
+        let token = CancelToken::new();
+        assert_eq!(token.interrupted(), None);
+
+        let remote = token.clone();
+        thread::spawn(move || remote.cancel()).join().unwrap();
+
+        assert_eq!(token.interrupted(), Some(Interruption::Cancelled));
+    }
+
+    #[test]
+    fn timeout_shares_the_cancel_flag() {
+        let token = CancelToken::new();
+        let expired = token.with_timeout(Duration::ZERO);
+        assert_eq!(expired.interrupted(), Some(Interruption::TimedOut));
+
+        let long = token.with_timeout(Duration::from_secs(3600));
+        assert_eq!(long.interrupted(), None);
+        token.cancel();
+        assert_eq!(long.interrupted(), Some(Interruption::Cancelled));
+    }
+
+    #[test]
+    fn huge_timeout_means_no_deadline() {
+        let token = CancelToken::new();
+        let forever = token.with_timeout(parse_duration(&u64::MAX.to_string()).unwrap());
+        assert_eq!(forever.interrupted(), None);
+        // 外側に期限があれば、そちらが残る
+        let expired = token
+            .with_timeout(Duration::ZERO)
+            .with_timeout(Duration::MAX);
+        assert_eq!(expired.interrupted(), Some(Interruption::TimedOut));
+    }
+
+    #[test]
+    fn parses_durations_and_sizes() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("3"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("2h"), None);
+        assert_eq!(parse_size("10M"), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("64k"), Some(64 * 1024));
+        assert_eq!(parse_size("big"), None);
+    }
+}
@@ -0,0 +1,268 @@
+// ====================================================================
+// 大文字小文字を区別する検索の高速版（memchr + バッファ全体検索）
+// ====================================================================
+//
+// search は「1行ずつ contains」だが、こちらは:
+//   1. ファイル全体から query を直接探す（行に分けない）
+//   2. 見つかったら、その位置を含む行の先頭と末尾を探して1行として返す
+//   3. 次はその行の次から探す（同じ行を2回返さない）
+//
+// → マッチしない行は「行に分ける」処理すら行わない
+//
+// バイトを探す処理は SWAR（SIMD Within A Register）で書く:
+//   u64 に 8 バイトを詰め、8 バイト同時に「目的のバイトがあるか」を調べる
+//   （外部クレートの memchr は SSE2/AVX2 を使うが、考え方は同じ）
+//
+// 部分文字列の検索（find）は「珍しい2バイト」で候補を絞る:
+//   query の先頭バイトで探すと、"l" や日本語の先頭バイト 0xE3 のように
+//   よく出るバイトでは候補が多すぎて遅い（ベンチで std の contains に負けた）
+//   → query の中で出現頻度が低そうな2バイトを選び、両方が揃った位置だけを比べる
+//
+// 結果は search と完全に同じになる（lines() と同じく末尾の \r\n の \r を除く）
+//
+// ベンチの結果（cargo bench --bench search）から分かったこと:
+//   - ASCII の query で短い行が多いテキスト → こちらが速い（行ごとの contains の手間がない）
+//   - 日本語の query や非常に長い行 → std の contains（内部で SIMD を使う）のほうが速い
+//   → run では ASCII の query のときだけこちらを使う（search_cancellable を参照）
+
+use std::str::Lines;
+
+const LO: u64 = 0x0101_0101_0101_0101;
+const HI: u64 = 0x8080_8080_8080_8080;
+
+// x の中で 0 のバイトの最上位ビットを立てたマスク（8 バイト同時に判定するビット演算の定番）
+// 0 のバイトは必ず立つ。0 のバイトより上位に誤検出が混ざることはあるので、候補として使う
+fn zero_bytes(x: u64) -> u64 {
+    x.wrapping_sub(LO) & !x & HI
+}
+
+fn has_zero_byte(x: u64) -> bool {
+    zero_bytes(x) != 0
+}
+
+// リトルエンディアンで読む → マスクの下位ビットがスライスの前のほうに対応する
+fn load(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+// haystack の中で最初に needle が現れる位置
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let repeated = LO * needle as u64;
+    let mut i = 0;
+
+    // 8 バイトずつ見て、含まれていそうなブロックに当たったら止まる
+    while i + 8 <= haystack.len() {
+        if has_zero_byte(load(&haystack[i..i + 8]) ^ repeated) {
+            break;
+        }
+        i += 8;
+    }
+
+    haystack[i..]
+        .iter()
+        .position(|&b| b == needle)
+        .map(|p| i + p)
+}
+
+// haystack の中で最後に needle が現れる位置
+pub fn memrchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let repeated = LO * needle as u64;
+    let mut end = haystack.len();
+
+    while end >= 8 {
+        if has_zero_byte(load(&haystack[end - 8..end]) ^ repeated) {
+            break;
+        }
+        end -= 8;
+    }
+
+    haystack[..end].iter().rposition(|&b| b == needle)
+}
+
+// バイトの「よく出る度合い」のおおまかな順位（大きいほどよく出る）
+// 英文と日本語（UTF-8）のテキストを想定した経験則
+fn commonness(b: u8) -> u8 {
+    match b {
+        b' ' | b'e' | b't' | b'a' | b'o' | b'i' | b'n' => 250,
+        b's' | b'h' | b'r' | b'l' | b'd' | b'c' | b'u' => 240,
+        0xE3 => 235, // ひらがな・カタカナの UTF-8 先頭バイト
+        b'a'..=b'z' => 200,
+        0xE4..=0xE9 => 180, // 漢字の先頭バイト
+        0x81..=0x83 => 210, // ひらがな・カタカナの2バイト目
+        0x80..=0xBF => 150, // その他の UTF-8 継続バイト
+        b'A'..=b'Z' | b'0'..=b'9' => 140,
+        b'\n' | b'.' | b',' | b'_' | b'(' | b')' => 130,
+        _ => 50,
+    }
+}
+
+// needle の中で一番珍しいバイトと、その次に珍しいバイトの位置（小さいほうが先）
+fn rare_pair(needle: &[u8]) -> (usize, usize) {
+    let mut order: Vec<usize> = (0..needle.len()).collect();
+    order.sort_by_key(|&i| commonness(needle[i]));
+    let (a, b) = (order[0], order[1]);
+    (a.min(b), a.max(b))
+}
+
+// 部分文字列の検索
+pub fn find(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => return None,
+        1 => return memchr(needle[0], haystack),
+        _ => {}
+    }
+
+    let (i1, i2) = rare_pair(needle);
+    let (r1, r2) = (LO * needle[i1] as u64, LO * needle[i2] as u64);
+    let mut pos = 0; // 候補となる開始位置
+
+    // pos..pos+8 の各開始位置について、i1 と i2 のバイトが揃っているかを同時に調べる
+    while pos + i2 + 8 <= haystack.len() {
+        let mut candidates = zero_bytes(load(&haystack[pos + i1..]) ^ r1)
+            & zero_bytes(load(&haystack[pos + i2..]) ^ r2);
+        while candidates != 0 {
+            let start = pos + candidates.trailing_zeros() as usize / 8;
+            if haystack[start..].starts_with(needle) {
+                return Some(start);
+            }
+            candidates &= candidates - 1;
+        }
+        pos += 8;
+    }
+
+    // 残り（8 バイトに満たない部分）は1つずつ比べる
+    (pos..haystack.len()).find(|&start| haystack[start..].starts_with(needle))
+}
+
+// 見つけた行を1つずつ返すイテレータ（search_iter と同じく怠惰）
+//   next() を呼ぶたびに、前の行の続きから次のマッチまでだけを探す
+//   → take(n) すれば n 件目の行の後ろは読まれない（-m の早期終了）
+pub struct Matches<'a, 'q> {
+    query: &'q str,
+    contents: &'a str,
+    pos: usize, // 次に探し始める位置（末尾まで返したら contents.len() + 1）
+    // 空の query（全行マッチ）や改行を含む query は行ごとに contains（search と同じ判定）
+    lines: Option<Lines<'a>>,
+}
+
+pub fn search_iter<'a, 'q>(query: &'q str, contents: &'a str) -> Matches<'a, 'q> {
+    let by_line = query.is_empty() || query.contains(['\n', '\r']);
+    Matches {
+        query,
+        contents,
+        pos: 0,
+        lines: by_line.then(|| contents.lines()),
+    }
+}
+
+pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    search_iter(query, contents).collect()
+}
+
+impl<'a> Iterator for Matches<'a, '_> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if let Some(lines) = &mut self.lines {
+            let query = self.query;
+            return lines.find(|line| line.contains(query));
+        }
+
+        let bytes = self.contents.as_bytes();
+        let pos = self.pos;
+        let Some(found) = find(self.query.as_bytes(), bytes.get(pos..)?) else {
+            self.pos = bytes.len() + 1;
+            return None;
+        };
+        let at = pos + found;
+        let start = memrchr(b'\n', &bytes[pos..at]).map_or(pos, |i| pos + i + 1);
+        let end = memchr(b'\n', &bytes[at..]).map_or(bytes.len(), |i| at + i);
+        self.pos = end + 1;
+
+        // \n は1バイト文字なので、その前後は必ず char の境界
+        let line = &self.contents[start..end];
+        // lines() と同じく、\n の直前の \r だけを取り除く
+        Some(if end < bytes.len() {
+            line.strip_suffix('\r').unwrap_or(line)
+        } else {
+            line
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memchr_finds_first_and_last() {
+        let haystack = b"0123456789abcdef0123456789abcdef!";
+        assert_eq!(memchr(b'a', haystack), Some(10));
+        assert_eq!(memrchr(b'a', haystack), Some(26));
+        assert_eq!(memchr(b'!', haystack), Some(32));
+        assert_eq!(memchr(b'z', haystack), None);
+        assert_eq!(memrchr(b'0', haystack), Some(16));
+        assert_eq!(memrchr(b'z', b""), None);
+    }
+
+    #[test]
+    fn find_handles_partial_prefixes() {
+        assert_eq!(find(b"aab", b"aaaab"), Some(2));
+        assert_eq!(find(b"abc", b"ab"), None);
+        assert_eq!(find(b"", b"abc"), None);
+        assert_eq!(find(b"c", b"abc"), Some(2));
+
+        let haystack = "ライフタイムとトレイト、そしてライフタイム注釈".repeat(3);
+        for needle in ["トレイト", "イム注", "釈", "そして", "ない"] {
+            assert_eq!(
+                find(needle.as_bytes(), haystack.as_bytes()),
+                haystack.find(needle),
+                "needle = {needle}"
+            );
+        }
+    }
+
+    #[test]
+    fn stops_after_the_lines_it_returns() {
+        let contents = format!("rust one\nrust two\n{}", "rust\n".repeat(100_000));
+        let mut matches = search_iter("rust", &contents);
+        assert_eq!(matches.next(), Some("rust one"));
+        // 1件目の行の直後までしか進んでいない
+        assert_eq!(matches.pos, "rust one\n".len());
+        assert_eq!(matches.next(), Some("rust two"));
+        assert_eq!(matches.pos, "rust one\nrust two\n".len());
+
+        let mut matches = search_iter("zzz", "abc");
+        assert_eq!(matches.next(), None);
+        assert_eq!(matches.next(), None);
+    }
+
+    #[test]
+    fn agrees_with_line_search() {
+        let contents = "\
+Rust:\r
+safe, fast, productive.
+Pick three.\r
+\r
+Duct tape. duct duct
+所有権とライフタイム
+最後の行\r";
+        for query in [
+            "duct",
+            "Rust",
+            "\r",
+            "ライフ",
+            "three.",
+            "e",
+            "行\r",
+            "zzz",
+            "",
+        ] {
+            assert_eq!(
+                search(query, contents),
+                crate::search(query, contents),
+                "query = {query:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,418 @@
+// ====================================================================
+// ファイルの種類とグロブによる絞り込み（-t / -T / --glob）
+// ====================================================================
+//
+//   -t rust            — Rust のファイルだけ
+//   -T markdown        — Markdown 以外
+//   --glob '*.toml'    — グロブに一致するファイルだけ
+//   --glob '!vendor/**' — ! で始まるグロブは除外
+//   --type-add 'web:*.vue' — 種類を追加（既存の種類にも足せる）
+//   --type-list        — 種類の一覧を表示
+//
+// 絞り込みは walk がファイルを集める段階で行う（ファイルを開く前）
+//   例外: 拡張子のないファイルで、シバン（#!/usr/bin/env python など）で
+//         種類を判定する必要があるときだけ、先頭行を読む
+//
+// グロブの書き方:
+//   *   — "/" 以外の任意の文字列
+//   ?   — "/" 以外の任意の1文字
+//   **  — "/" を含む任意の文字列（"**/" は0個以上のディレクトリ）
+//   [abc] / [a-z] / [!abc] — 文字クラス
+//   "/" を含まないグロブはファイル名だけに、含むものは root からの相対パスに当てはめる
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// ====================================================================
+// Glob
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(char),
+    Any,     // *
+    One,     // ?
+    AnyPath, // **
+    AnyDirs, // **/
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glob {
+    pattern: String,
+    tokens: Vec<Token>,
+    basename_only: bool,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Glob, &'static str> {
+        let basename_only = !pattern.contains('/');
+        let body = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        let mut tokens = Vec::new();
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        Token::AnyDirs
+                    } else {
+                        Token::AnyPath
+                    }
+                }
+                '*' => Token::Any,
+                '?' => Token::One,
+                '[' => {
+                    let negated = chars.next_if(|&c| c == '!' || c == '^').is_some();
+                    let mut ranges = Vec::new();
+                    loop {
+                        let start = match chars.next() {
+                            Some(']') if !ranges.is_empty() => break,
+                            Some(c) => c,
+                            None => return Err("unclosed character class in glob"),
+                        };
+                        let end = if chars.next_if_eq(&'-').is_some() {
+                            match chars.next() {
+                                Some(']') | None => return Err("invalid range in glob"),
+                                Some(end) => end,
+                            }
+                        } else {
+                            start
+                        };
+                        ranges.push((start, end));
+                    }
+                    Token::Class { ranges, negated }
+                }
+                '\\' => Token::Literal(chars.next().ok_or("trailing backslash in glob")?),
+                c => Token::Literal(c),
+            };
+            tokens.push(token);
+        }
+
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            tokens,
+            basename_only,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    // relative は root からの相対パス（区切りは "/"）
+    pub fn is_match(&self, relative: &str) -> bool {
+        let text = if self.basename_only {
+            relative.rsplit('/').next().unwrap_or(relative)
+        } else {
+            relative
+        };
+        let text: Vec<char> = text.chars().collect();
+        matches(&self.tokens, &text)
+    }
+
+    // "vendor/**" のようなグロブなら、ディレクトリ vendor ごと飛ばしてよい
+    fn excludes_dir(&self, relative_dir: &str) -> bool {
+        match self.tokens.split_last() {
+            Some((Token::AnyPath, rest)) if rest.last() == Some(&Token::Literal('/')) => {
+                let prefix = &rest[..rest.len() - 1];
+                let text: Vec<char> = relative_dir.chars().collect();
+                matches(prefix, &text)
+            }
+            _ => false,
+        }
+    }
+}
+
+// バックトラックで1文字ずつ照合する（パスは短いので十分速い）
+fn matches(tokens: &[Token], text: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return text.is_empty();
+    };
+    match token {
+        Token::Literal(c) => text.first() == Some(c) && matches(rest, &text[1..]),
+        Token::One => text.first().is_some_and(|&c| c != '/') && matches(rest, &text[1..]),
+        Token::Class { ranges, negated } => {
+            text.first().is_some_and(|&c| {
+                c != '/' && ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated
+            }) && matches(rest, &text[1..])
+        }
+        Token::Any => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| matches(rest, &text[i..])),
+        Token::AnyPath => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+        // 空、または "/" の直後から続きを照合する
+        Token::AnyDirs => {
+            matches(rest, text)
+                || (0..text.len())
+                    .filter(|&i| text[i] == '/')
+                    .any(|i| matches(rest, &text[i + 1..]))
+        }
+    }
+}
+
+// ====================================================================
+// ファイルの種類
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileType {
+    pub name: String,
+    pub globs: Vec<Glob>,
+    pub shebangs: Vec<String>, // 拡張子がないときに先頭行の #! で判定するインタプリタ名
+}
+
+// (名前, グロブ, シバンのインタプリタ)
+const BUILTIN_TYPES: &[(&str, &[&str], &[&str])] = &[
+    ("c", &["*.c", "*.h"], &[]),
+    ("csv", &["*.csv", "*.tsv"], &[]),
+    ("go", &["*.go"], &[]),
+    ("html", &["*.html", "*.htm"], &[]),
+    ("js", &["*.js", "*.mjs", "*.cjs"], &["node"]),
+    ("json", &["*.json", "*.jsonl"], &[]),
+    ("markdown", &["*.md", "*.markdown"], &[]),
+    ("py", &["*.py"], &["python", "python3"]),
+    ("rust", &["*.rs"], &[]),
+    ("sh", &["*.sh", "*.bash", "*.zsh"], &["sh", "bash", "zsh"]),
+    ("toml", &["*.toml", "Cargo.lock"], &[]),
+    ("ts", &["*.ts", "*.tsx"], &[]),
+    ("txt", &["*.txt"], &[]),
+    ("yaml", &["*.yml", "*.yaml"], &[]),
+];
+
+pub fn builtin_types() -> Vec<FileType> {
+    BUILTIN_TYPES
+        .iter()
+        .map(|&(name, globs, shebangs)| FileType {
+            name: name.to_string(),
+            globs: globs.iter().map(|g| Glob::new(g).unwrap()).collect(),
+            shebangs: shebangs.iter().map(|s| s.to_string()).collect(),
+        })
+        .collect()
+}
+
+// ====================================================================
+// FileFilter — walk に渡す絞り込み条件
+// ====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileFilter {
+    types: Vec<FileType>,
+    select: Vec<String>, // -t
+    negate: Vec<String>, // -T
+    include: Vec<Glob>,  // --glob
+    exclude: Vec<Glob>,  // --glob '!...'
+}
+
+impl Default for FileFilter {
+    fn default() -> Self {
+        FileFilter {
+            types: builtin_types(),
+            select: Vec::new(),
+            negate: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl FileFilter {
+    pub fn types(&self) -> &[FileType] {
+        &self.types
+    }
+
+    // "name:glob" 形式で種類を追加する
+    pub fn add_type(&mut self, definition: &str) -> Result<(), &'static str> {
+        let (name, glob) = definition
+            .split_once(':')
+            .filter(|(name, glob)| !name.is_empty() && !glob.is_empty())
+            .ok_or("--type-add must look like name:glob")?;
+        let glob = Glob::new(glob)?;
+        match self.types.iter_mut().find(|t| t.name == name) {
+            Some(existing) => existing.globs.push(glob),
+            None => {
+                self.types.push(FileType {
+                    name: name.to_string(),
+                    globs: vec![glob],
+                    shebangs: Vec::new(),
+                });
+                self.types.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn select_type(&mut self, name: &str) {
+        self.select.push(name.to_string());
+    }
+
+    pub fn negate_type(&mut self, name: &str) {
+        self.negate.push(name.to_string());
+    }
+
+    pub fn add_glob(&mut self, pattern: &str) -> Result<(), &'static str> {
+        match pattern.strip_prefix('!') {
+            Some(negated) => self.exclude.push(Glob::new(negated)?),
+            None => self.include.push(Glob::new(pattern)?),
+        }
+        Ok(())
+    }
+
+    // -t / -T に未知の種類がないか（--type-add をすべて読んだ後に確認する）
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let known = |name: &String| self.types.iter().any(|t| &t.name == name);
+        if self.select.iter().chain(&self.negate).all(known) {
+            Ok(())
+        } else {
+            Err("Unknown file type (see --type-list)")
+        }
+    }
+
+    // ディレクトリごと飛ばせるか（--glob '!vendor/**' など）
+    pub fn allows_dir(&self, relative: &str) -> bool {
+        !self.exclude.iter().any(|g| g.excludes_dir(relative))
+    }
+
+    // relative は root からの相対パス、path は実際に開けるパス（シバン判定用）
+    pub fn allows_file(&self, relative: &str, path: &Path) -> bool {
+        if self.exclude.iter().any(|g| g.is_match(relative)) {
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|g| g.is_match(relative)) {
+            return false;
+        }
+        if self.select.is_empty() && self.negate.is_empty() {
+            return true;
+        }
+
+        // シバンは必要になったときに1回だけ読む
+        let mut shebang = None;
+        let mut is_type = |name: &String| {
+            let Some(file_type) = self.types.iter().find(|t| &t.name == name) else {
+                return false;
+            };
+            if file_type.globs.iter().any(|g| g.is_match(relative)) {
+                return true;
+            }
+            if file_type.shebangs.is_empty() || path.extension().is_some() {
+                return false;
+            }
+            let interpreter = shebang.get_or_insert_with(|| read_shebang(path));
+            interpreter
+                .as_ref()
+                .is_some_and(|i| file_type.shebangs.contains(i))
+        };
+
+        if self.negate.iter().any(&mut is_type) {
+            return false;
+        }
+        self.select.is_empty() || self.select.iter().any(is_type)
+    }
+}
+
+// "#!/usr/bin/env python3" → "python3"、"#!/bin/sh -e" → "sh"
+fn read_shebang(path: &Path) -> Option<String> {
+    let mut head = [0; 128];
+    let n = File::open(path).ok()?.read(&mut head).ok()?;
+    let first_line = head[..n].split(|&b| b == b'\n').next()?;
+    let line = std::str::from_utf8(first_line).ok()?.strip_prefix("#!")?;
+
+    let mut words = line.split_whitespace();
+    let program = words.next()?.rsplit('/').next()?;
+    let interpreter = if program == "env" {
+        words.find(|w| !w.starts_with('-'))?
+    } else {
+        program
+    };
+    Some(interpreter.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn glob(pattern: &str) -> Glob {
+        Glob::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn star_stays_within_a_segment() {
+        assert!(glob("*.rs").is_match("src/lib.rs"));
+        assert!(glob("src/*.rs").is_match("src/lib.rs"));
+        assert!(!glob("src/*.rs").is_match("src/records/csv.rs"));
+        assert!(glob("?.txt").is_match("a.txt"));
+        assert!(!glob("?.txt").is_match("ab.txt"));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        assert!(glob("src/**/*.rs").is_match("src/lib.rs"));
+        assert!(glob("src/**/*.rs").is_match("src/records/csv.rs"));
+        assert!(glob("vendor/**").is_match("vendor/a/b.rs"));
+        assert!(!glob("vendor/**").is_match("src/vendor.rs"));
+        assert!(glob("**/tests/*.rs").is_match("tests/a.rs"));
+        assert!(glob("/Cargo.toml").is_match("Cargo.toml"));
+    }
+
+    #[test]
+    fn character_classes() {
+        assert!(glob("step[0-9].rs").is_match("step7.rs"));
+        assert!(!glob("step[!0-9].rs").is_match("step7.rs"));
+        assert!(glob("[ab]*").is_match("b.md"));
+        assert!(Glob::new("[abc").is_err());
+    }
+
+    #[test]
+    fn filters_by_type_and_glob() {
+        let mut filter = FileFilter::default();
+        filter.select_type("rust");
+        filter.add_glob("!vendor/**").unwrap();
+        assert!(filter.allows_file("src/lib.rs", Path::new("src/lib.rs")));
+        assert!(!filter.allows_file("README.md", Path::new("README.md")));
+        assert!(!filter.allows_file("vendor/x.rs", Path::new("vendor/x.rs")));
+        assert!(!filter.allows_dir("vendor"));
+        assert!(filter.allows_dir("src"));
+
+        let mut filter = FileFilter::default();
+        filter.negate_type("markdown");
+        assert!(filter.allows_file("notes.txt", Path::new("notes.txt")));
+        assert!(!filter.allows_file("notes.md", Path::new("notes.md")));
+    }
+
+    #[test]
+    fn type_add_extends_and_validates() {
+        let mut filter = FileFilter::default();
+        filter.add_type("web:*.vue").unwrap();
+        filter.add_type("rust:*.rs.in").unwrap();
+        filter.select_type("web");
+        assert!(filter.validate().is_ok());
+        assert!(filter.allows_file("App.vue", Path::new("App.vue")));
+
+        filter.select_type("cobol");
+        assert!(filter.validate().is_err());
+        assert!(filter.add_type("no-colon").is_err());
+    }
+
+    #[test]
+    fn detects_shebang_for_extensionless_files() {
+        let dir = std::env::temp_dir().join(format!("minigrep-shebang-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("deploy");
+        fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+
+        let mut filter = FileFilter::default();
+        filter.select_type("py");
+        let allowed = filter.allows_file("deploy", &script);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(allowed);
+        assert_eq!(read_shebang(Path::new("/nonexistent")), None);
+    }
+}
@@ -0,0 +1,586 @@
+// ====================================================================
+// Git を考慮した検索範囲（--git-tracked / --git-modified / --git-since）
+// ====================================================================
+//
+// 「このブランチで変えたファイルだけ」「追跡中のファイルだけ」を検索したい
+//   → git コマンドを呼び出さず、.git の中身を直接読んで対象のファイルを決める
+//
+//   --git-tracked     — インデックス（.git/index）に載っているファイル
+//   --git-modified    — HEAD のコミットと作業ツリーで中身が違う追跡中のファイル
+//                       （ステージ済み・未ステージのどちらの変更も含む）
+//   --git-since <rev> — <rev> のコミットと作業ツリーで中身が違う追跡中のファイル
+//                       <rev> は HEAD / ブランチ名 / タグ名 / ID（短縮も可）と ~N, ^N
+//
+// 未追跡のファイル（git add していないもの）はどのスコープにも入らない
+//
+// 読み込むもの:
+//   index  — 追跡中のファイルの一覧（git/index.rs）
+//   refs   — HEAD, refs/heads/*, refs/tags/*, packed-refs
+//   objects — コミット → ツリー → ファイルの ID（git/odb.rs、zlib は git/inflate.rs）
+//   作業ツリーのファイルは "blob <サイズ>\0<中身>" の SHA-1（git/sha1.rs）で比べる
+
+pub mod index;
+pub mod inflate;
+pub mod odb;
+pub mod sha1;
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use index::IndexEntry;
+use odb::{Kind, Odb};
+use sha1::{ObjectId, blob_id};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitScope {
+    Tracked,
+    Modified,
+    Since(String),
+}
+
+#[derive(Debug)]
+pub enum GitError {
+    NotARepository(PathBuf),
+    UnknownRevision(String),
+    Corrupt(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::NotARepository(path) => write!(
+                f,
+                "not a git repository (or any of the parent directories): {}",
+                path.display()
+            ),
+            GitError::UnknownRevision(rev) => write!(f, "unknown revision: {rev}"),
+            GitError::Corrupt(message) => write!(f, "cannot read git data: {message}"),
+            GitError::Io(e) => write!(f, "cannot read git data: {e}"),
+        }
+    }
+}
+
+impl Error for GitError {}
+
+impl From<io::Error> for GitError {
+    fn from(e: io::Error) -> GitError {
+        GitError::Io(e)
+    }
+}
+
+// ====================================================================
+// リポジトリ
+// ====================================================================
+
+pub struct Repository {
+    pub workdir: PathBuf, // 作業ツリーのルート（canonicalize 済み）
+    git_dir: PathBuf,     // HEAD と index がある場所
+    common_dir: PathBuf,  // refs と objects がある場所（git worktree では git_dir と別）
+    odb: Odb,
+}
+
+impl Repository {
+    // start から親へたどって .git を探す（ファイルを渡された場合はその親から）
+    pub fn discover(start: &Path) -> Result<Repository, GitError> {
+        let not_a_repo = || GitError::NotARepository(start.to_path_buf());
+        let start = fs::canonicalize(start).map_err(|_| not_a_repo())?;
+
+        for dir in start.ancestors() {
+            let dot_git = dir.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else if dot_git.is_file() {
+                // git worktree / サブモジュール: ".git" ファイルに "gitdir: <パス>" と書いてある
+                let text = fs::read_to_string(&dot_git)?;
+                let target = text
+                    .strip_prefix("gitdir:")
+                    .ok_or_else(|| GitError::Corrupt(format!("{}: no gitdir", dot_git.display())))?
+                    .trim();
+                dir.join(target)
+            } else {
+                continue;
+            };
+
+            let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common) => git_dir.join(common.trim()),
+                Err(_) => git_dir.clone(),
+            };
+            let odb = Odb::open(&common_dir.join("objects"))?;
+            return Ok(Repository {
+                workdir: dir.to_path_buf(),
+                git_dir,
+                common_dir,
+                odb,
+            });
+        }
+        Err(not_a_repo())
+    }
+
+    pub fn index(&self) -> Result<Vec<IndexEntry>, GitError> {
+        match fs::read(self.git_dir.join("index")) {
+            Ok(data) => index::parse(&data),
+            // まだ何も git add していないリポジトリ
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // --------------------------------------------------------------------
+    // リビジョンの解決: "main~2" → コミットの ID
+    // --------------------------------------------------------------------
+
+    pub fn resolve(&self, rev: &str) -> Result<ObjectId, GitError> {
+        let unknown = || GitError::UnknownRevision(rev.to_string());
+
+        // 末尾の ~N / ^N を後ろから切り出す（"HEAD~2^2" なら HEAD → ~2 → ^2）
+        let name_end = rev.find(['~', '^']).unwrap_or(rev.len());
+        let (name, mut suffix) = rev.split_at(name_end);
+        let mut id = self.resolve_name(name)?.ok_or_else(unknown)?;
+
+        while !suffix.is_empty() {
+            // ~ と ^ 以外（"HEAD~1x" の x など）は受け付けない
+            let (first_parent, after) = if let Some(after) = suffix.strip_prefix('~') {
+                (true, after)
+            } else if let Some(after) = suffix.strip_prefix('^') {
+                (false, after)
+            } else {
+                return Err(unknown());
+            };
+            suffix = after;
+            let digits = suffix
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(suffix.len());
+            let n: usize = if digits == 0 {
+                1
+            } else {
+                suffix[..digits].parse().map_err(|_| unknown())?
+            };
+            suffix = &suffix[digits..];
+
+            id = self.peel_to_commit(id)?;
+            if first_parent {
+                // ~N: 1番目の親を N 回たどる
+                for _ in 0..n {
+                    id = *self.parents(&id)?.first().ok_or_else(unknown)?;
+                }
+            } else if n > 0 {
+                // ^N: N 番目の親（^0 はコミット自身）
+                id = *self.parents(&id)?.get(n - 1).ok_or_else(unknown)?;
+            }
+        }
+        self.peel_to_commit(id)
+    }
+
+    fn resolve_name(&self, name: &str) -> Result<Option<ObjectId>, GitError> {
+        // git rev-parse と同じ順番で探す
+        for candidate in [
+            name.to_string(),
+            format!("refs/{name}"),
+            format!("refs/tags/{name}"),
+            format!("refs/heads/{name}"),
+            format!("refs/remotes/{name}"),
+            format!("refs/remotes/{name}/HEAD"),
+        ] {
+            if let Some(id) = self.read_ref(&candidate, 0)? {
+                return Ok(Some(id));
+            }
+        }
+
+        if name.len() >= 4 && name.len() <= 40 && name.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return self.odb.find_prefix(&name.to_ascii_lowercase());
+        }
+        Ok(None)
+    }
+
+    // ref ファイル（"ref: refs/heads/main" ならたどる）→ なければ packed-refs
+    fn read_ref(&self, name: &str, depth: usize) -> Result<Option<ObjectId>, GitError> {
+        if depth > 5 || name.is_empty() || name.contains("..") {
+            return Ok(None);
+        }
+        // HEAD などは git_dir、refs/ は共通ディレクトリ
+        let base = if name.starts_with("refs/") {
+            &self.common_dir
+        } else {
+            &self.git_dir
+        };
+        let path = base.join(name);
+        if path.is_file() {
+            let text = fs::read_to_string(&path)?;
+            let text = text.trim();
+            if let Some(target) = text.strip_prefix("ref:") {
+                return self.read_ref(target.trim(), depth + 1);
+            }
+            return Ok(sha1::from_hex(text));
+        }
+
+        // packed-refs: "<ID> <名前>" の行（"#" はヘッダ、"^" は直前の注釈付きタグの中身）
+        match fs::read_to_string(self.common_dir.join("packed-refs")) {
+            Ok(text) => Ok(text
+                .lines()
+                .filter(|line| !line.starts_with(['#', '^']))
+                .filter_map(|line| line.split_once(' '))
+                .find(|(_, ref_name)| *ref_name == name)
+                .and_then(|(hex, _)| sha1::from_hex(hex))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // 注釈付きタグはコミットを指すまでたどる
+    fn peel_to_commit(&self, mut id: ObjectId) -> Result<ObjectId, GitError> {
+        loop {
+            let object = self.odb.read(&id)?;
+            match object.kind {
+                Kind::Commit => return Ok(id),
+                Kind::Tag => {
+                    id = header_ids(&object.data, "object")
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| GitError::Corrupt("tag without object".to_string()))?;
+                }
+                _ => {
+                    return Err(GitError::UnknownRevision(format!(
+                        "{} is not a commit",
+                        sha1::to_hex(&id)
+                    )));
+                }
+            }
+        }
+    }
+
+    fn parents(&self, commit: &ObjectId) -> Result<Vec<ObjectId>, GitError> {
+        Ok(header_ids(&self.odb.read(commit)?.data, "parent"))
+    }
+
+    // --------------------------------------------------------------------
+    // コミットのツリー → "パス → ID" の表
+    // --------------------------------------------------------------------
+
+    pub fn commit_files(&self, commit: &ObjectId) -> Result<HashMap<String, ObjectId>, GitError> {
+        let tree = header_ids(&self.odb.read(commit)?.data, "tree")
+            .into_iter()
+            .next()
+            .ok_or_else(|| GitError::Corrupt("commit without tree".to_string()))?;
+        let mut files = HashMap::new();
+        self.collect_tree(&tree, "", &mut files)?;
+        Ok(files)
+    }
+
+    // ツリーのエントリ: "<mode> <名前>\0<20 バイトの ID>" の繰り返し
+    fn collect_tree(
+        &self,
+        tree: &ObjectId,
+        prefix: &str,
+        files: &mut HashMap<String, ObjectId>,
+    ) -> Result<(), GitError> {
+        let data = self.odb.read(tree)?.data;
+        let mut rest = data.as_slice();
+        while !rest.is_empty() {
+            let corrupt = || GitError::Corrupt("malformed tree entry".to_string());
+            // 空白は名前の手前（NUL より前）にしかない
+            let nul = rest.iter().position(|&b| b == 0).ok_or_else(corrupt)?;
+            let space = rest[..nul]
+                .iter()
+                .position(|&b| b == b' ')
+                .ok_or_else(corrupt)?;
+            let id: ObjectId = rest
+                .get(nul + 1..nul + 21)
+                .ok_or_else(corrupt)?
+                .try_into()
+                .unwrap();
+            let mode = &rest[..space];
+            let name = String::from_utf8_lossy(&rest[space + 1..nul]);
+            let path = format!("{prefix}{name}");
+            match mode {
+                b"40000" => self.collect_tree(&id, &format!("{path}/"), files)?,
+                b"160000" => {} // サブモジュールの中は別のリポジトリ
+                _ => {
+                    files.insert(path, id);
+                }
+            }
+            rest = &rest[nul + 21..];
+        }
+        Ok(())
+    }
+}
+
+// コミットやタグのヘッダから "<key> <ID>" の行を集める（空行から先はメッセージ）
+fn header_ids(data: &[u8], key: &str) -> Vec<ObjectId> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.strip_prefix(key)?.strip_prefix(' '))
+        .filter_map(sha1::from_hex)
+        .collect()
+}
+
+// ====================================================================
+// スコープ → 検索してよいファイルの集合
+// ====================================================================
+//
+// 返すのは「作業ツリーのルート（canonicalize 済み）+ インデックスのパス」
+// 呼び出し側は walk で集めたファイルを canonicalize して、この集合に入っているかを見る
+
+pub fn scope_files(start: &Path, scope: &GitScope) -> Result<HashSet<PathBuf>, GitError> {
+    let repo = Repository::discover(start)?;
+    let entries = repo.index()?;
+
+    let base = match scope {
+        GitScope::Tracked => {
+            return Ok(entries
+                .iter()
+                .filter(|entry| entry.is_regular_file())
+                .map(|entry| repo.workdir.join(&entry.path))
+                .collect());
+        }
+        // コミットがまだない HEAD は「空のツリー」と比べる（追跡中のファイルはすべて変更扱い）
+        GitScope::Modified => match repo.resolve("HEAD") {
+            Ok(head) => repo.commit_files(&head)?,
+            Err(GitError::UnknownRevision(_)) => HashMap::new(),
+            Err(e) => return Err(e),
+        },
+        GitScope::Since(rev) => repo.commit_files(&repo.resolve(rev)?)?,
+    };
+
+    let index_mtime = fs::metadata(repo.git_dir.join("index"))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+
+    let mut changed = HashSet::new();
+    for entry in entries.iter().filter(|entry| entry.is_regular_file()) {
+        let path = repo.workdir.join(&entry.path);
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue; // 作業ツリーで削除されている → 検索できない
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        // git と同じ「stat の一致」による省略:
+        //   サイズと mtime がインデックスの記録と同じなら、中身はインデックスの ID のまま
+        //   ただし index を書いたのと同じ秒に変更されたファイルは見分けられないので、ハッシュする
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+        let stat_clean = match (mtime, index_mtime) {
+            (Some(mtime), Some(index_mtime)) => {
+                u64::from(entry.size) == metadata.len()
+                    && (mtime.as_secs() as u32, mtime.subsec_nanos()) == entry.mtime
+                    && mtime.as_secs() < index_mtime.as_secs()
+            }
+            _ => false,
+        };
+        let worktree_id = if stat_clean {
+            entry.id
+        } else {
+            blob_id(&fs::read(&path)?)
+        };
+
+        if base.get(&entry.path) != Some(&worktree_id) {
+            changed.insert(path);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    // テスト用のリポジトリは git コマンドで作る（読む側は git コマンドを使わない）
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args([
+                "-c",
+                "user.name=minigrep",
+                "-c",
+                "user.email=minigrep@example.com",
+                "-c",
+                "commit.gpgsign=false",
+                "-c",
+                "init.defaultBranch=main",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("git must be installed to run these tests");
+        assert!(output.status.success(), "git {args:?} failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn names(repo_dir: &Path, files: &HashSet<PathBuf>) -> Vec<String> {
+        let root = repo_dir.canonicalize().unwrap();
+        let mut names: Vec<String> = files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    // 1つ目のコミット: a.txt, b.txt, src/c.rs（タグ v1 と注釈付きタグ v1-note）
+    // 2つ目のコミット: a.txt を変更
+    // 作業ツリー: b.txt を変更（未ステージ）、d.txt を追加（ステージ済み）、e.txt（未追跡）
+    fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("minigrep-git-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        git(&dir, &["init", "-q"]);
+
+        // 長めの中身にして、パック時にデルタ・動的ハフマンが使われるようにする
+        let long: String = (0..200)
+            .map(|i| format!("line {i}: 所有権と借用\n"))
+            .collect();
+        fs::write(dir.join("a.txt"), &long).unwrap();
+        fs::write(dir.join("b.txt"), "bee\n").unwrap();
+        fs::write(dir.join("src/c.rs"), "fn main() {}\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "first"]);
+        git(&dir, &["tag", "v1"]);
+        git(&dir, &["tag", "-a", "v1-note", "-m", "annotated"]);
+
+        fs::write(dir.join("a.txt"), format!("{long}line 200: ライフタイム\n")).unwrap();
+        git(&dir, &["commit", "-q", "-am", "second"]);
+
+        fs::write(dir.join("b.txt"), "bumblebee\n").unwrap();
+        fs::write(dir.join("d.txt"), "dee\n").unwrap();
+        git(&dir, &["add", "d.txt"]);
+        fs::write(dir.join("e.txt"), "untracked\n").unwrap();
+        dir
+    }
+
+    fn check_scopes(dir: &Path) {
+        let scope = |scope: GitScope| names(dir, &scope_files(dir, &scope).unwrap());
+
+        assert_eq!(
+            scope(GitScope::Tracked),
+            ["a.txt", "b.txt", "d.txt", "src/c.rs"]
+        );
+        assert_eq!(scope(GitScope::Modified), ["b.txt", "d.txt"]);
+        for rev in ["v1", "v1-note", "HEAD~1", "HEAD^", "main~1", "refs/tags/v1"] {
+            assert_eq!(
+                scope(GitScope::Since(rev.to_string())),
+                ["a.txt", "b.txt", "d.txt"],
+                "rev {rev}"
+            );
+        }
+        assert_eq!(
+            scope(GitScope::Since("HEAD".to_string())),
+            ["b.txt", "d.txt"]
+        );
+
+        // 短縮した ID でも指定できる
+        let first = git(dir, &["rev-parse", "v1"]);
+        assert_eq!(
+            scope(GitScope::Since(first[..8].to_string())),
+            ["a.txt", "b.txt", "d.txt"]
+        );
+
+        // サブディレクトリから始めてもリポジトリのルートを見つける
+        let from_src = scope_files(&dir.join("src"), &GitScope::Tracked).unwrap();
+        assert_eq!(from_src.len(), 4);
+
+        assert!(matches!(
+            scope_files(dir, &GitScope::Since("no-such-branch".to_string())),
+            Err(GitError::UnknownRevision(_))
+        ));
+        assert!(scope_files(dir, &GitScope::Since("HEAD~9".to_string())).is_err());
+        // ~ / ^ の後ろに余計な文字（ASCII 以外も）
+        for rev in ["HEAD~1x", "HEAD~1あ", "HEAD^あ", "HEAD~あ"] {
+            assert!(
+                matches!(
+                    scope_files(dir, &GitScope::Since(rev.to_string())),
+                    Err(GitError::UnknownRevision(_))
+                ),
+                "rev {rev}"
+            );
+        }
+    }
+
+    #[test]
+    fn scopes_from_loose_objects() {
+        let dir = fixture("loose");
+        let repo = Repository::discover(&dir).unwrap();
+        assert_eq!(
+            sha1::to_hex(&repo.resolve("HEAD").unwrap()),
+            git(&dir, &["rev-parse", "HEAD"])
+        );
+        check_scopes(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn scopes_from_packed_objects_and_refs() {
+        // gc でオブジェクトをパックにまとめ（デルタあり）、refs を packed-refs に移す
+        // index v4（パスの前方圧縮）も試す
+        let dir = fixture("packed");
+        git(&dir, &["gc", "-q", "--aggressive"]);
+        git(&dir, &["update-index", "--index-version", "4"]);
+        assert!(!dir.join(".git/refs/tags/v1").exists());
+        check_scopes(&dir);
+
+        // パックから読んだ a.txt が元の中身と同じ
+        let repo = Repository::discover(&dir).unwrap();
+        let files = repo.commit_files(&repo.resolve("HEAD").unwrap()).unwrap();
+        let blob = repo.odb.read(&files["a.txt"]).unwrap();
+        assert_eq!(blob.data, fs::read(dir.join("a.txt")).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_tree_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("minigrep-git-tree-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q"]);
+        let repo = Repository::discover(&dir).unwrap();
+
+        let collect = |data: &[u8]| {
+            fs::write(dir.join("tree"), data).unwrap();
+            let hex = git(
+                &dir,
+                &["hash-object", "-t", "tree", "--literally", "-w", "tree"],
+            );
+            let id = sha1::from_hex(&hex).unwrap();
+            repo.collect_tree(&id, "", &mut HashMap::new())
+        };
+        // 最初の空白が NUL より後ろ（ID の中）にある
+        let mut late_space = b"abc\0".to_vec();
+        late_space.extend_from_slice(&[1; 20]);
+        late_space.extend_from_slice(b" z");
+        assert!(matches!(collect(&late_space), Err(GitError::Corrupt(_))));
+        // ID が途中で切れている
+        assert!(matches!(
+            collect(b"100644 a.txt\0abc"),
+            Err(GitError::Corrupt(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn outside_a_repository_is_a_clear_error() {
+        let dir = std::env::temp_dir().join(format!("minigrep-nogit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let err = scope_files(&dir, &GitScope::Tracked).unwrap_err();
+        // temp_dir 自体がリポジトリの中にある環境では見つかってしまうので、そのときは確かめない
+        if let GitError::NotARepository(path) = &err {
+            assert_eq!(path, &dir);
+            assert!(err.to_string().starts_with("not a git repository"));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+// ====================================================================
+// .git/index の読み込み
+// ====================================================================
+//
+// インデックス（ステージングエリア）には追跡中のファイルが1行ずつ入っている
+//
+//   ヘッダ: "DIRC" + バージョン（2, 3, 4）+ エントリ数   — すべてビッグエンディアン
+//   エントリ:
+//     ctime, mtime（秒 + ナノ秒）, dev, ino, mode, uid, gid, size — 各 4 バイト
+//     オブジェクト ID（20 バイト）, フラグ（2 バイト）
+//     [v3 以降] 拡張フラグ（フラグに 0x4000 が立っているときだけ 2 バイト）
+//     パス（NUL 終端）
+//       v2/v3 — エントリ全体が 8 バイトの倍数になるよう NUL で埋める
+//       v4    — 直前のパスと共通する部分を省略する（可変長整数で「削る長さ」+ 残り）
+//   エントリの後ろの拡張（TREE キャッシュなど）と末尾のチェックサムは読まない
+
+use super::GitError;
+use super::sha1::ObjectId;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub path: String, // リポジトリのルートからの "/" 区切りのパス
+    pub id: ObjectId,
+    pub mode: u32,
+    pub size: u32,
+    pub mtime: (u32, u32), // (秒, ナノ秒)
+    pub stage: u16,        // 0 以外ならマージの衝突中
+}
+
+impl IndexEntry {
+    // 通常ファイル（100644 / 100755）か — シンボリックリンクやサブモジュールは検索しない
+    pub fn is_regular_file(&self) -> bool {
+        self.mode & 0o170000 == 0o100000
+    }
+}
+
+pub fn parse(data: &[u8]) -> Result<Vec<IndexEntry>, GitError> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.bytes(4)? != b"DIRC" {
+        return Err(corrupt("index does not start with DIRC"));
+    }
+    let version = reader.u32()?;
+    if !(2..=4).contains(&version) {
+        return Err(GitError::Corrupt(format!(
+            "unsupported index version {version}"
+        )));
+    }
+    let count = reader.u32()?;
+
+    // count は壊れたファイルなら何でもありうるので、データに入りうる数までしか確保しない
+    // （エントリは最短でも 62 バイト = 固定部分 40 + ID 20 + フラグ 2）
+    let mut entries = Vec::with_capacity((count as usize).min(data.len() / 62));
+    let mut previous_path: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let start = reader.pos;
+        reader.bytes(8)?; // ctime
+        let mtime = (reader.u32()?, reader.u32()?);
+        reader.bytes(8)?; // dev, ino
+        let mode = reader.u32()?;
+        reader.bytes(8)?; // uid, gid
+        let size = reader.u32()?;
+        let id: ObjectId = reader.bytes(20)?.try_into().unwrap();
+        let flags = reader.u16()?;
+        if version >= 3 && flags & 0x4000 != 0 {
+            reader.u16()?; // 拡張フラグ（skip-worktree など）
+        }
+        let stage = (flags >> 12) & 0x3;
+
+        let path = if version == 4 {
+            let strip = reader.varint()?;
+            let keep = previous_path
+                .len()
+                .checked_sub(strip)
+                .ok_or_else(|| corrupt("bad path prefix in index v4"))?;
+            let mut path = previous_path[..keep].to_vec();
+            path.extend_from_slice(reader.until_nul()?);
+            path
+        } else {
+            let path = reader.until_nul()?.to_vec();
+            // 固定部分 + パス + NUL を 8 の倍数に切り上げる（NUL は 1〜8 バイト）
+            let entry_len = (reader.pos - start + 7) & !7;
+            reader.pos = start + entry_len;
+            path
+        };
+
+        let text =
+            String::from_utf8(path.clone()).map_err(|_| corrupt("index path is not UTF-8"))?;
+        previous_path = path;
+        entries.push(IndexEntry {
+            path: text,
+            id,
+            mode,
+            size,
+            mtime,
+            stage,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn corrupt(message: &str) -> GitError {
+    GitError::Corrupt(message.to_string())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], GitError> {
+        let slice = self
+            .data
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| corrupt("index is truncated"))?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, GitError> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u16(&mut self) -> Result<u16, GitError> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    // v2/v3 の埋め草で pos がデータの外に出ていることもある
+    fn until_nul(&mut self) -> Result<&'a [u8], GitError> {
+        let rest = self
+            .data
+            .get(self.pos..)
+            .ok_or_else(|| corrupt("index is truncated"))?;
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| corrupt("index path is not terminated"))?;
+        self.pos += len + 1;
+        Ok(&rest[..len])
+    }
+
+    // Git の「オフセット形式」の可変長整数（OFS_DELTA と同じ）
+    fn varint(&mut self) -> Result<usize, GitError> {
+        let mut byte = self.bytes(1)?[0];
+        let mut value = usize::from(byte & 0x7f);
+        while byte & 0x80 != 0 {
+            byte = self.bytes(1)?[0];
+            value = value
+                .checked_add(1)
+                .and_then(|v| v.checked_mul(1 << 7))
+                .ok_or_else(|| corrupt("index varint is too large"))?
+                | usize::from(byte & 0x7f);
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // v2 のエントリを1つ組み立てる
+    fn entry_v2(path: &str, mode: u32) -> Vec<u8> {
+        let mut entry = vec![0u8; 24];
+        entry.extend_from_slice(&mode.to_be_bytes());
+        entry.extend_from_slice(&[0; 8]);
+        entry.extend_from_slice(&5u32.to_be_bytes()); // size
+        entry.extend_from_slice(&[0xab; 20]);
+        entry.extend_from_slice(&(path.len() as u16).to_be_bytes());
+        entry.extend_from_slice(path.as_bytes());
+        let padded = (entry.len() + 8) & !7;
+        entry.resize(padded, 0);
+        entry
+    }
+
+    #[test]
+    fn parses_v2_entries() {
+        let mut data = b"DIRC".to_vec();
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend(entry_v2("README.md", 0o100644));
+        data.extend(entry_v2("src/main.rs", 0o120000));
+        data.extend_from_slice(&[0; 20]); // チェックサム（読まない）
+
+        let entries = parse(&data).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "README.md");
+        assert_eq!(entries[0].size, 5);
+        assert!(entries[0].is_regular_file());
+        assert_eq!(entries[1].path, "src/main.rs");
+        assert!(!entries[1].is_regular_file());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(b"PACK\0\0\0\x02").is_err());
+        assert!(parse(b"DIRC\0\0\0\x02\0\0\0\x01").is_err());
+    }
+
+    #[test]
+    fn truncated_or_corrupt_index_is_an_error() {
+        let mut data = b"DIRC".to_vec();
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend_from_slice(&u32::MAX.to_be_bytes()); // ありえないエントリ数
+        let entry = entry_v2("README.md", 0o100644);
+        data.extend_from_slice(&entry);
+        // どこで切っても panic せず Err になる
+        for len in 0..data.len() {
+            assert!(parse(&data[..len]).is_err(), "{len}");
+        }
+        assert!(parse(&data).is_err());
+
+        // パディングの途中で終わる（pos がデータの外に出る）
+        let mut short = data[..data.len() - 1].to_vec();
+        short[8..12].copy_from_slice(&2u32.to_be_bytes());
+        short.extend_from_slice(&entry[..64]);
+        assert!(parse(&short).is_err());
+
+        // v4 の巨大な varint
+        let mut v4 = b"DIRC".to_vec();
+        v4.extend_from_slice(&4u32.to_be_bytes());
+        v4.extend_from_slice(&1u32.to_be_bytes());
+        v4.extend_from_slice(&entry[..62]);
+        v4.extend_from_slice(&[0xff; 16]);
+        assert!(parse(&v4).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+// ====================================================================
+// zlib / DEFLATE の展開（RFC 1950 / RFC 1951）
+// ====================================================================
+//
+// Git のオブジェクトは zlib で圧縮されている（ルーズオブジェクトもパック内も）
+// 依存クレートを増やさないため、展開だけを自前で実装する
+//
+// DEFLATE のブロックは3種類:
+//   0 — 無圧縮（長さ + 生データ）
+//   1 — 固定ハフマン符号（RFC で決められた符号表）
+//   2 — 動的ハフマン符号（符号表そのものもハフマン符号で送られてくる）
+// 符号を解くと「リテラル1バイト」か「(長さ, 距離) = 少し前のデータのコピー」が出てくる
+
+use super::GitError;
+
+// 長さ符号 257..=285 の基本値と追加ビット数
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+// 距離符号 0..=29 の基本値と追加ビット数
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+// 動的ハフマンで「符号長の符号長」が送られてくる順番
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+// zlib ヘッダ（2バイト）を確かめてから DEFLATE を展開する
+// 末尾の Adler-32 は確かめない（Git 側で SHA-1 による確認ができるため）
+pub fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>, GitError> {
+    if data.len() < 2
+        || data[0] & 0x0f != 8
+        || (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 != 0
+    {
+        return Err(corrupt("bad zlib header"));
+    }
+    if data[1] & 0x20 != 0 {
+        return Err(corrupt("zlib preset dictionary is not supported"));
+    }
+    inflate(&data[2..])
+}
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, GitError> {
+    let mut bits = BitReader::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => stored_block(&mut bits, &mut out)?,
+            1 => {
+                let (literals, distances) = fixed_tables();
+                compressed_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_tables(&mut bits)?;
+                compressed_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            _ => return Err(corrupt("invalid deflate block type")),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn corrupt(message: &str) -> GitError {
+    GitError::Corrupt(message.to_string())
+}
+
+// --------------------------------------------------------------------
+// ビット単位の読み出し（DEFLATE は下位ビットから詰める）
+// --------------------------------------------------------------------
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize, // 次に読むバイト
+    bit: u32,   // 読みかけのビット列
+    count: u32, // bit に残っているビット数
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            data,
+            pos: 0,
+            bit: 0,
+            count: 0,
+        }
+    }
+
+    fn read(&mut self, n: u32) -> Result<u32, GitError> {
+        while self.count < n {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| corrupt("unexpected end of deflate stream"))?;
+            self.bit |= u32::from(byte) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = self.bit & ((1u64 << n) - 1) as u32;
+        self.bit >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    // 無圧縮ブロックはバイト境界から始まる
+    fn align(&mut self) {
+        self.bit = 0;
+        self.count = 0;
+    }
+}
+
+fn stored_block(bits: &mut BitReader, out: &mut Vec<u8>) -> Result<(), GitError> {
+    bits.align();
+    let header = bits
+        .data
+        .get(bits.pos..bits.pos + 4)
+        .ok_or_else(|| corrupt("truncated stored block"))?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err(corrupt("stored block length mismatch"));
+    }
+    let start = bits.pos + 4;
+    let block = bits
+        .data
+        .get(start..start + usize::from(len))
+        .ok_or_else(|| corrupt("truncated stored block"))?;
+    out.extend_from_slice(block);
+    bits.pos = start + usize::from(len);
+    Ok(())
+}
+
+// --------------------------------------------------------------------
+// ハフマン符号表
+// --------------------------------------------------------------------
+//
+// 符号長の一覧から「正準ハフマン符号」を組み立てる（RFC 1951 3.2.2）
+// counts[len] = その長さの符号の数、symbols = 符号順に並べた記号
+
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[usize::from(offsets[usize::from(len)])] = symbol as u16;
+                offsets[usize::from(len)] += 1;
+            }
+        }
+        Huffman { counts, symbols }
+    }
+
+    // 1ビットずつ読み、各長さの符号の範囲に入ったら記号が決まる
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, GitError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= bits.read(1)? as i32;
+            let count = i32::from(self.counts[len]);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(corrupt("invalid huffman code"))
+    }
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_tables(bits: &mut BitReader) -> Result<(Huffman, Huffman), GitError> {
+    let literal_count = bits.read(5)? as usize + 257;
+    let distance_count = bits.read(5)? as usize + 1;
+    let code_length_count = bits.read(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &i in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[i] = bits.read(3)? as u8;
+    }
+    let code_length_table = Huffman::new(&code_lengths);
+
+    // 16 = 直前の長さを繰り返す、17 / 18 = 0 を繰り返す
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_length_table.decode(bits)? {
+            len @ 0..=15 => (len as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| corrupt("repeat with no previous length"))?;
+                (previous, 3 + bits.read(2)?)
+            }
+            17 => (0, 3 + bits.read(3)?),
+            _ => (0, 11 + bits.read(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err(corrupt("too many code lengths"));
+    }
+
+    let (literals, distances) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literals), Huffman::new(distances)))
+}
+
+fn compressed_block(
+    bits: &mut BitReader,
+    out: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), GitError> {
+    loop {
+        let symbol = literals.decode(bits)?;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let i = usize::from(symbol - 257);
+                let length =
+                    usize::from(LENGTH_BASE[i]) + bits.read(u32::from(LENGTH_EXTRA[i]))? as usize;
+                let d = usize::from(distances.decode(bits)?);
+                if d >= DIST_BASE.len() {
+                    return Err(corrupt("invalid distance code"));
+                }
+                let distance =
+                    usize::from(DIST_BASE[d]) + bits.read(u32::from(DIST_EXTRA[d]))? as usize;
+                if distance > out.len() {
+                    return Err(corrupt("distance too far back"));
+                }
+                // コピー元とコピー先が重なってよい（"abcabcabc..." の繰り返し）ので1バイトずつ
+                let start = out.len() - distance;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+            _ => return Err(corrupt("invalid literal/length code")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflates_fixed_huffman_block() {
+        // zlib.compress(b"hello")
+        let data = [
+            0x78, 0x9c, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00, 0x06, 0x2c, 0x02, 0x15,
+        ];
+        assert_eq!(zlib_decompress(&data).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn inflates_stored_block() {
+        // zlib.compress(b"abc", level=0)
+        let data = [
+            0x78, 0x01, 0x01, 0x03, 0x00, 0xfc, 0xff, 0x61, 0x62, 0x63, 0x02, 0x4d, 0x01, 0x27,
+        ];
+        assert_eq!(zlib_decompress(&data).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn rejects_truncated_streams() {
+        assert!(zlib_decompress(&[0x78, 0x9c, 0xcb, 0x48]).is_err());
+        assert!(zlib_decompress(b"not zlib").is_err());
+    }
+}
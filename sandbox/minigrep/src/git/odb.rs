@@ -0,0 +1,498 @@
+// ====================================================================
+// オブジェクトデータベース（.git/objects）
+// ====================================================================
+//
+// オブジェクトの置き場所は2通り:
+//   ルーズ — objects/ab/cdef...（ID の先頭2文字がディレクトリ）
+//            中身は zlib 圧縮された "<種類> <サイズ>\0<データ>"
+//   パック — objects/pack/pack-*.idx と pack-*.pack の組
+//            .idx（v2）: fanout 表 → ソート済み ID → CRC → オフセット で場所を引く
+//            .pack     : 種類とサイズのヘッダ + zlib データ
+//                        差分（デルタ）で保存されたオブジェクトは、元オブジェクトに命令を当てて復元する
+//                          OFS_DELTA — 元は同じパック内の「何バイト前」
+//                          REF_DELTA — 元はオブジェクト ID で指定
+//
+// パックファイルはまとめてメモリに読み込む（std だけで mmap はできないため）
+// 学習用リポジトリ程度の大きさなら問題にならない
+//
+// .idx・.pack の中身は信用しない — 途中で切れていたり壊れていたりしたら panic せず Corrupt を返す
+//   .idx は開くときに表の大きさを確かめる。デルタをたどる深さも MAX_DELTA_DEPTH までにする
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::GitError;
+use super::inflate::zlib_decompress;
+use super::sha1::{ObjectId, to_hex};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+}
+
+impl Kind {
+    fn from_name(name: &[u8]) -> Option<Kind> {
+        match name {
+            b"commit" => Some(Kind::Commit),
+            b"tree" => Some(Kind::Tree),
+            b"blob" => Some(Kind::Blob),
+            b"tag" => Some(Kind::Tag),
+            _ => None,
+        }
+    }
+
+    // パック内の種類番号（6, 7 はデルタなので別扱い）
+    fn from_pack_type(n: u8) -> Option<Kind> {
+        match n {
+            1 => Some(Kind::Commit),
+            2 => Some(Kind::Tree),
+            3 => Some(Kind::Blob),
+            4 => Some(Kind::Tag),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Object {
+    pub kind: Kind,
+    pub data: Vec<u8>,
+}
+
+struct Pack {
+    index: Vec<u8>,
+    data: Vec<u8>,
+}
+
+const OFS_DELTA: u8 = 6;
+const REF_DELTA: u8 = 7;
+
+// git の既定は 50 段。自分自身を元にする REF_DELTA などで無限にたどらない（スタックもあふれない）ための上限
+const MAX_DELTA_DEPTH: usize = 128;
+
+pub struct Odb {
+    objects_dir: PathBuf,
+    packs: Vec<Pack>,
+}
+
+impl Odb {
+    pub fn open(objects_dir: &Path) -> Result<Odb, GitError> {
+        let mut packs = Vec::new();
+        let pack_dir = objects_dir.join("pack");
+        if pack_dir.is_dir() {
+            let mut names: Vec<PathBuf> = fs::read_dir(&pack_dir)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "idx"))
+                .collect();
+            names.sort();
+            for idx in names {
+                let index = fs::read(&idx)?;
+                if index.get(..8) != Some(&[0xff, b't', b'O', b'c', 0, 0, 0, 2]) {
+                    return Err(GitError::Corrupt(format!(
+                        "{}: only pack index v2 is supported",
+                        idx.display()
+                    )));
+                }
+                let data = fs::read(idx.with_extension("pack"))?;
+                packs.push(Pack::new(index, data)?);
+            }
+        }
+        Ok(Odb {
+            objects_dir: objects_dir.to_path_buf(),
+            packs,
+        })
+    }
+
+    pub fn read(&self, id: &ObjectId) -> Result<Object, GitError> {
+        self.read_at_depth(id, 0)
+    }
+
+    // depth — いくつデルタをたどってきたか
+    fn read_at_depth(&self, id: &ObjectId, depth: usize) -> Result<Object, GitError> {
+        let hex = to_hex(id);
+        match fs::read(self.objects_dir.join(&hex[..2]).join(&hex[2..])) {
+            Ok(compressed) => return parse_loose(&zlib_decompress(&compressed)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        for pack in &self.packs {
+            if let Some(offset) = pack.find(id)? {
+                return self.read_packed(pack, offset, depth);
+            }
+        }
+        Err(GitError::Corrupt(format!("object {hex} not found")))
+    }
+
+    // 短縮した ID（"a1b2c3d" など）から完全な ID を探す
+    pub fn find_prefix(&self, prefix: &str) -> Result<Option<ObjectId>, GitError> {
+        let mut found: Vec<ObjectId> = Vec::new();
+
+        if prefix.len() >= 2
+            && let Ok(entries) = fs::read_dir(self.objects_dir.join(&prefix[..2]))
+        {
+            for entry in entries {
+                let name = entry?.file_name();
+                let hex = format!("{}{}", &prefix[..2], name.to_string_lossy());
+                if hex.starts_with(prefix)
+                    && let Some(id) = super::sha1::from_hex(&hex)
+                {
+                    found.push(id);
+                }
+            }
+        }
+        for pack in &self.packs {
+            found.extend(pack.ids().filter(|id| to_hex(id).starts_with(prefix)));
+        }
+
+        found.sort();
+        found.dedup();
+        match found.as_slice() {
+            [] => Ok(None),
+            [id] => Ok(Some(*id)),
+            _ => Err(GitError::UnknownRevision(format!("{prefix} (ambiguous)"))),
+        }
+    }
+
+    fn read_packed(&self, pack: &Pack, offset: usize, depth: usize) -> Result<Object, GitError> {
+        if depth > MAX_DELTA_DEPTH {
+            return Err(corrupt("delta chain is too deep"));
+        }
+        let data = &pack.data;
+        let mut pos = offset;
+        let mut byte = *data
+            .get(pos)
+            .ok_or_else(|| corrupt("offset past end of pack"))?;
+        pos += 1;
+        let pack_type = (byte >> 4) & 0x7;
+        // サイズは可変長（ここでは使わない — 展開した長さでわかる）
+        while byte & 0x80 != 0 {
+            byte = *data
+                .get(pos)
+                .ok_or_else(|| corrupt("truncated pack header"))?;
+            pos += 1;
+        }
+
+        match pack_type {
+            OFS_DELTA => {
+                let mut distance = 0;
+                loop {
+                    let byte = *data
+                        .get(pos)
+                        .ok_or_else(|| corrupt("truncated delta offset"))?;
+                    pos += 1;
+                    distance |= usize::from(byte & 0x7f);
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                    distance = distance
+                        .checked_add(1)
+                        .and_then(|d| d.checked_mul(1 << 7))
+                        .ok_or_else(|| corrupt("delta offset is too large"))?;
+                }
+                // 元は必ず前にある（0 だと自分自身になってしまう）
+                let base_offset = offset
+                    .checked_sub(distance)
+                    .filter(|_| distance > 0)
+                    .ok_or_else(|| corrupt("delta base before start of pack"))?;
+                let base = self.read_packed(pack, base_offset, depth + 1)?;
+                let delta = zlib_decompress(&data[pos..])?;
+                Ok(Object {
+                    kind: base.kind,
+                    data: apply_delta(&base.data, &delta)?,
+                })
+            }
+            REF_DELTA => {
+                let base_id: ObjectId = data
+                    .get(pos..pos + 20)
+                    .ok_or_else(|| corrupt("truncated delta base"))?
+                    .try_into()
+                    .unwrap();
+                let base = self.read_at_depth(&base_id, depth + 1)?;
+                let delta = zlib_decompress(&data[pos + 20..])?;
+                Ok(Object {
+                    kind: base.kind,
+                    data: apply_delta(&base.data, &delta)?,
+                })
+            }
+            n => Ok(Object {
+                kind: Kind::from_pack_type(n).ok_or_else(|| corrupt("unknown pack object type"))?,
+                data: zlib_decompress(&data[pos..])?,
+            }),
+        }
+    }
+}
+
+const FANOUT: usize = 8;
+const IDS: usize = FANOUT + 256 * 4;
+
+impl Pack {
+    // fanout 表が昇順で、ID・CRC・オフセットの表がすべて .idx に収まっていることを確かめる
+    // → ids・find・offset の小さい表の読み出しは範囲内になる（大きい表だけはその都度確かめる）
+    fn new(index: Vec<u8>, data: Vec<u8>) -> Result<Pack, GitError> {
+        let mut previous = 0;
+        for b in 0..256 {
+            let n = be_u32(&index, FANOUT + b * 4)?;
+            if n < previous {
+                return Err(corrupt("pack index fanout is not sorted"));
+            }
+            previous = n;
+        }
+        let tables_end = (previous as usize)
+            .checked_mul(20 + 4 + 4)
+            .and_then(|len| len.checked_add(IDS))
+            .ok_or_else(|| corrupt("pack index is truncated"))?;
+        if index.len() < tables_end {
+            return Err(corrupt("pack index is truncated"));
+        }
+        Ok(Pack { index, data })
+    }
+
+    fn count(&self) -> usize {
+        self.fanout(255)
+    }
+
+    // 先頭バイトが b 以下の ID の数（new で確かめたので範囲内）
+    fn fanout(&self, b: usize) -> usize {
+        let pos = FANOUT + b * 4;
+        u32::from_be_bytes(self.index[pos..pos + 4].try_into().unwrap()) as usize
+    }
+
+    fn ids(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        (0..self.count()).map(move |i| {
+            self.index[IDS + i * 20..IDS + i * 20 + 20]
+                .try_into()
+                .unwrap()
+        })
+    }
+
+    // fanout[b] = 先頭バイトが b 以下の ID の数 → その範囲だけを二分探索する
+    fn find(&self, id: &ObjectId) -> Result<Option<usize>, GitError> {
+        let lo = if id[0] == 0 {
+            0
+        } else {
+            self.fanout(usize::from(id[0]) - 1)
+        };
+        let hi = self.fanout(usize::from(id[0]));
+        let id_at = |i: usize| &self.index[IDS + i * 20..IDS + i * 20 + 20];
+
+        let (mut lo, mut hi) = (lo, hi);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match id_at(mid).cmp(id.as_slice()) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return self.offset(mid).map(Some),
+            }
+        }
+        Ok(None)
+    }
+
+    // 最上位ビットが立っていたら、2GB を超える位置用の 8 バイト表を引く
+    fn offset(&self, i: usize) -> Result<usize, GitError> {
+        let n = self.count();
+        let offsets = IDS + n * 20 + n * 4;
+        let offset = be_u32(&self.index, offsets + i * 4)?;
+        if offset & 0x8000_0000 == 0 {
+            return Ok(offset as usize);
+        }
+        let large = offsets + n * 4 + (offset & 0x7fff_ffff) as usize * 8;
+        let bytes = self
+            .index
+            .get(large..large + 8)
+            .ok_or_else(|| corrupt("pack index is truncated"))?;
+        Ok(u64::from_be_bytes(bytes.try_into().unwrap()) as usize)
+    }
+}
+
+fn be_u32(data: &[u8], pos: usize) -> Result<u32, GitError> {
+    let bytes = data
+        .get(pos..pos + 4)
+        .ok_or_else(|| corrupt("pack index is truncated"))?;
+    Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+fn corrupt(message: &str) -> GitError {
+    GitError::Corrupt(message.to_string())
+}
+
+// "<種類> <サイズ>\0<データ>"
+fn parse_loose(raw: &[u8]) -> Result<Object, GitError> {
+    let nul = raw
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| corrupt("loose object has no header"))?;
+    let header = &raw[..nul];
+    let space = header
+        .iter()
+        .position(|&b| b == b' ')
+        .ok_or_else(|| corrupt("loose object header has no size"))?;
+    let kind = Kind::from_name(&header[..space]).ok_or_else(|| corrupt("unknown object type"))?;
+    Ok(Object {
+        kind,
+        data: raw[nul + 1..].to_vec(),
+    })
+}
+
+// デルタ: 元のサイズ、復元後のサイズ（どちらも 7 ビットずつの可変長）に続いて命令の列
+//   0x80 が立っている — コピー: 下位4ビットで offset、次の3ビットで size のどのバイトがあるか
+//   それ以外（1〜127） — 挿入: その数だけ後ろのバイトをそのまま追加
+fn apply_delta(base: &[u8], delta: &[u8]) -> Result<Vec<u8>, GitError> {
+    let mut pos = 0;
+    let mut size = || -> Result<usize, GitError> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = *delta.get(pos).ok_or_else(|| corrupt("truncated delta"))?;
+            pos += 1;
+            if shift >= usize::BITS {
+                return Err(corrupt("delta size is too large"));
+            }
+            value |= usize::from(byte & 0x7f) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    };
+    let base_size = size()?;
+    let result_size = size()?;
+    if base_size != base.len() {
+        return Err(corrupt("delta base size mismatch"));
+    }
+
+    // result_size も信用しない（実際に足りなければ Vec が伸びる）
+    let mut out = Vec::with_capacity(result_size.min(base.len() + delta.len()));
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            let mut read_le = |flags: u8, count: usize| -> Result<usize, GitError> {
+                let mut value = 0;
+                for i in 0..count {
+                    if flags & (1 << i) != 0 {
+                        let byte = *delta
+                            .get(pos)
+                            .ok_or_else(|| corrupt("truncated delta copy"))?;
+                        pos += 1;
+                        value |= usize::from(byte) << (8 * i);
+                    }
+                }
+                Ok(value)
+            };
+            let offset = read_le(op, 4)?;
+            let len = match read_le(op >> 4, 3)? {
+                0 => 0x10000,
+                len => len,
+            };
+            let chunk = base
+                .get(offset..offset + len)
+                .ok_or_else(|| corrupt("delta copy out of range"))?;
+            out.extend_from_slice(chunk);
+        } else if op != 0 {
+            let chunk = delta
+                .get(pos..pos + usize::from(op))
+                .ok_or_else(|| corrupt("truncated delta insert"))?;
+            out.extend_from_slice(chunk);
+            pos += usize::from(op);
+        } else {
+            return Err(corrupt("reserved delta opcode"));
+        }
+    }
+
+    if out.len() != result_size {
+        return Err(corrupt("delta result size mismatch"));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_copy_and_insert_instructions() {
+        let base = b"hello, world";
+        // 元 12 バイト → 結果 12 バイト: "hello" をコピー、", rust" を挿入、"!" を挿入
+        let delta = [
+            12, 12, 0x90, 5, // copy offset=0 size=5
+            6, b',', b' ', b'r', b'u', b's', b't', // insert 6
+            1, b'!', // insert 1
+        ];
+        assert_eq!(apply_delta(base, &delta).unwrap(), b"hello, rust!");
+    }
+
+    #[test]
+    fn rejects_out_of_range_copies() {
+        let delta = [3, 10, 0x91, 2, 10];
+        assert!(apply_delta(b"abc", &delta).is_err());
+        // サイズの可変長整数が usize に収まらない
+        assert!(apply_delta(b"abc", &[0xff; 12]).is_err());
+    }
+
+    // ID を1つだけ持つ .idx（オフセットは offset）
+    fn index_with(id: ObjectId, offset: u32) -> Vec<u8> {
+        let mut index = vec![0xff, b't', b'O', b'c', 0, 0, 0, 2];
+        for b in 0..256 {
+            let n: u32 = if b < usize::from(id[0]) { 0 } else { 1 };
+            index.extend_from_slice(&n.to_be_bytes());
+        }
+        index.extend_from_slice(&id);
+        index.extend_from_slice(&[0; 4]); // CRC
+        index.extend_from_slice(&offset.to_be_bytes());
+        index
+    }
+
+    fn odb_with(pack: Pack) -> Odb {
+        Odb {
+            objects_dir: PathBuf::from("/nonexistent/objects"),
+            packs: vec![pack],
+        }
+    }
+
+    #[test]
+    fn truncated_pack_index_is_rejected() {
+        let index = index_with([7; 20], 12);
+        assert!(Pack::new(index.clone(), Vec::new()).is_ok());
+        for len in [0, 8, 100, IDS, index.len() - 1] {
+            assert!(
+                Pack::new(index[..len].to_vec(), Vec::new()).is_err(),
+                "{len}"
+            );
+        }
+        // fanout が減っている
+        let mut unsorted = index.clone();
+        unsorted[FANOUT + 200 * 4..FANOUT + 200 * 4 + 4].copy_from_slice(&5u32.to_be_bytes());
+        assert!(Pack::new(unsorted, Vec::new()).is_err());
+        // 大きいオフセット表を指しているのに表がない
+        let large = index_with([7; 20], 0x8000_0003);
+        let odb = odb_with(Pack::new(large, Vec::new()).unwrap());
+        assert!(odb.read(&[7; 20]).is_err());
+    }
+
+    #[test]
+    fn corrupt_pack_data_is_an_error() {
+        let id = [7; 20];
+        let read = |data: &[u8], offset: u32| {
+            odb_with(Pack::new(index_with(id, offset), data.to_vec()).unwrap()).read(&id)
+        };
+        // オフセットがパックの外
+        assert!(read(b"PACK", 100).is_err());
+        // OFS_DELTA のヘッダだけで、距離がない
+        assert!(read(&[0x60], 0).is_err());
+        // 距離の可変長整数があふれる
+        let mut huge = vec![0x60];
+        huge.extend_from_slice(&[0xff; 12]);
+        assert!(read(&huge, 0).is_err());
+        // 距離 0（自分自身）
+        assert!(read(&[0x60, 0x00], 0).is_err());
+        // REF_DELTA で自分自身を元にしている → 深さの上限で止まる
+        let mut cycle = vec![0x70];
+        cycle.extend_from_slice(&id);
+        assert!(read(&cycle, 0).is_err());
+    }
+}
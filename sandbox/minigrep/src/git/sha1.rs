@@ -0,0 +1,119 @@
+// ====================================================================
+// SHA-1（FIPS 180-4）
+// ====================================================================
+//
+// Git はオブジェクトを "blob <サイズ>\0<中身>" の SHA-1 で識別する
+// 作業ツリーのファイルがインデックスやコミットと同じ中身かどうかは、
+// 同じ形でハッシュを計算して比べればわかる
+//
+// ※ SHA-1 は暗号学的にはもう安全ではないが、ここでは「Git と同じ ID を作る」ためだけに使う
+
+pub type ObjectId = [u8; 20];
+
+pub fn sha1(data: &[u8]) -> ObjectId {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    // パディング: 0x80、0 を 64 バイト境界の 8 バイト手前まで、最後にビット長（ビッグエンディアン）
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut id = [0u8; 20];
+    for (chunk, word) in id.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    id
+}
+
+// git hash-object と同じ ID
+pub fn blob_id(contents: &[u8]) -> ObjectId {
+    let mut object = format!("blob {}\0", contents.len()).into_bytes();
+    object.extend_from_slice(contents);
+    sha1(&object)
+}
+
+pub fn to_hex(id: &ObjectId) -> String {
+    id.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<ObjectId> {
+    if hex.len() != 40 || !hex.is_ascii() {
+        return None;
+    }
+    let mut id = [0u8; 20];
+    for (i, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(
+            to_hex(&sha1(b"")),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            to_hex(&sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        // 2ブロック以上にまたがる入力
+        assert_eq!(
+            to_hex(&sha1(&[b'a'; 1000])),
+            "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+        );
+    }
+
+    #[test]
+    fn blob_id_matches_git_hash_object() {
+        // printf 'hello\n' | git hash-object --stdin
+        assert_eq!(
+            to_hex(&blob_id(b"hello\n")),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+        assert_eq!(from_hex(&to_hex(&blob_id(b"x"))), Some(blob_id(b"x")));
+        assert_eq!(from_hex("xyz"), None);
+    }
+}
@@ -9,10 +9,40 @@
 // 12.4: TDD で search 関数を開発
 // 12.5: 環境変数で大文字小文字の区別を制御
 // 12.6: エラーメッセージを stderr に出力（main.rs 側）
+//
+// 拡張:
+//   - records: CSV/TSV・JSON Lines のフィールド指定検索（--csv-field / --json-path）
+//   - rank: TF-IDF による関連度順の検索（--rank / --top）
+//   - walk: ディレクトリを渡したときのファイル走査
+//   - serve: HTTP で検索を提供する（minigrep serve）
+//   - cancel: キャンセル・タイムアウト（--timeout / --max-filesize / --max-depth）
+//   - fast: 大文字小文字を区別する検索の高速版（memchr）— benches/search.rs で比較
+//   - filter: ファイルの種類・グロブによる絞り込み（-t / -T / --glob / --type-add）
+//   - syntax: コメント・文字列・コードだけを検索する（--only）
+//   - git: 追跡中・変更したファイルだけを検索する（--git-tracked / --git-modified / --git-since）
+
+pub mod cancel;
+pub mod fast;
+pub mod filter;
+pub mod git;
+pub mod rank;
+pub mod records;
+pub mod serve;
+pub mod syntax;
+pub mod walk;
 
 use std::env;
 use std::error::Error;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use cancel::{CancelToken, Interruption};
+use filter::FileFilter;
+use git::GitScope;
+use records::{CsvOptions, FieldSelector};
+use syntax::{Language, Region};
+use walk::WalkOptions;
 
 // ====================================================================
 // Config 構造体
@@ -27,7 +57,18 @@ use std::fs;
 pub struct Config {
     pub query: String,
     pub file_path: String,
-    pub ignore_case: bool, // 12.5: 大文字小文字を無視するか
+    pub ignore_case: bool,            // 12.5: 大文字小文字を無視するか
+    pub field: Option<FieldSelector>, // 検索対象をフィールドに限定する
+    pub rank: bool,                   // --rank: 関連度順に並べる
+    pub top: usize,                   // --top: rank で表示する件数
+    pub timeout: Option<Duration>,    // --timeout: これを過ぎたら打ち切る
+    pub max_filesize: Option<u64>,    // --max-filesize: これより大きいファイルは読まない
+    pub max_depth: Option<usize>,     // --max-depth: ディレクトリをたどる深さ
+    pub filter: FileFilter,           // -t / -T / --glob: ディレクトリ検索の対象
+    pub type_list: bool,              // --type-list: 種類の一覧を表示するだけ
+    pub only: Option<Region>,         // --only: コメント・文字列・コードに限定する
+    pub max_count: Option<usize>,     // -m: 1ファイルあたりこの件数で打ち切る
+    pub git_scope: Option<GitScope>,  // --git-*: Git で追跡・変更したファイルに限定する
 }
 
 impl Config {
@@ -41,16 +82,148 @@ impl Config {
         // 最初の要素はプログラム名なのでスキップ
         args.next();
 
-        let query = match args.next() {
+        // オプション（--xxx）と位置引数（query, file_path）を振り分ける
+        //   "--" 以降はすべて位置引数（"-" で始まる query を渡したいとき用）
+        let mut positional = Vec::new();
+        let mut csv_field = None;
+        let mut json_path = None;
+        let mut csv_options = CsvOptions::default();
+        let mut rank = false;
+        let mut top = 10;
+        let mut timeout = None;
+        let mut max_filesize = None;
+        let mut max_depth = None;
+        let mut filter = FileFilter::default();
+        let mut type_list = false;
+        let mut only = None;
+        let mut max_count = None;
+        let mut git_scopes = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--csv-field" => {
+                    csv_field = Some(args.next().ok_or("--csv-field requires a field name")?);
+                }
+                "--json-path" => {
+                    json_path = Some(args.next().ok_or("--json-path requires a path")?);
+                }
+                "--delimiter" => {
+                    let value = args.next().ok_or("--delimiter requires a character")?;
+                    csv_options.delimiter = parse_delimiter(&value)?;
+                }
+                "--tsv" => csv_options.delimiter = '\t',
+                "--no-header" => csv_options.has_header = false,
+                "--rank" => rank = true,
+                "--top" => {
+                    top = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--top requires a positive number")?;
+                }
+                "--timeout" => {
+                    let value = args.next().ok_or("--timeout requires a duration")?;
+                    timeout = Some(
+                        cancel::parse_duration(&value)
+                            .ok_or("--timeout must look like 500ms, 30s or 2m")?,
+                    );
+                }
+                "--max-filesize" => {
+                    let value = args.next().ok_or("--max-filesize requires a size")?;
+                    max_filesize = Some(
+                        cancel::parse_size(&value)
+                            .ok_or("--max-filesize must look like 512, 64K, 10M or 1G")?,
+                    );
+                }
+                "--max-depth" => {
+                    max_depth = Some(
+                        args.next()
+                            .and_then(|n| n.parse().ok())
+                            .ok_or("--max-depth requires a number")?,
+                    );
+                }
+                "-t" | "--type" => {
+                    filter.select_type(&args.next().ok_or("-t requires a file type")?);
+                }
+                "-T" | "--type-not" => {
+                    filter.negate_type(&args.next().ok_or("-T requires a file type")?);
+                }
+                "-g" | "--glob" => {
+                    filter.add_glob(&args.next().ok_or("--glob requires a pattern")?)?;
+                }
+                "--type-add" => {
+                    filter.add_type(&args.next().ok_or("--type-add requires name:glob")?)?;
+                }
+                "--type-list" => type_list = true,
+                "--git-tracked" => git_scopes.push(GitScope::Tracked),
+                "--git-modified" => git_scopes.push(GitScope::Modified),
+                "--git-since" => {
+                    let rev = args.next().ok_or("--git-since requires a revision")?;
+                    git_scopes.push(GitScope::Since(rev));
+                }
+                "-m" | "--max-count" => {
+                    max_count = Some(
+                        args.next()
+                            .and_then(|n| n.parse().ok())
+                            .ok_or("-m requires a number")?,
+                    );
+                }
+                "--only" => {
+                    only = Some(
+                        args.next()
+                            .and_then(|value| Region::parse(&value))
+                            .ok_or("--only must be comments, strings or code")?,
+                    );
+                }
+                "--" => positional.extend(args.by_ref()),
+                option if option.starts_with("--") => return Err("Unknown option"),
+                _ => positional.push(arg),
+            }
+        }
+
+        // -t / -T の種類は --type-add より前に書かれていてもよいので、最後に確認する
+        filter.validate()?;
+
+        let mut positional = positional.into_iter();
+
+        // --type-list だけなら query と file_path はいらない
+        let query = match positional.next() {
             Some(arg) => arg,
+            None if type_list => String::new(),
             None => return Err("Didn't get a query string"),
         };
 
-        let file_path = match args.next() {
+        let file_path = match positional.next() {
             Some(arg) => arg,
+            None if type_list => String::new(),
             None => return Err("Didn't get a file path"),
         };
 
+        let field = match (csv_field, json_path) {
+            (Some(_), Some(_)) => return Err("--csv-field and --json-path cannot be combined"),
+            (Some(field), None) => Some(FieldSelector::Csv {
+                field,
+                options: csv_options,
+            }),
+            (None, Some(path)) => Some(FieldSelector::Json { path }),
+            (None, None) => None,
+        };
+
+        if rank && field.is_some() {
+            return Err("--rank cannot be combined with --csv-field or --json-path");
+        }
+        if git_scopes.len() > 1 {
+            return Err("only one of --git-tracked, --git-modified and --git-since can be used");
+        }
+        let git_scope = git_scopes.pop();
+
+        if rank && max_count.is_some() {
+            return Err("-m cannot be combined with --rank (use --top)");
+        }
+        if only.is_some() && (rank || field.is_some()) {
+            return Err("--only cannot be combined with --rank, --csv-field or --json-path");
+        }
+
         // 12.5: 環境変数 IGNORE_CASE が設定されているか確認
         let ignore_case = env::var("IGNORE_CASE").is_ok();
 
@@ -58,29 +231,257 @@ impl Config {
             query,
             file_path,
             ignore_case,
+            field,
+            rank,
+            top,
+            timeout,
+            max_filesize,
+            max_depth,
+            filter,
+            type_list,
+            only,
+            max_count,
+            git_scope,
         })
     }
 }
 
+// "\t" や "tab" でタブを指定できるようにする（シェルでタブを打つのは面倒なため）
+fn parse_delimiter(value: &str) -> Result<char, &'static str> {
+    match value {
+        "\\t" | "tab" => Ok('\t'),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' && c != '\r' => Ok(c),
+                _ => Err("--delimiter must be a single character"),
+            }
+        }
+    }
+}
+
 // ====================================================================
 // run 関数 — プログラムのメインロジック
 // ====================================================================
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.file_path)?;
+    run_with_cancel(config, &CancelToken::new())
+}
 
-    // 12.5: ignore_case に応じて検索関数を切り替え
-    let results = if config.ignore_case {
-        search_case_insensitive(&config.query, &contents)
-    } else {
-        search(&config.query, &contents)
+// 組み込み側（別スレッドから止めたい場合）はこちらを使う
+//   - token.cancel() されたら、それまでの結果を出力して Interruption を返す
+//   - config.timeout があれば token に期限を付け足す
+pub fn run_with_cancel(config: Config, token: &CancelToken) -> Result<(), Box<dyn Error>> {
+    let token = match config.timeout {
+        Some(timeout) => token.with_timeout(timeout),
+        None => token.clone(),
     };
+    let path = Path::new(&config.file_path);
+
+    if config.type_list {
+        print_type_list(&config.filter);
+        return Ok(());
+    }
+
+    if config.rank {
+        return run_rank(&config, path, &token);
+    }
+
+    // ディレクトリなら配下のファイルをすべて検索し、"パス:行" で出力する
+    if path.is_dir() {
+        for file in target_files(&config, path, &token)? {
+            check(&token)?;
+            // --only のときは、レキサのない言語のファイルは対象外
+            if config.only.is_some() && Language::detect(&file).is_none() {
+                continue;
+            }
+            let Some(contents) = walk::read_text(&file)? else {
+                continue;
+            };
+            let outcome = search_contents(&config, &file, &contents, &token)?;
+            for line in outcome.results {
+                println!("{}:{line}", file.display());
+            }
+            if let Some(reason) = outcome.interrupted {
+                return Err(reason.into());
+            }
+        }
+        return check(&token);
+    }
 
-    for line in results {
+    if let Some(scope) = &config.git_scope
+        && !git::scope_files(path, scope)?.contains(&fs::canonicalize(path)?)
+    {
+        return Ok(());
+    }
+
+    let contents = read_file(path, config.max_filesize)?;
+    let outcome = search_contents(&config, path, &contents, &token)?;
+
+    for line in outcome.results {
         println!("{line}");
     }
 
-    Ok(())
+    match outcome.interrupted {
+        Some(reason) => Err(reason.into()),
+        None => Ok(()),
+    }
+}
+
+// ディレクトリ配下の検索対象（--git-* があれば、その範囲のファイルだけに絞る）
+fn target_files(
+    config: &Config,
+    path: &Path,
+    token: &CancelToken,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = walk::files(path, &walk_options(config, token))?;
+    if let Some(scope) = &config.git_scope {
+        let allowed = git::scope_files(path, scope)?;
+        files.retain(|file| fs::canonicalize(file).is_ok_and(|file| allowed.contains(&file)));
+    }
+    Ok(files)
+}
+
+fn walk_options(config: &Config, token: &CancelToken) -> WalkOptions {
+    WalkOptions {
+        max_depth: config.max_depth,
+        max_filesize: config.max_filesize,
+        cancel: Some(token.clone()),
+        filter: config.filter.clone(),
+    }
+}
+
+// rust: *.rs
+// py: *.py (#!python, #!python3)
+fn print_type_list(filter: &FileFilter) {
+    for file_type in filter.types() {
+        let globs: Vec<&str> = file_type.globs.iter().map(|g| g.as_str()).collect();
+        print!("{}: {}", file_type.name, globs.join(", "));
+        if !file_type.shebangs.is_empty() {
+            let shebangs: Vec<String> = file_type
+                .shebangs
+                .iter()
+                .map(|s| format!("#!{s}"))
+                .collect();
+            print!(" ({})", shebangs.join(", "));
+        }
+        println!();
+    }
+}
+
+fn check(token: &CancelToken) -> Result<(), Box<dyn Error>> {
+    match token.interrupted() {
+        Some(reason) => Err(reason.into()),
+        None => Ok(()),
+    }
+}
+
+// 明示的に渡されたファイルは、通常ファイルかどうかとサイズを開く前に確かめる
+// （FIFO を read_to_string すると書き込み側が来るまで止まってしまう）
+fn read_file(path: &Path, max_filesize: Option<u64>) -> Result<String, Box<dyn Error>> {
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a regular file", path.display()).into());
+    }
+    if let Some(max) = max_filesize
+        && metadata.len() > max
+    {
+        return Err(format!(
+            "{} is {} bytes, larger than --max-filesize ({max} bytes)",
+            path.display(),
+            metadata.len()
+        )
+        .into());
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+fn search_contents<'a>(
+    config: &Config,
+    path: &Path,
+    contents: &'a str,
+    token: &CancelToken,
+) -> Result<SearchOutcome<'a>, Box<dyn Error>> {
+    let matcher = Matcher::new(&config.query, config.ignore_case);
+    let limit = config.max_count.unwrap_or(usize::MAX);
+
+    // フィールド指定があればレコード単位で検索する
+    if let Some(selector) = &config.field {
+        let mut results = records::search_fields(&matcher, selector, contents)?;
+        results.truncate(limit);
+        return Ok(SearchOutcome {
+            results,
+            interrupted: None,
+        });
+    }
+
+    // --only: 言語はパスの拡張子で決める
+    //   レキサが失敗したファイル（閉じていない文字列など）は普通の検索に戻す
+    if let Some(region) = config.only {
+        let language = Language::detect(path).ok_or_else(|| {
+            format!(
+                "{}: --only supports Rust, TOML and Markdown files",
+                path.display()
+            )
+        })?;
+        match syntax::classify(language, contents) {
+            Ok(spans) => {
+                let mut results = syntax::search_regions(&matcher, contents, &spans, region);
+                results.truncate(limit);
+                return Ok(SearchOutcome {
+                    results,
+                    interrupted: None,
+                });
+            }
+            Err(err) => eprintln!(
+                "{}: {err}; searching the whole file instead",
+                path.display()
+            ),
+        }
+    }
+
+    // 12.5: ignore_case は Matcher が受け持つ
+    //   （search / search_case_insensitive と同じ判定で、途中で止められる版）
+    Ok(search_cancellable(&matcher, contents, token, limit))
+}
+
+// --rank: ディレクトリならファイル単位、1ファイルなら行単位で順位を付ける
+// 途中で止まった場合は、それまでに読んだファイルだけで順位を付けて出力する
+fn run_rank(config: &Config, path: &Path, token: &CancelToken) -> Result<(), Box<dyn Error>> {
+    if path.is_dir() {
+        let mut labels = Vec::new();
+        let mut texts = Vec::new();
+        for file in target_files(config, path, token)? {
+            if token.interrupted().is_some() {
+                break;
+            }
+            if let Some(contents) = walk::read_text(&file)? {
+                labels.push(file.display().to_string());
+                texts.push(contents);
+            }
+        }
+        let documents: Vec<&str> = texts.iter().map(String::as_str).collect();
+
+        for ranked in rank::rank(&config.query, config.ignore_case, &documents)
+            .iter()
+            .take(config.top)
+        {
+            println!("{:.3}\t{}", ranked.score, labels[ranked.index]);
+        }
+    } else {
+        let contents = read_file(path, config.max_filesize)?;
+        let lines: Vec<&str> = contents.lines().collect();
+
+        for ranked in rank::rank(&config.query, config.ignore_case, &lines)
+            .iter()
+            .take(config.top)
+        {
+            let line_number = ranked.index + 1;
+            println!("{:.3}\t{line_number}:{}", ranked.score, lines[ranked.index]);
+        }
+    }
+
+    check(token)
 }
 
 // ====================================================================
@@ -93,12 +494,32 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
 //
 // 13.3: for ループ → イテレータチェーンに書き換え
 //   lines().filter().collect() で同じ処理をより宣言的に
+//
+// さらに collect() をやめて、イテレータのまま返す版（search_iter）を用意した
+//   - 全件を Vec に貯めないのでメモリを使わない
+//   - 呼び出し側が take(1) すれば最初の1件で検索が止まる（13.2: イテレータは怠惰）
+//   - search は search_iter を collect() するだけの薄いラッパ
+
+// 1件のマッチ: 行番号（1始まり）と行そのもの
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match<'a> {
+    pub line_number: usize,
+    pub line: &'a str,
+}
+
+pub fn search_iter<'a>(query: &str, contents: &'a str) -> impl Iterator<Item = Match<'a>> {
+    numbered_lines(contents).filter(move |m| m.line.contains(query))
+}
 
 pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    contents
-        .lines()
-        .filter(|line| line.contains(query))
-        .collect()
+    search_iter(query, contents).map(|m| m.line).collect()
+}
+
+fn numbered_lines(contents: &str) -> impl Iterator<Item = Match<'_>> {
+    contents.lines().enumerate().map(|(i, line)| Match {
+        line_number: i + 1,
+        line,
+    })
 }
 
 // ====================================================================
@@ -107,15 +528,142 @@ pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
 //
 // 13.3: こちらもイテレータチェーンに書き換え
 
-pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+// query の小文字化は最初に1回だけ行い、String ごとクロージャに move する
+pub fn search_case_insensitive_iter<'a>(
+    query: &str,
+    contents: &'a str,
+) -> impl Iterator<Item = Match<'a>> {
     let query = query.to_lowercase();
 
-    contents
-        .lines()
-        .filter(|line| line.to_lowercase().contains(&query))
+    numbered_lines(contents).filter(move |m| m.line.to_lowercase().contains(&query))
+}
+
+pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    search_case_insensitive_iter(query, contents)
+        .map(|m| m.line)
         .collect()
 }
 
+// ====================================================================
+// 途中で止められる検索
+// ====================================================================
+//
+// 行ごとに CancelToken を確認すると遅いので、CHECK_INTERVAL 行ごとに確認する
+// 止まった場合も、それまでに見つかった行は results に残る
+//
+// 大文字小文字を区別し、query が ASCII のときは fast::search_iter を使う（ベンチで速かった条件）
+//   → CHUNK_BYTES ごと（行の途中では切らない）に区切って、チャンクの間で確認する
+//   チャンクの中も take で残りの件数だけ取るので、limit 件目の行の後ろは読まない
+//
+// limit 件見つかったらそこで止める（-m / --max-count、制限なしなら usize::MAX）
+
+pub struct SearchOutcome<'a> {
+    pub results: Vec<&'a str>,
+    pub interrupted: Option<Interruption>, // None なら最後まで検索した
+}
+
+const CHECK_INTERVAL: usize = 1024;
+const CHUNK_BYTES: usize = 1 << 20;
+
+pub fn search_cancellable<'a>(
+    matcher: &Matcher,
+    contents: &'a str,
+    token: &CancelToken,
+    limit: usize,
+) -> SearchOutcome<'a> {
+    let mut interrupted = None;
+
+    if !matcher.ignore_case && matcher.query.is_ascii() {
+        let mut results = Vec::new();
+        let mut rest = contents;
+        while !rest.is_empty() && results.len() < limit {
+            if let Some(reason) = token.interrupted() {
+                interrupted = Some(reason);
+                break;
+            }
+            let cut = chunk_end(rest);
+            let wanted = limit - results.len();
+            results.extend(fast::search_iter(&matcher.query, &rest[..cut]).take(wanted));
+            rest = &rest[cut..];
+        }
+        return SearchOutcome {
+            results,
+            interrupted,
+        };
+    }
+
+    // 行ごとの検索はイテレータのまま組み立てて、最後に1回だけ collect() する
+    //   take(limit) に達した時点で残りの行は読まれない（-m の早期終了）
+    let results = numbered_lines(contents)
+        .take_while(|m| {
+            if m.line_number % CHECK_INTERVAL == 1 {
+                interrupted = token.interrupted();
+            }
+            interrupted.is_none()
+        })
+        .filter(|m| matcher.is_match(m.line))
+        .map(|m| m.line)
+        .take(limit)
+        .collect();
+
+    SearchOutcome {
+        results,
+        interrupted,
+    }
+}
+
+// CHUNK_BYTES 以降で最初の改行の直後（なければ末尾）
+fn chunk_end(text: &str) -> usize {
+    if text.len() <= CHUNK_BYTES {
+        return text.len();
+    }
+    fast::memchr(b'\n', &text.as_bytes()[CHUNK_BYTES..]).map_or(text.len(), |i| CHUNK_BYTES + i + 1)
+}
+
+// ====================================================================
+// Matcher — 1つのテキストが query にマッチするか
+// ====================================================================
+//
+// search / search_case_insensitive と同じ判定（contains / to_lowercase）を
+// 行単位以外（CSV のフィールドなど）でも使えるように切り出したもの
+// query の小文字化は new で1回だけ行う
+
+pub struct Matcher {
+    query: String,
+    ignore_case: bool,
+}
+
+impl Matcher {
+    pub fn new(query: &str, ignore_case: bool) -> Matcher {
+        let query = if ignore_case {
+            query.to_lowercase()
+        } else {
+            query.to_string()
+        };
+        Matcher { query, ignore_case }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        if self.ignore_case {
+            text.to_lowercase().contains(&self.query)
+        } else {
+            text.contains(&self.query)
+        }
+    }
+
+    // 出現回数（重ならない数え方）— rank の TF に使う
+    pub fn count(&self, text: &str) -> usize {
+        if self.query.is_empty() {
+            return 0;
+        }
+        if self.ignore_case {
+            text.to_lowercase().matches(&self.query).count()
+        } else {
+            text.matches(&self.query).count()
+        }
+    }
+}
+
 // ====================================================================
 // 13.4: ゼロコスト抽象化（Zero-Cost Abstractions）
 // ====================================================================
@@ -131,6 +679,11 @@ pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a st
 //   - ループの境界チェックも不要と判断し除去する
 //
 // → 安心して高レベルな書き方ができる（パフォーマンスのペナルティなし）
+//
+// ただし「手書きループと同じ速さ」と「最速」は別の話:
+//   search は1行ずつ contains するので、どの行も必ず切り出される
+//   fast::search はファイル全体を memchr で探すので、マッチしない行には触れない
+//   → cargo bench で両者を比較できる（benches/search.rs）
 
 // ====================================================================
 // テスト
@@ -139,6 +692,7 @@ pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a st
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::prop::Rng;
 
     // --- Config のテスト ---
 
@@ -162,6 +716,248 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn config_build_field_options() {
+        let args = [
+            "program",
+            "--tsv",
+            "--no-header",
+            "--csv-field",
+            "2",
+            "query",
+            "data.tsv",
+        ]
+        .map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert_eq!(config.query, "query");
+        assert_eq!(config.file_path, "data.tsv");
+        assert_eq!(
+            config.field,
+            Some(FieldSelector::Csv {
+                field: String::from("2"),
+                options: CsvOptions {
+                    delimiter: '\t',
+                    has_header: false,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn config_build_rank_options() {
+        let args = ["program", "--rank", "--top", "3", "query", "docs"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert!(config.rank);
+        assert_eq!(config.top, 3);
+
+        let args = ["program", "--top", "0", "query", "docs"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn config_build_limit_options() {
+        let args = [
+            "program",
+            "--timeout",
+            "500ms",
+            "--max-filesize",
+            "1M",
+            "--max-depth",
+            "2",
+            "query",
+            "src",
+        ]
+        .map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert_eq!(config.timeout, Some(Duration::from_millis(500)));
+        assert_eq!(config.max_filesize, Some(1024 * 1024));
+        assert_eq!(config.max_depth, Some(2));
+
+        let args = ["program", "--timeout", "soon", "query", "src"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn config_build_file_filters() {
+        let args = [
+            "program",
+            "-t",
+            "web",
+            "--type-add",
+            "web:*.vue",
+            "--glob",
+            "!vendor/**",
+            "query",
+            "src",
+        ]
+        .map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert!(config.filter.allows_file("App.vue", Path::new("App.vue")));
+        assert!(
+            !config
+                .filter
+                .allows_file("vendor/App.vue", Path::new("App.vue"))
+        );
+
+        let args = ["program", "-t", "cobol", "query", "src"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+
+        let args = ["program", "--type-list"].map(String::from);
+        assert!(Config::build(args.into_iter()).unwrap().type_list);
+    }
+
+    #[test]
+    fn config_build_only_option() {
+        let args = ["program", "--only", "comments", "query", "src"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert_eq!(config.only, Some(Region::Comment));
+
+        let args = ["program", "--only", "docs", "query", "src"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+
+        let args = ["program", "--only", "code", "--rank", "query", "src"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn only_falls_back_to_plain_search_on_lex_errors() {
+        let args = ["program", "--only", "comments", "open", "x.rs"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        let token = CancelToken::new();
+
+        let contents = "// open
+let open = 1;";
+        let outcome = search_contents(&config, Path::new("x.rs"), contents, &token).unwrap();
+        assert_eq!(outcome.results, vec!["// open"]);
+
+        // 閉じていない文字列 → ファイル全体を普通に検索する
+        let contents = "// open
+let s = \"open;";
+        let outcome = search_contents(&config, Path::new("x.rs"), contents, &token).unwrap();
+        assert_eq!(outcome.results, vec!["// open", "let s = \"open;"]);
+
+        assert!(search_contents(&config, Path::new("x.txt"), contents, &token).is_err());
+    }
+
+    #[test]
+    fn config_build_git_scopes() {
+        let args = ["program", "--git-since", "main~2", "query", "."].map(String::from);
+        assert_eq!(
+            Config::build(args.into_iter()).unwrap().git_scope,
+            Some(GitScope::Since("main~2".to_string()))
+        );
+
+        let args = ["program", "--git-tracked", "--git-modified", "query", "."].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+
+        let args = ["program", "query", ".", "--git-since"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn max_count_stops_early() {
+        let args = ["program", "-m", "2", "query", "poem.txt"].map(String::from);
+        assert_eq!(Config::build(args.into_iter()).unwrap().max_count, Some(2));
+
+        let args = ["program", "-m", "2", "--rank", "query", "poem.txt"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+
+        let contents = "Match\nmatch\nno\nmatch\nMATCH\n";
+        let token = CancelToken::new();
+        // 大文字小文字を区別する（fast::search_iter）経路と、行ごとの経路の両方
+        for (ignore_case, expected) in [
+            (false, vec!["match", "match"]),
+            (true, vec!["Match", "match"]),
+        ] {
+            let matcher = Matcher::new("match", ignore_case);
+            let outcome = search_cancellable(&matcher, contents, &token, 2);
+            assert_eq!(outcome.results, expected);
+            assert_eq!(outcome.interrupted, None);
+        }
+
+        // -m 1 は最初のチャンクの中で止まる（後ろのマッチは読まない。fast.rs のテストも参照）
+        let large = format!("first match\n{}", "match\n".repeat(CHUNK_BYTES));
+        let outcome = search_cancellable(&Matcher::new("match", false), &large, &token, 1);
+        assert_eq!(outcome.results, ["first match"]);
+    }
+
+    // --- search_iter と search が同じ結果を返すことを、ランダムな入力で確かめる ---
+
+    // 乱数は test_support の xorshift — シードが同じなら毎回同じ入力になる
+    fn random_text(rng: &mut Rng, max_len: u64) -> String {
+        const ALPHABET: [char; 8] = ['a', 'b', 'A', 'B', ' ', 'あ', '\n', '\r'];
+        let len = rng.next_u64() % (max_len + 1);
+        (0..len)
+            .map(|_| ALPHABET[rng.below(ALPHABET.len() as u128) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn iterator_api_agrees_with_vec_api() {
+        let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let contents = random_text(&mut rng, 80);
+            let query = random_text(&mut rng, 3).replace(['\n', '\r'], "");
+
+            let lines: Vec<&str> = search_iter(&query, &contents).map(|m| m.line).collect();
+            assert_eq!(
+                lines,
+                search(&query, &contents),
+                "query {query:?} in {contents:?}"
+            );
+
+            let lines: Vec<&str> = search_case_insensitive_iter(&query, &contents)
+                .map(|m| m.line)
+                .collect();
+            assert_eq!(lines, search_case_insensitive(&query, &contents));
+
+            // 行番号は contents.lines() の位置と一致する
+            for m in search_case_insensitive_iter(&query, &contents) {
+                assert_eq!(contents.lines().nth(m.line_number - 1), Some(m.line));
+            }
+
+            // take(n) は Vec の先頭 n 件と同じ（途中で止めても結果が変わらない）
+            let n = rng.below(3) as usize;
+            let first: Vec<&str> = search_iter(&query, &contents)
+                .take(n)
+                .map(|m| m.line)
+                .collect();
+            let all = search(&query, &contents);
+            assert_eq!(first, all[..n.min(all.len())]);
+        }
+    }
+
+    #[test]
+    fn cancelled_search_keeps_partial_results() {
+        let contents = "match\n".repeat(CHECK_INTERVAL * 2);
+        let matcher = Matcher::new("match", false);
+
+        let token = CancelToken::new();
+        let outcome = search_cancellable(&matcher, &contents, &token, usize::MAX);
+        assert_eq!(outcome.results.len(), CHECK_INTERVAL * 2);
+        assert_eq!(outcome.interrupted, None);
+
+        token.cancel();
+        let outcome = search_cancellable(&matcher, &contents, &token, usize::MAX);
+        assert!(outcome.results.is_empty());
+        assert_eq!(outcome.interrupted, Some(Interruption::Cancelled));
+    }
+
+    #[test]
+    fn chunked_search_matches_line_search() {
+        let line = "a".repeat(1000);
+        let contents = format!("{line}\r\nneedle {line}\n").repeat(CHUNK_BYTES / 1000);
+        let matcher = Matcher::new("needle", false);
+        let outcome = search_cancellable(&matcher, &contents, &CancelToken::new(), usize::MAX);
+        assert_eq!(outcome.results, search("needle", &contents));
+    }
+
+    #[test]
+    fn config_build_rejects_unknown_option() {
+        let args = ["program", "--nope", "query", "file.txt"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
     // --- 12.4: search 関数のテスト（大文字小文字を区別） ---
 
     #[test]
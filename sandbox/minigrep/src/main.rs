@@ -29,13 +29,63 @@
 //   cargo run -- the poem.txt
 //   IGNORE_CASE=1 cargo run -- the poem.txt     （大文字小文字を無視）
 //   cargo run -- the poem.txt > output.txt       （結果をファイルに保存）
+//   cargo run -- -m 1 the poem.txt               （最初の1件で止める）
+//
+// フィールド指定検索（records モジュール）:
+//   cargo run -- --csv-field name ferris users.csv        （name 列だけを検索）
+//   cargo run -- --tsv --no-header --csv-field 2 x a.tsv  （ヘッダなし TSV の2列目）
+//   cargo run -- --delimiter ';' --csv-field city Tokyo data.csv
+//   cargo run -- --json-path .ctx.user ferris app.jsonl   （JSON Lines のパス指定）
+//
+// ディレクトリ検索と関連度順（rank モジュール）:
+//   cargo run -- the docs/                       （配下の全ファイルを "パス:行" で出力）
+//   cargo run -- --rank "trait lifetime" docs/   （TF-IDF スコアの高いファイル順）
+//   cargo run -- --rank --top 3 frog poem.txt    （1ファイルなら行単位で上位3件）
+//
+// 長い検索の打ち切り（cancel モジュール）:
+//   cargo run -- --timeout 5s --max-filesize 10M --max-depth 3 TODO .
+//   → 打ち切られた場合はそこまでの結果を出力し、理由を stderr に出して終了コード 1
+//
+// ファイルの絞り込み（filter モジュール）:
+//   cargo run -- -t rust Config .                     （Rust のファイルだけ）
+//   cargo run -- -T markdown --glob '!target/**' x .  （Markdown と target/ を除く）
+//   cargo run -- --type-add 'web:*.vue' -t web x .    （種類を追加して使う）
+//   cargo run -- --type-list                          （種類の一覧）
+//
+// 構文を考慮した検索（syntax モジュール、.rs / .toml / .md のみ）:
+//   cargo run -- --only comments 所有権 src/   （コメントの中だけ）
+//   cargo run -- --only strings "{}" src/lib.rs （文字列リテラルの中だけ）
+//   cargo run -- --only code unwrap notes.md    （Markdown のコードブロック・インラインコード）
+//
+// Git の追跡・変更で絞り込む（git モジュール、.git を直接読む）:
+//   cargo run -- --git-tracked TODO .          （追跡中のファイルだけ）
+//   cargo run -- --git-modified unwrap .       （HEAD から変更したファイルだけ）
+//   cargo run -- --git-since main~3 Config .   （main~3 から変更したファイルだけ）
+//
+// HTTP サーバ（serve モジュール）:
+//   cargo run -- serve --root docs --port 7878
+//   curl 'http://127.0.0.1:7878/search?q=rust&i=1'
 
 use std::env;
 use std::process;
 
 use minigrep::Config;
+use minigrep::serve::{self, ServeConfig};
 
 fn main() {
+    // serve サブコマンド: minigrep serve --root DIR --port N
+    if env::args().nth(1).as_deref() == Some("serve") {
+        let config = ServeConfig::build(env::args().skip(2)).unwrap_or_else(|err| {
+            eprintln!("Problem parsing arguments: {err}");
+            process::exit(1);
+        });
+        if let Err(e) = serve::serve(config) {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+
     // 13.3: env::args() のイテレータを直接渡す
     // 以前: let args: Vec<String> = env::args().collect();
     //       Config::build(&args)
@@ -0,0 +1,107 @@
+// ====================================================================
+// TF-IDF による関連度順の検索（--rank）
+// ====================================================================
+//
+// grep は「見つかった順」に出すが、ドキュメント検索では
+// 「一番関係がありそうなもの」から見たい
+//
+// TF-IDF:
+//   TF  (term frequency)          — その文書の中で語が何回出てくるか
+//   IDF (inverse document frequency) — その語がどれだけ「珍しい」か
+//     → どの文書にも出てくる語（"the" など）は重みが小さくなる
+//
+//   score(d) = Σ_term (1 + ln tf) × idf
+//   idf(t)   = ln((N + 1) / (df + 1)) + 1     ← df = t を含む文書数
+//
+//   - tf は対数で抑える（同じ語を100回書いた文書が100倍強くならないように）
+//   - idf は +1 で平滑化（全文書に出てくる語でも 0 にはしない）
+//
+// 語が出てくるかどうかの判定は search と同じ Matcher を使う
+// → 大文字小文字の扱いが通常の検索と一致する
+
+use crate::Matcher;
+
+#[derive(Debug, PartialEq)]
+pub struct Ranked {
+    pub index: usize, // documents の何番目か
+    pub score: f64,
+}
+
+// query を空白で区切って複数語のクエリとして扱う
+// 1語もマッチしない文書は結果に含めない
+pub fn rank(query: &str, ignore_case: bool, documents: &[&str]) -> Vec<Ranked> {
+    let matchers: Vec<Matcher> = query
+        .split_whitespace()
+        .map(|term| Matcher::new(term, ignore_case))
+        .collect();
+
+    // counts[d][t] = 文書 d に語 t が出てくる回数
+    let counts: Vec<Vec<usize>> = documents
+        .iter()
+        .map(|doc| matchers.iter().map(|m| m.count(doc)).collect())
+        .collect();
+
+    let n = documents.len() as f64;
+    let idf: Vec<f64> = (0..matchers.len())
+        .map(|t| {
+            let df = counts.iter().filter(|c| c[t] > 0).count() as f64;
+            ((n + 1.0) / (df + 1.0)).ln() + 1.0
+        })
+        .collect();
+
+    let mut ranked: Vec<Ranked> = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.iter().any(|&tf| tf > 0))
+        .map(|(index, c)| {
+            let score = c
+                .iter()
+                .zip(&idf)
+                .filter(|&(&tf, _)| tf > 0)
+                .map(|(&tf, idf)| (1.0 + (tf as f64).ln()) * idf)
+                .sum();
+            Ranked { index, score }
+        })
+        .collect();
+
+    // スコアの高い順。同点なら元の順番（結果を決定的にする）
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.index.cmp(&b.index)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rarer_terms_weigh_more() {
+        let documents = [
+            "rust rust rust",
+            "rust ownership",
+            "python",
+            "rust borrow ownership ownership",
+        ];
+        let ranked = rank("rust ownership", false, &documents);
+        let order: Vec<usize> = ranked.iter().map(|r| r.index).collect();
+        // "ownership" は2文書にしか出ない → "rust" だけの文書0より上に来る
+        assert_eq!(order, vec![3, 1, 0]);
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[test]
+    fn respects_ignore_case_like_search() {
+        let documents = ["Rust", "rust", "RUST"];
+        assert_eq!(rank("rust", false, &documents).len(), 1);
+        assert_eq!(rank("rust", true, &documents).len(), 3);
+    }
+
+    #[test]
+    fn ties_keep_document_order() {
+        let documents = ["a x", "b", "a y"];
+        let order: Vec<usize> = rank("a", false, &documents)
+            .iter()
+            .map(|r| r.index)
+            .collect();
+        assert_eq!(order, vec![0, 2]);
+    }
+}
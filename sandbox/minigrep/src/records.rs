@@ -0,0 +1,214 @@
+// ====================================================================
+// フィールド指定検索 — CSV/TSV と JSON Lines
+// ====================================================================
+//
+// line.contains(query) だと「別の列」にマッチしてしまう:
+//   id,name,comment
+//   1,Ferris,"likes rust"   ← name を検索したいのに comment にヒット
+//
+// → レコードをパースして、指定したフィールドの値だけに query を適用する
+// → 出力はレコード全体（元のテキストのまま）
+
+pub mod csv;
+pub mod json;
+
+use std::error::Error;
+use std::fmt;
+
+use crate::Matcher;
+
+// どのフィールドを検索対象にするか（Config から渡される）
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldSelector {
+    // --csv-field: ヘッダ付きなら列名、ヘッダなしなら1始まりの列番号
+    Csv { field: String, options: CsvOptions },
+    // --json-path: ".a.b" 形式のパス
+    Json { path: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: char,  // --delimiter / --tsv
+    pub has_header: bool, // --no-header で false
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            has_header: true,
+        }
+    }
+}
+
+// パース・列指定のエラー（どの行で起きたかを持つ）
+#[derive(Debug, PartialEq)]
+pub struct RecordError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl RecordError {
+    pub fn new(line: usize, message: impl Into<String>) -> RecordError {
+        RecordError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for RecordError {}
+
+pub fn search_fields<'a>(
+    matcher: &Matcher,
+    selector: &FieldSelector,
+    contents: &'a str,
+) -> Result<Vec<&'a str>, RecordError> {
+    match selector {
+        FieldSelector::Csv { field, options } => search_csv(matcher, field, options, contents),
+        FieldSelector::Json { path } => search_json_lines(matcher, path, contents),
+    }
+}
+
+pub fn search_csv<'a>(
+    matcher: &Matcher,
+    field: &str,
+    options: &CsvOptions,
+    contents: &'a str,
+) -> Result<Vec<&'a str>, RecordError> {
+    let records = csv::parse(contents, options.delimiter)?;
+    let mut records = records.into_iter();
+
+    // ヘッダがあれば列名で、なければ列番号で位置を決める
+    let column = if options.has_header {
+        let header = match records.next() {
+            Some(header) => header,
+            None => return Ok(Vec::new()),
+        };
+        header
+            .fields
+            .iter()
+            .position(|name| name == field)
+            .ok_or_else(|| RecordError::new(header.line, format!("no column named `{field}`")))?
+    } else {
+        match field.parse::<usize>() {
+            Ok(n) if n >= 1 => n - 1,
+            _ => {
+                return Err(RecordError::new(
+                    1,
+                    format!("`{field}` is not a column number (use 1, 2, ... with --no-header)"),
+                ));
+            }
+        }
+    };
+
+    // 列が足りないレコードはマッチしない扱い
+    Ok(records
+        .filter(|record| {
+            record
+                .fields
+                .get(column)
+                .is_some_and(|value| matcher.is_match(value))
+        })
+        .map(|record| record.raw)
+        .collect())
+}
+
+pub fn search_json_lines<'a>(
+    matcher: &Matcher,
+    path: &str,
+    contents: &'a str,
+) -> Result<Vec<&'a str>, RecordError> {
+    let mut results = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = json::parse(line).map_err(|message| RecordError::new(i + 1, message))?;
+        // パスが存在しない行はマッチしない扱い
+        if value
+            .get_path(path)
+            .is_some_and(|field| matcher.is_match(&field.to_text()))
+        {
+            results.push(line);
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USERS: &str = "\
+id,name,comment
+1,Ferris,likes rust
+2,Rusty,\"says \"\"hi\"\",
+and waves\"
+3,Corro,rust fan";
+
+    #[test]
+    fn csv_matches_only_selected_column() {
+        let matcher = Matcher::new("rust", false);
+        let results = search_csv(&matcher, "name", &CsvOptions::default(), USERS).unwrap();
+        // comment 列の "rust" は無視され、name 列の "Rusty" もケースが違うのでマッチしない
+        assert!(results.is_empty());
+
+        let matcher = Matcher::new("rust", true);
+        let results = search_csv(&matcher, "name", &CsvOptions::default(), USERS).unwrap();
+        assert_eq!(results, vec!["2,Rusty,\"says \"\"hi\"\",\nand waves\""]);
+    }
+
+    #[test]
+    fn csv_without_header_uses_column_number() {
+        let options = CsvOptions {
+            delimiter: '\t',
+            has_header: false,
+        };
+        let contents = "a\tapple\nb\tbanana\n";
+        let matcher = Matcher::new("nan", false);
+        let results = search_csv(&matcher, "2", &options, contents).unwrap();
+        assert_eq!(results, vec!["b\tbanana"]);
+    }
+
+    #[test]
+    fn csv_unknown_column_is_an_error() {
+        let matcher = Matcher::new("x", false);
+        let err = search_csv(&matcher, "email", &CsvOptions::default(), USERS).unwrap_err();
+        assert_eq!(err.line, 1);
+    }
+
+    #[test]
+    fn json_lines_match_nested_path() {
+        let contents = r#"{"level":"info","msg":"started","ctx":{"user":"ferris"}}
+{"level":"error","msg":"ferris crashed","ctx":{"user":"corro"}}
+
+{"level":"info","msg":"no ctx"}"#;
+        let matcher = Matcher::new("ferris", false);
+        let results = search_json_lines(&matcher, ".ctx.user", contents).unwrap();
+        assert_eq!(
+            results,
+            vec![r#"{"level":"info","msg":"started","ctx":{"user":"ferris"}}"#]
+        );
+    }
+
+    #[test]
+    fn json_lines_report_bad_line() {
+        let matcher = Matcher::new("x", false);
+        let err = search_json_lines(&matcher, ".a", "{\"a\":1}\n{oops}\n").unwrap_err();
+        assert_eq!(err.line, 2);
+
+        // 深すぎる入れ子もその行のエラー（スタックはあふれない）
+        let deep = format!("{{\"a\":1}}\n{}\n", "[".repeat(100_000));
+        let err = search_json_lines(&matcher, ".a", &deep).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
@@ -0,0 +1,158 @@
+// ====================================================================
+// CSV / TSV パーサ（手書き）
+// ====================================================================
+//
+// RFC 4180 をベースにした最小限の実装:
+//   - 区切り文字は設定可能（, や \t）
+//   - "..." で囲まれたフィールドは区切り文字・改行を含められる
+//   - "" は引用符1つにエスケープされる
+//   - 空行はレコードとして扱わない
+//
+// 各レコードは「元テキストのスライス」と「分解したフィールド」の両方を持つ
+// → 検索はフィールド単位、出力はレコード全体（元の見た目のまま）
+
+use super::RecordError;
+
+#[derive(Debug, PartialEq)]
+pub struct CsvRecord<'a> {
+    pub raw: &'a str,        // レコード全体（末尾の改行は含まない）
+    pub fields: Vec<String>, // 引用符を外したフィールド
+    pub line: usize,         // レコードが始まる行番号（1始まり）
+}
+
+pub fn parse(contents: &str, delimiter: char) -> Result<Vec<CsvRecord<'_>>, RecordError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+
+    let mut start = 0; // 現在のレコードの開始バイト位置
+    let mut line = 1; // 現在の行番号
+    let mut record_line = 1; // 現在のレコードの開始行
+    let mut in_quotes = false;
+    let mut after_quote = false; // 閉じ引用符の直後か
+
+    let mut chars = contents.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                // "" はエスケープされた引用符、それ以外は閉じ引用符
+                if let Some(&(_, '"')) = chars.peek() {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                    after_quote = true;
+                }
+            } else {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !after_quote => in_quotes = true,
+            c if c == delimiter => {
+                fields.push(std::mem::take(&mut field));
+                after_quote = false;
+            }
+            '\n' | '\r' => {
+                if c == '\r' && chars.peek().is_some_and(|&(_, next)| next == '\n') {
+                    chars.next();
+                }
+                fields.push(std::mem::take(&mut field));
+                push_record(
+                    &mut records,
+                    &contents[start..i],
+                    std::mem::take(&mut fields),
+                    record_line,
+                    after_quote,
+                );
+
+                start = chars.peek().map_or(contents.len(), |&(j, _)| j);
+                line += 1;
+                record_line = line;
+                after_quote = false;
+            }
+            _ if after_quote => {
+                return Err(RecordError::new(
+                    line,
+                    format!("unexpected character `{c}` after closing quote"),
+                ));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(RecordError::new(record_line, "unterminated quoted field"));
+    }
+
+    if start < contents.len() || !fields.is_empty() {
+        fields.push(field);
+        push_record(
+            &mut records,
+            &contents[start..],
+            fields,
+            record_line,
+            after_quote,
+        );
+    }
+
+    Ok(records)
+}
+
+// 空行（引用符なしの空フィールド1つだけ）はレコードにしない
+fn push_record<'a>(
+    records: &mut Vec<CsvRecord<'a>>,
+    raw: &'a str,
+    fields: Vec<String>,
+    line: usize,
+    quoted: bool,
+) {
+    let blank = fields.len() == 1 && fields[0].is_empty() && !quoted;
+    if !blank {
+        records.push(CsvRecord { raw, fields, line });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_simple_records() {
+        let records = parse("a,b,c\n1,2,3\n", ',').unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].fields, vec!["1", "2", "3"]);
+        assert_eq!(records[1].raw, "1,2,3");
+        assert_eq!(records[1].line, 2);
+    }
+
+    #[test]
+    fn quoted_field_can_span_lines() {
+        let contents = "id,note\n1,\"first line\nsecond, line\"\n2,\"say \"\"hi\"\"\"\n";
+        let records = parse(contents, ',').unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].fields[1], "first line\nsecond, line");
+        assert_eq!(records[1].raw, "1,\"first line\nsecond, line\"");
+        assert_eq!(records[2].fields[1], "say \"hi\"");
+        assert_eq!(records[2].line, 4);
+    }
+
+    #[test]
+    fn tab_delimiter_and_blank_lines() {
+        let records = parse("x\ty\r\n\r\n1\t2", '\t').unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].fields, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_an_error() {
+        let err = parse("a,b\n1,\"oops\n", ',').unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
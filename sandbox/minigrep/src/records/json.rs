@@ -0,0 +1,348 @@
+// ====================================================================
+// JSON パーサ（手書き・再帰下降）
+// ====================================================================
+//
+// JSON Lines の1行 = 1つの JSON 値
+// 検索に必要なのは「パスで指定したフィールドの値」だけなので、
+// 数値は元の文字列のまま保持する（f64 に変換すると桁が落ちるため）
+// 入れ子は MAX_DEPTH 段まで（再帰で読むので、深すぎる行でスタックをあふれさせない）
+
+#[derive(Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    // ".a.b" / ".items.0" のようなパスで値をたどる
+    // 配列は数値のセグメントでインデックス指定できる
+    pub fn get_path(&self, path: &str) -> Option<&Json> {
+        path.split('.')
+            .filter(|segment| !segment.is_empty())
+            .try_fold(self, |value, segment| match value {
+                Json::Object(entries) => entries
+                    .iter()
+                    .find(|(key, _)| key == segment)
+                    .map(|(_, v)| v),
+                Json::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            })
+    }
+
+    // 検索対象のテキストにする
+    //   文字列 → エスケープを外した中身
+    //   それ以外 → JSON としての表記
+    pub fn to_text(&self) -> String {
+        match self {
+            Json::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Number(n) => write!(f, "{n}"),
+            Json::String(s) => write!(f, "{s:?}"),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{key:?}:{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 128;
+
+pub fn parse(input: &str) -> Result<Json, String> {
+    let mut parser = Parser {
+        bytes: input.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.bytes.len() {
+        return Err(format!("trailing characters at column {}", parser.pos + 1));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    // いま開いている [ と { の数
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Err(format!("{message} at column {}", self.pos + 1))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", byte as char))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            self.error("invalid literal")
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(open @ (b'{' | b'[')) => {
+                if self.depth == MAX_DEPTH {
+                    return self.error("nesting too deep");
+                }
+                self.depth += 1;
+                let value = if open == b'{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => self.error("unexpected character"),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            let value = self.value()?;
+            entries.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return self.error("expected `,` or `}`"),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return self.error("expected `,` or `]`"),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let digits_start = self.pos;
+        while let Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+        if self.pos == digits_start {
+            return self.error("invalid number");
+        }
+        // 入力は &str 由来で、ここまでは ASCII しか読んでいない
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        Ok(Json::Number(text.to_string()))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            // エスケープも引用符もない区間はまとめてコピーする
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let c = self.unicode_escape()?;
+                            out.push(c);
+                            continue;
+                        }
+                        _ => return self.error("invalid escape"),
+                    };
+                    self.pos += 1;
+                    out.push(escaped);
+                }
+                _ => return self.error("unterminated string"),
+            }
+        }
+    }
+
+    // \uXXXX（サロゲートペア 😀 にも対応）
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        if (0xD800..0xDC00).contains(&high) {
+            if self.bytes[self.pos..].starts_with(b"\\u") {
+                self.pos += 2;
+                let low = self.hex4()?;
+                if (0xDC00..0xE000).contains(&low) {
+                    let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                    return char::from_u32(code)
+                        .map_or_else(|| self.error("invalid surrogate"), Ok);
+                }
+            }
+            return self.error("invalid surrogate");
+        }
+        char::from_u32(high).map_or_else(|| self.error("invalid unicode escape"), Ok)
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        // from_str_radix は先頭の + も読んでしまうので、4桁とも16進数字か先に確かめる
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .filter(|b| b.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .and_then(|s| u32::from_str_radix(s, 16).ok());
+        match digits {
+            Some(n) => {
+                self.pos += 4;
+                Ok(n)
+            }
+            None => self.error("invalid unicode escape"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(r#"{"user": {"name": "Ferris", "tags": ["rust", "crab"]}, "n": -1.5e3}"#)
+            .unwrap();
+        assert_eq!(
+            value.get_path(".user.name"),
+            Some(&Json::String(String::from("Ferris")))
+        );
+        assert_eq!(value.get_path(".user.tags.1").unwrap().to_text(), "crab");
+        assert_eq!(value.get_path("n").unwrap().to_text(), "-1.5e3");
+        assert_eq!(value.get_path(".missing"), None);
+    }
+
+    #[test]
+    fn decodes_escapes() {
+        let value = parse(r#""こん \"hi\" \ud83e\udd80""#).unwrap();
+        assert_eq!(value.to_text(), "こん \"hi\" 🦀");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse(r#"{"a": }"#).is_err());
+        assert!(parse(r#"{"a": 1} x"#).is_err());
+        assert!(parse(r#""open"#).is_err());
+        assert!(parse(r#""\u+123""#).is_err());
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        assert_eq!(
+            parse(&"[".repeat(200_000)),
+            Err(String::from("nesting too deep at column 129"))
+        );
+        let nested = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse(&nested).is_ok());
+    }
+}
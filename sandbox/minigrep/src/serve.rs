@@ -0,0 +1,665 @@
+// ====================================================================
+// minigrep serve — HTTP で検索を提供する（std の TcpListener だけで実装）
+// ====================================================================
+//
+// 使い方:
+//   minigrep serve --root docs --port 7878 [--threads 4]
+//
+//   GET /                          → 検索フォーム（HTML）
+//   GET /search?q=rust             → JSON で結果を返す
+//   GET /search?q=rust&i=1         → 大文字小文字を無視
+//   GET /search?q=rust&path=guide  → root 配下の guide だけを検索
+//
+// 21章の Web サーバと同じ構成:
+//   - TcpListener で接続を受け付ける
+//   - ThreadPool（mpsc チャネル + Arc<Mutex<Receiver>>）で並行に処理
+//   - Drop でワーカーを join して行儀よく終了する
+//
+// 検索そのものはライブラリの search_iter / search_case_insensitive_iter を使う
+//
+// ワーカーは数個しかないので、1つの接続に居座られないよう制限をかける
+//   - 読み書きのタイムアウト（IO_TIMEOUT）— 黙ったままのクライアントは 408
+//   - リクエスト行は MAX_REQUEST_LINE バイトまで（超えたら 414）
+//   - ヘッダは合計 MAX_HEADER_BYTES バイト・MAX_HEADER_LINES 行まで（超えたら 431）
+//   - 1回の検索で返す結果は MAX_RESULTS 件まで（超えた分は捨てて "truncated": true）
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use crate::walk::{self, WalkOptions};
+use crate::{Match, search_case_insensitive_iter, search_iter};
+
+// ====================================================================
+// ServeConfig — serve サブコマンドの設定
+// ====================================================================
+
+#[derive(Debug, PartialEq)]
+pub struct ServeConfig {
+    pub root: PathBuf,
+    pub port: u16,
+    pub threads: usize,
+}
+
+impl ServeConfig {
+    // args はプログラム名と "serve" を読み飛ばした残り
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<ServeConfig, &'static str> {
+        let mut root = None;
+        let mut port = 7878;
+        let mut threads = 4;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--root" => {
+                    root = Some(PathBuf::from(
+                        args.next().ok_or("--root requires a directory")?,
+                    ))
+                }
+                "--port" => {
+                    port = args
+                        .next()
+                        .and_then(|p| p.parse().ok())
+                        .ok_or("--port requires a port number")?;
+                }
+                "--threads" => {
+                    threads = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--threads requires a positive number")?;
+                }
+                _ => return Err("Unknown option for serve"),
+            }
+        }
+
+        Ok(ServeConfig {
+            root: root.ok_or("serve requires --root")?,
+            port,
+            threads,
+        })
+    }
+}
+
+pub fn serve(config: ServeConfig) -> Result<(), Box<dyn Error>> {
+    // canonicalize しておくと、後でパスが root の外に出ていないか比較できる
+    let root = Arc::new(config.root.canonicalize()?);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()).into());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", config.port))?;
+    let pool = ThreadPool::new(config.threads);
+    println!(
+        "Serving {} on http://{}",
+        root.display(),
+        listener.local_addr()?
+    );
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("connection failed: {e}");
+                continue;
+            }
+        };
+        let root = Arc::clone(&root);
+        pool.execute(move || {
+            if let Err(e) = handle_connection(stream, &root) {
+                eprintln!("request failed: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+// ====================================================================
+// リクエスト処理
+// ====================================================================
+
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REQUEST_LINE: usize = 8 * 1024;
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_HEADER_LINES: usize = 100;
+const MAX_RESULTS: usize = 1000;
+
+pub fn handle_connection(mut stream: TcpStream, root: &Path) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let response = match read_request(&mut BufReader::new(&stream)) {
+        Ok(request_line) => respond(&request_line, root),
+        Err(response) => response,
+    };
+    stream.write_all(&response.to_bytes())?;
+    stream.flush()
+}
+
+// リクエスト行（"GET /search?q=x HTTP/1.1"）を返す
+// ヘッダは使わないが、読み切ってから返事をする（途中で切るとクライアントがエラーになる）
+fn read_request(reader: &mut impl BufRead) -> Result<String, Response> {
+    let read_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            Response::error("408 Request Timeout", "timed out reading the request")
+        }
+        _ => Response::error("400 Bad Request", "could not read the request"),
+    };
+
+    let mut request_line = String::new();
+    reader
+        .take(MAX_REQUEST_LINE as u64)
+        .read_line(&mut request_line)
+        .map_err(read_error)?;
+    if request_line.len() == MAX_REQUEST_LINE && !request_line.ends_with('\n') {
+        return Err(Response::error(
+            "414 URI Too Long",
+            "request line is too long",
+        ));
+    }
+
+    let too_large = || {
+        Response::error(
+            "431 Request Header Fields Too Large",
+            "request headers are too large",
+        )
+    };
+    let mut header = String::new();
+    let mut header_bytes = 0;
+    for _ in 0..MAX_HEADER_LINES {
+        header.clear();
+        let n = reader
+            .take((MAX_HEADER_BYTES - header_bytes) as u64)
+            .read_line(&mut header)
+            .map_err(read_error)?;
+        header_bytes += n;
+        if n == 0 && header_bytes < MAX_HEADER_BYTES {
+            // 空行の前に接続が閉じられた。リクエスト行だけでも答えておく
+            return Ok(request_line.trim_end().to_string());
+        }
+        if !header.ends_with('\n') {
+            return Err(too_large());
+        }
+        if header.trim_end().is_empty() {
+            return Ok(request_line.trim_end().to_string());
+        }
+    }
+    Err(too_large())
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &'static str, body: String) -> Response {
+        Response {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn error(status: &'static str, message: &str) -> Response {
+        let body = format!("{{\"error\":{}}}", json_string(message));
+        Response::new(status, "application/json; charset=utf-8", body)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+fn respond(request_line: &str, root: &Path) -> Response {
+    let mut parts = request_line.split(' ');
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method, target),
+        _ => return Response::error("400 Bad Request", "malformed request line"),
+    };
+    if method != "GET" {
+        return Response::error("405 Method Not Allowed", "only GET is supported");
+    }
+
+    let (route, query) = target.split_once('?').unwrap_or((target, ""));
+    match route {
+        "/" => Response::new(
+            "200 OK",
+            "text/html; charset=utf-8",
+            String::from(INDEX_HTML),
+        ),
+        "/search" => search_route(&parse_query(query), root),
+        _ => Response::error("404 Not Found", "not found"),
+    }
+}
+
+fn search_route(params: &[(String, String)], root: &Path) -> Response {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let query = match param("q") {
+        Some(q) if !q.is_empty() => q,
+        _ => return Response::error("400 Bad Request", "missing query parameter `q`"),
+    };
+    let ignore_case = matches!(param("i"), Some("1" | "true"));
+
+    let target = match resolve(root, param("path").unwrap_or("")) {
+        Some(target) => target,
+        None => {
+            return Response::error("403 Forbidden", "path is hidden or outside of the root");
+        }
+    };
+
+    let files = if target.is_dir() {
+        match walk::files(&target, &WalkOptions::default()) {
+            Ok(files) => files,
+            Err(e) => return Response::error("500 Internal Server Error", &e.to_string()),
+        }
+    } else if target.is_file() {
+        vec![target]
+    } else {
+        return Response::error("404 Not Found", "no such path");
+    };
+
+    let mut results = String::new();
+    let mut count = 0;
+    let mut truncated = false;
+    'files: for file in &files {
+        let contents = match walk::read_text(file) {
+            Ok(Some(contents)) => contents,
+            _ => continue,
+        };
+        // Match が行番号を持っているので、そのまま JSON にできる
+        let matches: Box<dyn Iterator<Item = Match>> = if ignore_case {
+            Box::new(search_case_insensitive_iter(query, &contents))
+        } else {
+            Box::new(search_iter(query, &contents))
+        };
+        let relative = file.strip_prefix(root).unwrap_or(file);
+
+        for Match { line_number, line } in matches {
+            if count == MAX_RESULTS {
+                truncated = true;
+                break 'files;
+            }
+            count += 1;
+            if !results.is_empty() {
+                results.push(',');
+            }
+            let _ = write!(
+                results,
+                "{{\"path\":{},\"line\":{line_number},\"text\":{}}}",
+                json_string(&relative.to_string_lossy()),
+                json_string(line)
+            );
+        }
+    }
+
+    let body = format!(
+        "{{\"query\":{},\"ignore_case\":{ignore_case},\"truncated\":{truncated},\"results\":[{results}]}}",
+        json_string(query)
+    );
+    Response::new("200 OK", "application/json; charset=utf-8", body)
+}
+
+// ====================================================================
+// パストラバーサル対策
+// ====================================================================
+//
+// ?path=../../etc/passwd のような指定で root の外を読まれないようにする
+//   1. ".." や絶対パスを含む指定はその時点で拒否
+//      "." で始まる名前（.git/config など）も拒否する — ディレクトリの走査でも飛ばしている隠しファイル
+//   2. シンボリックリンクで外に出ている場合に備え、
+//      canonicalize した結果が root の中にあるかも確認する
+
+pub fn resolve(root: &Path, requested: &str) -> Option<PathBuf> {
+    let requested = Path::new(requested);
+    let allowed = |c: Component| match c {
+        Component::Normal(name) => !name.to_string_lossy().starts_with('.'),
+        Component::CurDir => true,
+        _ => false,
+    };
+    if !requested.components().all(allowed) {
+        return None;
+    }
+
+    let joined = root.join(requested);
+    match joined.canonicalize() {
+        Ok(real) if real.starts_with(root) => Some(real),
+        Ok(_) => None,
+        // 存在しないパスは後で 404 にする
+        Err(_) => Some(joined),
+    }
+}
+
+// ====================================================================
+// クエリ文字列のデコード
+// ====================================================================
+//
+// "q=hello+world&i=1" → [("q", "hello world"), ("i", "1")]
+// %XX はバイト列に戻してから UTF-8 として解釈する（日本語の検索語のため）
+
+pub fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="ja">
+<head><meta charset="utf-8"><title>minigrep</title></head>
+<body>
+  <h1>minigrep</h1>
+  <form action="/search" method="get">
+    <input name="q" placeholder="検索語" autofocus>
+    <input name="path" placeholder="サブディレクトリ（任意）">
+    <label><input type="checkbox" name="i" value="1"> 大文字小文字を無視</label>
+    <button type="submit">検索</button>
+  </form>
+</body>
+</html>
+"#;
+
+// ====================================================================
+// ThreadPool（21章と同じ形）
+// ====================================================================
+//
+// - new(size) で size 個のワーカースレッドを起動
+// - execute(f) でクロージャをチャネルに送る → 空いているワーカーが受け取る
+// - Drop で送信側を閉じる → ワーカーの recv() が Err になりループを抜ける → join
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: Option<mpsc::Sender<Job>>,
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> ThreadPool {
+        assert!(size > 0);
+
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size)
+            .map(|id| Worker::new(id, Arc::clone(&receiver)))
+            .collect();
+
+        ThreadPool {
+            workers,
+            sender: Some(sender),
+        }
+    }
+
+    pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender.as_ref().unwrap().send(Box::new(f)).unwrap();
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                thread.join().unwrap();
+            }
+        }
+    }
+}
+
+struct Worker {
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Worker {
+        let thread = thread::Builder::new()
+            .name(format!("minigrep-worker-{id}"))
+            .spawn(move || {
+                loop {
+                    // ロックはジョブを受け取る間だけ持つ（let で一時値をすぐ捨てる）
+                    let message = receiver.lock().unwrap().recv();
+                    match message {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                }
+            })
+            .unwrap();
+
+        Worker {
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn decodes_query_string() {
+        let params = parse_query("q=%E3%82%AB%E3%83%8B+crab&i=1&flag");
+        assert_eq!(
+            params,
+            vec![
+                (String::from("q"), String::from("カニ crab")),
+                (String::from("i"), String::from("1")),
+                (String::from("flag"), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_root() {
+        let root = std::env::temp_dir().canonicalize().unwrap();
+        assert!(resolve(&root, "../etc").is_none());
+        assert!(resolve(&root, "/etc/passwd").is_none());
+        assert!(resolve(&root, "a/../../b").is_none());
+        assert_eq!(resolve(&root, ""), Some(root.clone()));
+    }
+
+    #[test]
+    fn rejects_hidden_paths() {
+        let root = std::env::temp_dir().canonicalize().unwrap();
+        assert!(resolve(&root, ".git/config").is_none());
+        assert!(resolve(&root, "docs/.env").is_none());
+        assert!(resolve(&root, "./.hidden").is_none());
+        assert_eq!(resolve(&root, "./docs"), Some(root.join("docs")));
+    }
+
+    fn status_of(request: &[u8]) -> Result<String, &'static str> {
+        read_request(&mut BufReader::new(request)).map_err(|r| r.status)
+    }
+
+    #[test]
+    fn limits_request_line_and_headers() {
+        assert_eq!(
+            status_of(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Ok(String::from("GET / HTTP/1.1"))
+        );
+        // 空行の前に閉じられても、リクエスト行には答える
+        assert_eq!(
+            status_of(b"GET / HTTP/1.1\r\n"),
+            Ok(String::from("GET / HTTP/1.1"))
+        );
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_REQUEST_LINE));
+        assert_eq!(status_of(long_line.as_bytes()), Err("414 URI Too Long"));
+
+        let big_header = format!(
+            "GET / HTTP/1.1\r\nX: {}\r\n\r\n",
+            "a".repeat(MAX_HEADER_BYTES)
+        );
+        assert_eq!(
+            status_of(big_header.as_bytes()),
+            Err("431 Request Header Fields Too Large")
+        );
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X: 1\r\n".repeat(MAX_HEADER_LINES)
+        );
+        assert_eq!(
+            status_of(many_headers.as_bytes()),
+            Err("431 Request Header Fields Too Large")
+        );
+    }
+
+    #[test]
+    fn slow_clients_get_408() {
+        // 読み込みのタイムアウトは TimedOut（Windows）か WouldBlock（Unix）のエラーになる
+        struct Silent;
+        impl Read for Silent {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::WouldBlock.into())
+            }
+        }
+        let status = read_request(&mut BufReader::new(Silent)).map_err(|r| r.status);
+        assert_eq!(status, Err("408 Request Timeout"));
+    }
+
+    #[test]
+    fn serves_search_results_as_json() {
+        let root = std::env::temp_dir().join(format!("minigrep-serve-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("guide")).unwrap();
+        fs::write(
+            root.join("guide/intro.md"),
+            "Rust:\n\"safe\" and fast\nTrust me.\n",
+        )
+        .unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_root = root.clone();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream, &server_root).unwrap();
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client
+            .write_all(b"GET /search?q=rust&i=1 HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(
+            "\"results\":[{\"path\":\"guide/intro.md\",\"line\":1,\"text\":\"Rust:\"},\
+             {\"path\":\"guide/intro.md\",\"line\":3,\"text\":\"Trust me.\"}]}"
+        ));
+    }
+
+    #[test]
+    fn reports_errors_with_status_codes() {
+        let root = std::env::temp_dir().canonicalize().unwrap();
+        assert_eq!(
+            respond("GET /search HTTP/1.1", &root).status,
+            "400 Bad Request"
+        );
+        assert_eq!(
+            respond("GET /search?q=x&path=.. HTTP/1.1", &root).status,
+            "403 Forbidden"
+        );
+        assert_eq!(
+            respond("POST / HTTP/1.1", &root).status,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(respond("GET /nope HTTP/1.1", &root).status, "404 Not Found");
+        assert_eq!(respond("GET / HTTP/1.1", &root).status, "200 OK");
+    }
+
+    #[test]
+    fn caps_the_number_of_results() {
+        let root = std::env::temp_dir().join(format!("minigrep-serve-cap-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("many.txt"), "rust\n".repeat(MAX_RESULTS + 500)).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let body = respond("GET /search?q=rust HTTP/1.1", &root).body;
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(body.contains("\"truncated\":true"));
+        assert_eq!(body.matches("\"line\":").count(), MAX_RESULTS);
+    }
+}
@@ -0,0 +1,245 @@
+// ====================================================================
+// 構文を考慮した検索（--only comments|strings|code）
+// ====================================================================
+//
+// このリポジトリの .rs はコメントに日本語の学習ノートが多い
+//   → 「コメントの中だけ」「コードの中だけ」を検索できると便利
+//
+// しくみ:
+//   1. 言語ごとの簡易レキサがファイルを「バイト範囲 → 種類」に分類する
+//   2. 選んだ種類の範囲だけに Matcher を当てる
+//   3. マッチした範囲を含む行を出力する
+//
+// 対応言語と分類:
+//   Rust     — // /* */ はコメント、"..." r#"..."# b"..." '...' は文字列、残りはコード
+//   TOML     — # はコメント、"..." '...' """...""" '''...''' は文字列、残りはコード
+//   Markdown — ```フェンス``` と `インライン` はコード、本文と <!-- --> はコメント扱い
+//              （本文は人が読む文章なので、ドキュメンテーションコメントと同じ扱いにする）
+//
+// 閉じていないコメントや文字列などでレキサが失敗したら、
+// そのファイルだけ普通の検索に戻す（run が stderr に知らせる）
+
+pub mod markdown;
+pub mod rust;
+pub mod toml;
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use crate::Matcher;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region {
+    Code,
+    Comment,
+    String,
+}
+
+impl Region {
+    // --only の値
+    pub fn parse(value: &str) -> Option<Region> {
+        match value {
+            "code" => Some(Region::Code),
+            "comments" | "comment" => Some(Region::Comment),
+            "strings" | "string" => Some(Region::String),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub region: Region,
+    pub start: usize, // バイト位置（char の境界）
+    pub end: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LexError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl LexError {
+    // offset から行番号を求めて作る
+    pub fn at(src: &str, offset: usize, message: &'static str) -> LexError {
+        let line = src.as_bytes()[..offset]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1;
+        LexError { line, message }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for LexError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Rust,
+    Toml,
+    Markdown,
+}
+
+impl Language {
+    pub fn detect(path: &Path) -> Option<Language> {
+        if path.file_name().is_some_and(|name| name == "Cargo.lock") {
+            return Some(Language::Toml);
+        }
+        match path.extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "toml" => Some(Language::Toml),
+            "md" | "markdown" => Some(Language::Markdown),
+            _ => None,
+        }
+    }
+}
+
+// ファイル全体を隙間なく Span で覆う
+//   レキサはコメント・文字列などの「目立つ」範囲だけを返し、
+//   隙間は言語ごとの既定の種類（Rust/TOML はコード、Markdown は本文＝コメント）で埋める
+pub fn classify(language: Language, src: &str) -> Result<Vec<Span>, LexError> {
+    let (marked, gap) = match language {
+        Language::Rust => (rust::lex(src)?, Region::Code),
+        Language::Toml => (toml::lex(src)?, Region::Code),
+        Language::Markdown => (markdown::lex(src)?, Region::Comment),
+    };
+
+    let mut spans = Vec::with_capacity(marked.len() * 2 + 1);
+    let mut pos = 0;
+    for span in marked {
+        if pos < span.start {
+            spans.push(Span {
+                region: gap,
+                start: pos,
+                end: span.start,
+            });
+        }
+        pos = span.end;
+        spans.push(span);
+    }
+    if pos < src.len() {
+        spans.push(Span {
+            region: gap,
+            start: pos,
+            end: src.len(),
+        });
+    }
+    Ok(spans)
+}
+
+// 指定した種類の範囲にマッチがある行を返す
+// 複数行にまたがるコメントなどは行ごとに切って判定する
+pub fn search_regions<'a>(
+    matcher: &Matcher,
+    contents: &'a str,
+    spans: &[Span],
+    region: Region,
+) -> Vec<&'a str> {
+    let base = contents.as_ptr() as usize;
+    let mut selected = spans.iter().filter(|s| s.region == region).peekable();
+    let mut results = Vec::new();
+
+    for line in contents.lines() {
+        let line_start = line.as_ptr() as usize - base;
+        let line_end = line_start + line.len();
+
+        // この行より前で終わる範囲は読み捨てる
+        while selected.next_if(|s| s.end <= line_start).is_some() {}
+
+        let hit = selected
+            .clone()
+            .take_while(|s| s.start < line_end)
+            .any(|s| {
+                let start = s.start.max(line_start);
+                let end = s.end.min(line_end);
+                start < end && matcher.is_match(&contents[start..end])
+            });
+        if hit {
+            results.push(line);
+        }
+    }
+
+    results
+}
+
+// 文字列・コメントの終わりを探すときの共通処理: needle が現れる位置（なければ None）
+fn find_from(src: &str, from: usize, needle: &str) -> Option<usize> {
+    src[from..].find(needle).map(|i| from + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_comments_in_rust() {
+        let contents = "\
+// 所有権のメモ
+let s = \"所有権\"; // ここもコメント
+let 所有権 = 1;";
+        let spans = classify(Language::Rust, contents).unwrap();
+        let matcher = Matcher::new("所有権", false);
+
+        assert_eq!(
+            search_regions(&matcher, contents, &spans, Region::Comment),
+            vec!["// 所有権のメモ"]
+        );
+        assert_eq!(
+            search_regions(&matcher, contents, &spans, Region::String),
+            vec!["let s = \"所有権\"; // ここもコメント"]
+        );
+        assert_eq!(
+            search_regions(&matcher, contents, &spans, Region::Code),
+            vec!["let 所有権 = 1;"]
+        );
+    }
+
+    #[test]
+    fn spans_cover_the_whole_file() {
+        let src = "a = \"x\" # c\n";
+        let spans = classify(Language::Toml, src).unwrap();
+        let covered: usize = spans.iter().map(|s| s.end - s.start).sum();
+        assert_eq!(covered, src.len());
+        assert!(spans.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn multiline_comment_matches_per_line() {
+        let contents = "/* first\n   needle here\n*/ let needle = 1;";
+        let spans = classify(Language::Rust, contents).unwrap();
+        let matcher = Matcher::new("needle", false);
+        assert_eq!(
+            search_regions(&matcher, contents, &spans, Region::Comment),
+            vec!["   needle here"]
+        );
+        assert_eq!(
+            search_regions(&matcher, contents, &spans, Region::Code),
+            vec!["*/ let needle = 1;"]
+        );
+    }
+
+    #[test]
+    fn detects_language_from_path() {
+        assert_eq!(
+            Language::detect(Path::new("src/lib.rs")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::detect(Path::new("Cargo.lock")),
+            Some(Language::Toml)
+        );
+        assert_eq!(
+            Language::detect(Path::new("notes.md")),
+            Some(Language::Markdown)
+        );
+        assert_eq!(Language::detect(Path::new("poem.txt")), None);
+    }
+}
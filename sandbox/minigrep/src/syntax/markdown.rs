@@ -0,0 +1,175 @@
+// ====================================================================
+// Markdown の簡易レキサ
+// ====================================================================
+//
+//   ```rust ... ``` / ~~~ ... ~~~ — フェンスコードブロック（コード）
+//   `code` / ``co`de``            — インラインコード（同じ数のバッククォートで閉じる）
+//   <!-- ... -->                  — HTML コメント（コメント）
+//   それ以外の本文は classify が「コメント」として埋める
+//
+// CommonMark と同じく、閉じていないフェンスはファイル末尾までコード、
+// 対になるバッククォートがない ` はただの文字として扱う
+// （閉じていない HTML コメントだけはエラーにする）
+
+use super::{LexError, Region, Span, find_from};
+
+pub fn lex(src: &str) -> Result<Vec<Span>, LexError> {
+    let mut spans = Vec::new();
+    let mut fence: Option<(usize, char, usize)> = None; // (開始位置, 文字, 長さ)
+    let mut offset = 0;
+
+    for line in src.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        // フェンスの中: 閉じフェンスが来るまで全部コード
+        if let Some((start, fence_char, fence_len)) = fence {
+            if indent <= 3
+                && fence_run(trimmed, fence_char) >= fence_len
+                && trimmed.trim_start_matches(fence_char).trim().is_empty()
+            {
+                spans.push(Span {
+                    region: Region::Code,
+                    start,
+                    end: line_start + line.trim_end_matches(['\n', '\r']).len(),
+                });
+                fence = None;
+            }
+            continue;
+        }
+
+        if indent <= 3 {
+            for fence_char in ['`', '~'] {
+                let run = fence_run(trimmed, fence_char);
+                if run >= 3 {
+                    fence = Some((line_start + indent, fence_char, run));
+                }
+            }
+            if fence.is_some() {
+                continue;
+            }
+        }
+
+        inline(src, line_start, line_start + line.len(), &mut spans)?;
+    }
+
+    if let Some((start, _, _)) = fence {
+        spans.push(Span {
+            region: Region::Code,
+            start,
+            end: src.len(),
+        });
+    }
+
+    // HTML コメントが複数行にまたがると重なることがあるので、開始位置順に並べて重なりを除く
+    spans.sort_by_key(|s| s.start);
+    let mut merged: Vec<Span> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match merged.last() {
+            Some(last) if span.start < last.end => {}
+            _ => merged.push(span),
+        }
+    }
+    Ok(merged)
+}
+
+fn fence_run(text: &str, fence_char: char) -> usize {
+    text.chars().take_while(|&c| c == fence_char).count()
+}
+
+// 1行の中のインラインコードと HTML コメントを探す
+// HTML コメントは行をまたいでよいので、閉じ --> は src 全体から探す
+fn inline(src: &str, from: usize, to: usize, spans: &mut Vec<Span>) -> Result<(), LexError> {
+    let mut i = from;
+    while i < to {
+        let rest = &src[i..to];
+        if rest.starts_with("<!--") {
+            let end = find_from(src, i + 4, "-->")
+                .ok_or_else(|| LexError::at(src, i, "unterminated HTML comment"))?;
+            spans.push(Span {
+                region: Region::Comment,
+                start: i,
+                end: end + 3,
+            });
+            if end + 3 > to {
+                return Ok(());
+            }
+            i = end + 3;
+        } else if rest.starts_with('`') {
+            let ticks = fence_run(rest, '`');
+            let closing = "`".repeat(ticks);
+            // 閉じるのは「ちょうど同じ数」のバッククォート
+            // （match_indices は左から探すので、連続したバッククォートの先頭で必ず当たる）
+            let close = src[i + ticks..to]
+                .match_indices(closing.as_str())
+                .map(|(k, _)| i + ticks + k)
+                .find(|&j| !src[..j].ends_with('`') && fence_run(&src[j..to], '`') == ticks);
+            match close {
+                Some(j) => {
+                    spans.push(Span {
+                        region: Region::Code,
+                        start: i,
+                        end: j + ticks,
+                    });
+                    i = j + ticks;
+                }
+                None => i += ticks,
+            }
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(src: &str) -> Vec<(Region, &str)> {
+        lex(src)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.region, &src[s.start..s.end]))
+            .collect()
+    }
+
+    #[test]
+    fn fences_inline_code_and_comments() {
+        let src = "# 見出し\n`Vec<T>` は便利 <!-- memo -->\n```rust\nlet v = vec![1];\n```\n本文";
+        assert_eq!(
+            regions(src),
+            vec![
+                (Region::Code, "`Vec<T>`"),
+                (Region::Comment, "<!-- memo -->"),
+                (Region::Code, "```rust\nlet v = vec![1];\n```"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_backticks_and_open_fences() {
+        assert!(regions("a ` b").is_empty());
+        assert_eq!(regions("``a`b``"), vec![(Region::Code, "``a`b``")]);
+        assert_eq!(regions("~~~\ncode"), vec![(Region::Code, "~~~\ncode")]);
+    }
+
+    #[test]
+    fn japanese_inline_code() {
+        assert_eq!(
+            regions("これは `コード` と ``バック`クォート`` です"),
+            vec![
+                (Region::Code, "`コード`"),
+                (Region::Code, "``バック`クォート``"),
+            ]
+        );
+        assert!(regions("閉じない `コード").is_empty());
+    }
+
+    #[test]
+    fn unterminated_html_comment_is_an_error() {
+        assert_eq!(lex("ok\n<!-- open").unwrap_err().line, 2);
+    }
+}
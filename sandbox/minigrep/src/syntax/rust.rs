@@ -0,0 +1,204 @@
+// ====================================================================
+// Rust の簡易レキサ
+// ====================================================================
+//
+// コメントと文字列リテラルの範囲だけを見つける（トークン全体は分解しない）
+//   - // 行コメント（/// や //! のドキュメンテーションコメントも含む）
+//   - /* */ ブロックコメント（Rust はネストできる）
+//   - "..." b"..." c"..."   — \ でエスケープ
+//   - r"..." r#"..."# br#"..."# — 生文字列（エスケープなし、# の数で終わりを決める）
+//   - 'a' '\n' '\u{3042}'   — 文字リテラル
+//   ※ 'a（ライフタイム）は文字リテラルではないのでコードのまま
+
+use super::{LexError, Region, Span, find_from};
+
+pub fn lex(src: &str) -> Result<Vec<Span>, LexError> {
+    let bytes = src.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let (region, end) = match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => (
+                Region::Comment,
+                find_from(src, i, "\n").unwrap_or(src.len()),
+            ),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                (Region::Comment, block_comment_end(src, i)?)
+            }
+            b'"' => (Region::String, string_end(src, i + 1)?),
+            b'b' | b'c' | b'r' if !follows_identifier(bytes, i) => {
+                match prefixed_literal(src, i)? {
+                    Some(end) => (Region::String, end),
+                    None => {
+                        i += 1;
+                        continue;
+                    }
+                }
+            }
+            b'\'' => match char_literal_end(src, i) {
+                Some(end) => (Region::String, end),
+                None => {
+                    i += 1;
+                    continue;
+                }
+            },
+            _ => {
+                // マルチバイト文字の途中で止まらないよう、UTF-8 の1文字分進める
+                i += src[i..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+        };
+        spans.push(Span { region, start, end });
+        i = end;
+    }
+
+    Ok(spans)
+}
+
+// 直前が識別子の文字なら、r や b は識別子の一部（"for" や "sub" の末尾など）
+fn follows_identifier(bytes: &[u8], i: usize) -> bool {
+    i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_')
+}
+
+fn block_comment_end(src: &str, start: usize) -> Result<usize, LexError> {
+    let bytes = src.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    Err(LexError::at(src, start, "unterminated block comment"))
+}
+
+// from は開き " の次。閉じ " の直後の位置を返す
+fn string_end(src: &str, from: usize) -> Result<usize, LexError> {
+    let bytes = src.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Ok(i + 1),
+            _ => i += 1,
+        }
+    }
+    Err(LexError::at(src, from - 1, "unterminated string literal"))
+}
+
+// b"..." / c"..." / r#"..."# / br"..." / b'x'
+// 文字列リテラルでなければ None（ただの識別子 r, b, c の始まり）
+fn prefixed_literal(src: &str, start: usize) -> Result<Option<usize>, LexError> {
+    let bytes = src.as_bytes();
+    let mut i = start;
+    if matches!(bytes[i], b'b' | b'c') {
+        i += 1;
+        match bytes.get(i) {
+            Some(b'"') => return string_end(src, i + 1).map(Some),
+            Some(b'\'') if bytes[start] == b'b' => return Ok(char_literal_end(src, i)),
+            Some(b'r') => {}
+            _ => return Ok(None),
+        }
+    }
+
+    // ここで bytes[i] == b'r'
+    i += 1;
+    let hashes = bytes[i..].iter().take_while(|&&b| b == b'#').count();
+    i += hashes;
+    if bytes.get(i) != Some(&b'"') {
+        return Ok(None);
+    }
+
+    let closing = format!("\"{}", "#".repeat(hashes));
+    match find_from(src, i + 1, &closing) {
+        Some(end) => Ok(Some(end + closing.len())),
+        None => Err(LexError::at(src, start, "unterminated raw string literal")),
+    }
+}
+
+// 'a' / '\n' / '\'' / '\u{1F980}' なら閉じ ' の直後、ライフタイムなら None
+fn char_literal_end(src: &str, quote: usize) -> Option<usize> {
+    let rest = &src[quote + 1..];
+    let mut chars = rest.char_indices();
+    let (_, first) = chars.next()?;
+    let after = if first == '\\' {
+        // エスケープ: 閉じ ' まで（\u{...} を含む）
+        let (_, escaped) = chars.next()?;
+        if escaped == 'u' {
+            rest.find('}')? + 1
+        } else {
+            1 + escaped.len_utf8()
+        }
+    } else {
+        first.len_utf8()
+    };
+    (rest[after..].starts_with('\'')).then_some(quote + 1 + after + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(src: &str) -> Vec<(Region, &str)> {
+        lex(src)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.region, &src[s.start..s.end]))
+            .collect()
+    }
+
+    #[test]
+    fn finds_comments_and_strings() {
+        let src = "fn main() { /* a /* nested */ b */ let s = \"x\\\"y\"; } // end";
+        assert_eq!(
+            regions(src),
+            vec![
+                (Region::Comment, "/* a /* nested */ b */"),
+                (Region::String, "\"x\\\"y\""),
+                (Region::Comment, "// end"),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_byte_and_char_literals() {
+        let src = r####"let a = r#"say "hi""#; let b = b"\x00"; let c = 'あ'; let d = '\''; let e = br"raw";"####;
+        let found: Vec<&str> = regions(src).into_iter().map(|(_, s)| s).collect();
+        assert_eq!(
+            found,
+            vec![
+                r###"r#"say "hi""#"###,
+                r#"b"\x00""#,
+                "'あ'",
+                r"'\''",
+                r#"br"raw""#
+            ]
+        );
+    }
+
+    #[test]
+    fn lifetimes_and_identifiers_stay_code() {
+        let src = "fn longest<'a>(x: &'a str) -> &'a str { let bar = 1; for r in v {} x }";
+        assert!(regions(src).is_empty());
+    }
+
+    #[test]
+    fn unterminated_literals_are_errors() {
+        assert_eq!(lex("let s = \"open;\n").unwrap_err().line, 1);
+        assert_eq!(lex("ok\n/* never closed").unwrap_err().line, 2);
+        assert!(lex("r#\"raw").is_err());
+    }
+}
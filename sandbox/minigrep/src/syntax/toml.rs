@@ -0,0 +1,122 @@
+// ====================================================================
+// TOML の簡易レキサ
+// ====================================================================
+//
+//   # コメント（行末まで）
+//   "basic"      — \ でエスケープ、改行を含められない
+//   'literal'    — エスケープなし、改行を含められない
+//   """multi"""  — 複数行、\ でエスケープ
+//   '''multi'''  — 複数行、エスケープなし
+
+use super::{LexError, Region, Span, find_from};
+
+pub fn lex(src: &str) -> Result<Vec<Span>, LexError> {
+    let bytes = src.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let rest = &src[i..];
+        let (region, end) = if rest.starts_with('#') {
+            (
+                Region::Comment,
+                find_from(src, i, "\n").unwrap_or(src.len()),
+            )
+        } else if rest.starts_with("\"\"\"") {
+            (Region::String, basic_end(src, i + 3, "\"\"\"", true)?)
+        } else if rest.starts_with("'''") {
+            let end = find_from(src, i + 3, "'''")
+                .ok_or_else(|| LexError::at(src, i, "unterminated multi-line literal string"))?;
+            (Region::String, end + 3)
+        } else if rest.starts_with('"') {
+            (Region::String, basic_end(src, i + 1, "\"", false)?)
+        } else if rest.starts_with('\'') {
+            let end = find_from(src, i + 1, "'")
+                .filter(|&end| !src[i..end].contains('\n'))
+                .ok_or_else(|| LexError::at(src, i, "unterminated literal string"))?;
+            (Region::String, end + 1)
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        spans.push(Span { region, start, end });
+        i = end;
+    }
+
+    Ok(spans)
+}
+
+// エスケープを読み飛ばしながら closing を探し、その直後の位置を返す
+fn basic_end(src: &str, from: usize, closing: &str, multiline: bool) -> Result<usize, LexError> {
+    // 1バイトずつ進むので、文字の途中でも切れるバイト列で比べる
+    let bytes = src.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            // \ は ASCII なので直後は文字の境目。エスケープされた文字ごと飛ばす
+            i += 1 + src[i + 1..].chars().next().map_or(0, char::len_utf8);
+        } else if bytes[i..].starts_with(closing.as_bytes()) {
+            return Ok(i + closing.len());
+        } else if bytes[i] == b'\n' && !multiline {
+            break;
+        } else {
+            i += 1;
+        }
+    }
+    Err(LexError::at(
+        src,
+        from - closing.len(),
+        "unterminated string",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(src: &str) -> Vec<(Region, &str)> {
+        lex(src)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.region, &src[s.start..s.end]))
+            .collect()
+    }
+
+    #[test]
+    fn finds_comments_and_all_string_kinds() {
+        let src = "[package] # pkg\nname = \"a#b\"\npath = 'C:\\x'\ndesc = \"\"\"\nmulti \\\"\"\" line\n\"\"\"\nraw = '''\n#x'''\n";
+        assert_eq!(
+            regions(src),
+            vec![
+                (Region::Comment, "# pkg"),
+                (Region::String, "\"a#b\""),
+                (Region::String, "'C:\\x'"),
+                (Region::String, "\"\"\"\nmulti \\\"\"\" line\n\"\"\""),
+                (Region::String, "'''\n#x'''"),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_line_strings_cannot_span_lines() {
+        assert_eq!(lex("a = \"open\nb = 1").unwrap_err().line, 1);
+        assert_eq!(lex("a = 1\nb = 'open\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn japanese_strings_and_comments() {
+        let src = "a = \"あいう\" # 日本語\nb = \"改\\\"行\\あ\"\nc = '''\nいろは'''\nd = \"\"\"にほへ\"\"\"\n";
+        assert_eq!(
+            regions(src),
+            vec![
+                (Region::String, "\"あいう\""),
+                (Region::Comment, "# 日本語"),
+                (Region::String, "\"改\\\"行\\あ\""),
+                (Region::String, "'''\nいろは'''"),
+                (Region::String, "\"\"\"にほへ\"\"\""),
+            ]
+        );
+        assert_eq!(lex("a = \"あいう\n").unwrap_err().line, 1);
+    }
+}
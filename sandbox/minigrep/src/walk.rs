@@ -0,0 +1,148 @@
+// ====================================================================
+// ディレクトリの走査
+// ====================================================================
+//
+// file_path にディレクトリを渡したときに、検索対象のファイル一覧を集める
+//   - 再帰的にたどり、パス順（ソート済み）で返す → 出力順が毎回同じになる
+//   - "." で始まる隠しファイル・ディレクトリ（.git など）は飛ばす
+//   - シンボリックリンクのディレクトリはたどらない（循環を防ぐ）
+//   - 通常ファイルだけを集める（FIFO などは開くと読み込みで止まってしまう）
+//
+// WalkOptions で制限をかけられる:
+//   max_depth    — root 直下のファイルが深さ 1
+//   max_filesize — これより大きいファイルは開く前に除外する
+//   cancel       — 中断されたら、そこまでに見つけたファイルだけを返す
+//   filter       — ファイルの種類・グロブ（-t / -T / --glob）
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cancel::CancelToken;
+use crate::filter::FileFilter;
+
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    pub max_depth: Option<usize>,
+    pub max_filesize: Option<u64>,
+    pub cancel: Option<CancelToken>,
+    pub filter: FileFilter,
+}
+
+pub fn files(root: &Path, options: &WalkOptions) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    visit(root, root, 1, options, &mut found)?;
+    Ok(found)
+}
+
+// グロブは "/" 区切りの相対パスに当てはめる（OS に関係なく同じ書き方にするため）
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn visit(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    options: &WalkOptions,
+    found: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    if options.max_depth.is_some_and(|max| depth > max) {
+        return Ok(());
+    }
+    if options
+        .cancel
+        .as_ref()
+        .is_some_and(|token| token.interrupted().is_some())
+    {
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        // file_type() はリンク先ではなくリンク自体の種類を返す
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            if options.filter.allows_dir(&relative_path(root, &path)) {
+                visit(root, &path, depth + 1, options, found)?;
+            }
+        } else if file_type.is_file() {
+            if let Some(max) = options.max_filesize
+                && entry.metadata()?.len() > max
+            {
+                continue;
+            }
+            if !options
+                .filter
+                .allows_file(&relative_path(root, &path), &path)
+            {
+                continue;
+            }
+            found.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+// ファイルを文字列として読む
+// UTF-8 でないファイル（バイナリなど）は None にして検索対象から外す
+pub fn read_text(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_files_in_sorted_order_skipping_hidden() {
+        let root = std::env::temp_dir().join(format!("minigrep-walk-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("b/nested")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("b/nested/z.txt"), "z").unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join(".git/config"), "hidden").unwrap();
+
+        let found = files(&root, &WalkOptions::default()).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, vec![root.join("a.txt"), root.join("b/nested/z.txt")]);
+    }
+
+    #[test]
+    fn respects_depth_and_size_limits() {
+        let root = std::env::temp_dir().join(format!("minigrep-limits-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("deep")).unwrap();
+        fs::write(root.join("small.txt"), "ok").unwrap();
+        fs::write(root.join("large.txt"), "x".repeat(100)).unwrap();
+        fs::write(root.join("deep/inner.txt"), "ok").unwrap();
+
+        let options = WalkOptions {
+            max_depth: Some(1),
+            max_filesize: Some(10),
+            ..WalkOptions::default()
+        };
+        let found = files(&root, &options).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, vec![root.join("small.txt")]);
+    }
+}
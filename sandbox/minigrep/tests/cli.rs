@@ -0,0 +1,138 @@
+// ====================================================================
+// 結合テスト: minigrep バイナリを実際に実行する
+// ====================================================================
+//
+// lib.rs のユニットテストは search などの関数を直接呼ぶ
+// ここではユーザーと同じく、引数・環境変数・ファイルを与えてバイナリを動かし、
+// stdout / stderr / 終了コードを確かめる（12.6 の「結果は stdout、エラーは stderr」も含めて）
+//
+// 部品は test-support クレート:
+//   TempDir::with_tree  検索対象のファイルを一時ディレクトリに並べる
+//   Cmd                 env!("CARGO_BIN_EXE_minigrep") を実行して出力を取る
+//   EnvGuard            IGNORE_CASE を書き換えて Config::build を呼ぶテスト用
+//   assert_cmd_snapshot! 終了コード・stdout・stderr を tests/snapshots/ と比べる
+
+use minigrep::Config;
+use test_support::{Cmd, EnvGuard, TempDir, assert_cmd_snapshot};
+
+const POEM: &str = "
+-- poem.txt --
+I'm nobody! Who are you?
+Are you nobody, too?
+Then there's a pair of us - don't tell!
+They'd banish us, you know.
+
+How dreary to be somebody!
+How public, like a frog
+To tell your name the livelong day
+To an admiring bog!
+";
+
+fn minigrep(dir: &TempDir) -> Cmd {
+    let mut cmd = Cmd::new(env!("CARGO_BIN_EXE_minigrep"));
+    // テストを実行している端末の設定に左右されないように
+    cmd.current_dir(dir.path()).env_remove("IGNORE_CASE");
+    cmd
+}
+
+#[test]
+fn prints_matching_lines_to_stdout() {
+    let dir = TempDir::with_tree(POEM);
+    let out = minigrep(&dir).args(["to", "poem.txt"]).run();
+    out.assert_success();
+    assert_eq!(
+        out.stdout_lines(),
+        ["Are you nobody, too?", "How dreary to be somebody!"]
+    );
+    assert_eq!(out.stderr, "");
+}
+
+#[test]
+fn ignore_case_comes_from_the_environment() {
+    let dir = TempDir::with_tree(POEM);
+    let out = minigrep(&dir)
+        .args(["to", "poem.txt"])
+        .env("IGNORE_CASE", "1")
+        .run();
+    out.assert_success();
+    assert_eq!(
+        out.stdout_lines(),
+        [
+            "Are you nobody, too?",
+            "How dreary to be somebody!",
+            "To tell your name the livelong day",
+            "To an admiring bog!",
+        ]
+    );
+}
+
+#[test]
+fn directories_print_path_and_line() {
+    let dir = TempDir::with_tree(
+        "
+        -- notes/a.txt --
+        rust is fast
+        -- notes/b.txt --
+        go is simple
+        rust is safe
+        -- notes/empty/ --
+        ",
+    );
+    let out = minigrep(&dir).args(["rust", "notes"]).run();
+    out.assert_success();
+    let mut lines = out.stdout_lines();
+    lines.sort();
+    assert_eq!(
+        lines,
+        ["notes/a.txt:rust is fast", "notes/b.txt:rust is safe"]
+    );
+}
+
+#[test]
+fn errors_go_to_stderr_with_exit_code_1() {
+    let dir = TempDir::new().unwrap();
+
+    let out = minigrep(&dir).arg("only-a-query").run();
+    assert_eq!(out.code(), Some(1));
+    assert_eq!(out.stdout, "");
+    assert!(
+        out.stderr.starts_with("Problem parsing arguments:"),
+        "{out}"
+    );
+
+    let out = minigrep(&dir).args(["to", "missing.txt"]).run();
+    assert_eq!(out.code(), Some(1));
+    assert!(out.stderr.starts_with("Application error:"), "{out}");
+}
+
+#[test]
+fn output_snapshots() {
+    let dir = TempDir::with_tree(POEM);
+    assert_cmd_snapshot!("search", minigrep(&dir).args(["-m", "2", "ou", "poem.txt"]));
+    assert_cmd_snapshot!("type_list", minigrep(&dir).arg("--type-list"));
+    assert_cmd_snapshot!(
+        "bad_option",
+        minigrep(&dir).args(["--top", "0", "x", "poem.txt"])
+    );
+}
+
+// ----------------------------------------------------------------
+// Config::build は IGNORE_CASE を読む → 書き換えるテストどうしは EnvGuard で順番に
+// ----------------------------------------------------------------
+
+fn build(query: &str) -> Config {
+    let args = ["minigrep", query, "poem.txt"].map(String::from);
+    Config::build(args.into_iter()).unwrap()
+}
+
+#[test]
+fn config_reads_ignore_case_when_set() {
+    let _env = EnvGuard::lock().set("IGNORE_CASE", "1");
+    assert!(build("to").ignore_case);
+}
+
+#[test]
+fn config_is_case_sensitive_when_unset() {
+    let _env = EnvGuard::lock().remove("IGNORE_CASE");
+    assert!(!build("to").ignore_case);
+}
@@ -0,0 +1,85 @@
+// ====================================================================
+// プロパティテスト: search の性質をランダムな入力で確かめる
+// ====================================================================
+//
+// lib.rs のユニットテストは「この詩で "duct" を探すと…」のような例で確かめている
+// ここでは「どんな内容・query でも成り立つこと」を test-support の prop で試す
+//
+// 文字の種類を少なくしておくと query が本文に現れやすい（見つかる行がある入力が増える）
+//   FEW: a b c あ い う と \r（\r\n の行末も混ぜる）
+//
+// 失敗すると、縮めた入力と seed が表示される → PROP_SEED=... cargo test で再現
+
+use minigrep::{fast, search, search_case_insensitive};
+use test_support::prop::{self, Chars, Strategy, ascii, chars, japanese, strings, vecs};
+
+fn few() -> Chars {
+    chars(&['a'..='c', 'あ'..='う', 'A'..='C', '\r'..='\r'])
+}
+
+// (query, 行の Vec)。本文は行を \n でつないだもの
+fn query_and_lines(chars: Chars) -> impl Strategy<Value = (String, Vec<String>)> {
+    (
+        strings(chars.clone(), 1..=3),
+        vecs(strings(chars, 0..=12), 0..=12),
+    )
+}
+
+#[test]
+fn every_line_returned_by_search_contains_the_query() {
+    for chars in [few(), ascii().or(japanese())] {
+        prop::check(
+            "every line returned by search contains the query",
+            query_and_lines(chars),
+            |(query, lines)| {
+                let contents = lines.join("\n");
+                search(&query, &contents)
+                    .iter()
+                    .all(|line| line.contains(&query))
+            },
+        );
+    }
+}
+
+#[test]
+fn search_keeps_every_matching_line_in_order() {
+    prop::check(
+        "search is lines() filtered by contains",
+        query_and_lines(few()),
+        |(query, lines)| {
+            let contents = lines.join("\n");
+            let expected: Vec<&str> = contents.lines().filter(|l| l.contains(&query)).collect();
+            assert_eq!(search(&query, &contents), expected);
+        },
+    );
+}
+
+#[test]
+fn fast_search_agrees_with_search() {
+    prop::check(
+        "fast::search == search",
+        query_and_lines(few()),
+        |(query, lines)| {
+            let contents = lines.join("\n");
+            assert_eq!(fast::search(&query, &contents), search(&query, &contents));
+        },
+    );
+}
+
+#[test]
+fn case_insensitive_search_finds_at_least_the_exact_matches() {
+    prop::check(
+        "search ⊆ search_case_insensitive",
+        query_and_lines(few()),
+        |(query, lines)| {
+            let contents = lines.join("\n");
+            let loose = search_case_insensitive(&query, &contents);
+            let exact = search(&query, &contents);
+            // どちらも行の順に並ぶので、exact は loose の部分列になる
+            let mut rest = loose.iter();
+            exact
+                .iter()
+                .all(|line| rest.any(|l| std::ptr::eq(*l, *line)))
+        },
+    );
+}
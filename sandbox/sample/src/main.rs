@@ -32,11 +32,13 @@ fn main() {
     let s2 = s1; // s1 → s2 へムーブ (s1はここで無効になる)
     println!("s2: {}", s2);
     // println!("{}", s1); // コンパイルエラー: s1はムーブ済み
+    //   → tests/compile-fail/moved_by_assignment.rs で確かめている
 
     // 関数にムーブ
     let s3 = String::from("world");
     take_ownership(s3); // s3の所有権が関数に移動
     // println!("{}", s3); // コンパイルエラー: s3はムーブ済み
+    //   → tests/compile-fail/moved_into_function.rs
 
     // ======== 不変借用の確認 ========
     let s4 = String::from("不変借用テスト");
@@ -62,6 +64,7 @@ fn main() {
     // resultをここで使うとコンパイルエラーになる
     // println!("{}", result);
     // (string2のライフタイムが終わっているため)
+    //   → tests/compile-fail/result_outlives_string2.rs
 
     // ======== Clone vs Move ========
     let original = String::from("クローン元");
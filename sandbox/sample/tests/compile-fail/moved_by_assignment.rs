@@ -0,0 +1,9 @@
+// expect: E0382
+// sample/src/main.rs「ムーブの確認」: s1 → s2 へムーブしたあとは s1 を使えない
+
+pub fn moved_by_assignment() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+    println!("s2: {}", s2);
+    println!("{}", s1); // s1はムーブ済み
+}
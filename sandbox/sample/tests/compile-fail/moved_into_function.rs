@@ -0,0 +1,12 @@
+// expect: E0382
+// sample/src/main.rs「関数にムーブ」: take_ownership に渡した s3 は使えない
+
+fn take_ownership(s: String) {
+    println!("所有権を受け取った: {}", s);
+}
+
+pub fn moved_into_function() {
+    let s3 = String::from("world");
+    take_ownership(s3);
+    println!("{}", s3); // s3はムーブ済み
+}
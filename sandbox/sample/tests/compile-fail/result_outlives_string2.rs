@@ -0,0 +1,16 @@
+// expect: E0597
+// sample/src/main.rs「ライフタイムの確認」: string2 のスコープの外で result を使う
+
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+pub fn result_outlives_string2() {
+    let result;
+    let string1 = String::from("long string");
+    {
+        let string2 = String::from("xyz");
+        result = longest(&string1, &string2);
+    }
+    println!("{}", result); // string2のライフタイムが終わっている
+}
@@ -0,0 +1,3 @@
+// smart_pointer — main.rs の Cons リストを、ライブラリとして使える永続リストにしたもの
+
+pub mod list;
@@ -0,0 +1,459 @@
+// ====================================================================
+// 永続リスト（persistent list）— main.rs の Cons リストを使える形に
+// ====================================================================
+//
+// main.rs の List は Cons(値, Rc<List>) で「後ろを共有する」ところまで。
+// ここでは同じ形のまま、一度作ったら変わらない（immutable）リストとして
+// 先頭への追加・取り出し・走査・変換をひととおりそろえる
+//
+//   let a: List<i32> = [3, 4, 5].into_iter().collect();   // [3, 4, 5]
+//   let b = a.cons(2);                                    // [2, 3, 4, 5]（a のノードをそのまま使う）
+//   let c = a.cons(10);                                   // [10, 3, 4, 5]（b と c は a を共有）
+//
+// 「変える」操作はすべて新しいリストを返し、元のリストはそのまま残る。
+// 後ろの部分は Rc で共有するので、cons / tail は O(1)、コピーもしない
+//
+//   cons / head / tail / len           O(1)
+//   append(other)                      self の分だけ作り直し、other は丸ごと共有
+//   filter                             最後に落とした要素より後ろは共有
+//   reverse / map                      全部作り直す
+//
+// Drop は自前で書く。ふつうに drop すると Node → next の Rc → Node ... と
+// 再帰で片付けるので、100 万要素くらいでスタックがあふれる
+//
+// 中身は Rc 版と Arc 版で同じなので、マクロで2つ作る
+//   list::List（= list::rc::List）  Rc  … 1つのスレッドの中で
+//   list::sync::List                Arc … スレッドをまたいで共有できる
+
+use std::fmt;
+use std::iter::FusedIterator;
+
+macro_rules! persistent_list {
+    ($Ptr:ident) => {
+        pub struct List<T> {
+            head: Option<$Ptr<Node<T>>>,
+            len: usize,
+        }
+
+        struct Node<T> {
+            value: T,
+            next: Option<$Ptr<Node<T>>>,
+        }
+
+        impl<T> List<T> {
+            // 空のリスト（main.rs の Nil）
+            pub fn new() -> List<T> {
+                List { head: None, len: 0 }
+            }
+
+            // 先頭に value をつけた新しいリスト。self のノードはそのまま共有する
+            pub fn cons(&self, value: T) -> List<T> {
+                List {
+                    head: Some($Ptr::new(Node {
+                        value,
+                        next: self.head.clone(),
+                    })),
+                    len: self.len + 1,
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.value)
+            }
+
+            // 先頭を除いたリスト（共有するだけでコピーしない）。空なら None
+            pub fn tail(&self) -> Option<List<T>> {
+                self.head.as_ref().map(|node| List {
+                    head: node.next.clone(),
+                    len: self.len - 1,
+                })
+            }
+
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    next: self.head.as_deref(),
+                    remaining: self.len,
+                }
+            }
+
+            // 先頭のノードが同じ（= 中身をまるごと共有している）か
+            pub fn ptr_eq(&self, other: &List<T>) -> bool {
+                match (&self.head, &other.head) {
+                    (Some(a), Some(b)) => $Ptr::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+
+            // 後ろから順に cons していく（values は前から並んだ順）
+            fn with_tail(values: impl DoubleEndedIterator<Item = T>, tail: List<T>) -> List<T> {
+                values.rev().fold(tail, |list, value| list.cons(value))
+            }
+        }
+
+        impl<T: Clone> List<T> {
+            pub fn reverse(&self) -> List<T> {
+                self.iter()
+                    .fold(List::new(), |list, value| list.cons(value.clone()))
+            }
+
+            // self の後ろに other をつなぐ。self のノードは作り直すが、other は共有する
+            pub fn append(&self, other: &List<T>) -> List<T> {
+                let values: Vec<T> = self.iter().cloned().collect();
+                List::with_tail(values.into_iter(), other.clone())
+            }
+
+            // f が true の要素だけ残す。最後に落とした要素より後ろはそのまま共有する
+            pub fn filter(&self, mut f: impl FnMut(&T) -> bool) -> List<T> {
+                // kept: 最後に落とした要素までに残した値 / suffix: それより後ろ
+                let mut kept: Vec<T> = Vec::new();
+                let mut suffix = self.clone();
+                let mut pending = Vec::new();
+                let mut node = self.head.as_deref();
+                while let Some(n) = node {
+                    if f(&n.value) {
+                        pending.push(&n.value);
+                    } else {
+                        kept.extend(pending.drain(..).cloned());
+                        suffix = List {
+                            head: n.next.clone(),
+                            len: 0,
+                        };
+                    }
+                    node = n.next.as_deref();
+                }
+                suffix.len = pending.len();
+                List::with_tail(kept.into_iter(), suffix)
+            }
+        }
+
+        impl<T> List<T> {
+            pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> List<U> {
+                let values: Vec<U> = self.iter().map(f).collect();
+                List::with_tail(values.into_iter(), List::new())
+            }
+        }
+
+        // 共有している後ろの部分は、最後の持ち主が片付ける。
+        // 自分しか持っていないノードだけを、ループで1つずつはずして捨てる
+        impl<T> Drop for List<T> {
+            fn drop(&mut self) {
+                let mut next = self.head.take();
+                // try_unwrap だと、Arc 版で2つのスレッドが同時に最後の2つを手放したとき
+                // 両方失敗して、最後の1つが後ろを再帰で片付けてしまう。into_inner は
+                // どちらか一方が必ず中身を受け取れる
+                while let Some(node) = next {
+                    match $Ptr::into_inner(node) {
+                        Some(mut node) => next = node.next.take(),
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        // clone はポインタをコピーするだけ（中身は共有）
+        impl<T> Clone for List<T> {
+            fn clone(&self) -> List<T> {
+                List {
+                    head: self.head.clone(),
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<T> Default for List<T> {
+            fn default() -> List<T> {
+                List::new()
+            }
+        }
+
+        impl<T: fmt::Debug> fmt::Debug for List<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self).finish()
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for List<T> {
+            fn eq(&self, other: &List<T>) -> bool {
+                self.len == other.len && self.iter().eq(other)
+            }
+        }
+
+        impl<T: Eq> Eq for List<T> {}
+
+        impl<T> FromIterator<T> for List<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> List<T> {
+                let values: Vec<T> = iter.into_iter().collect();
+                List::with_tail(values.into_iter(), List::new())
+            }
+        }
+
+        // ---- 走査 ----
+
+        pub struct Iter<'a, T> {
+            next: Option<&'a Node<T>>,
+            remaining: usize,
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<&'a T> {
+                let node = self.next?;
+                self.next = node.next.as_deref();
+                self.remaining -= 1;
+                Some(&node.value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<T> ExactSizeIterator for Iter<'_, T> {}
+        impl<T> FusedIterator for Iter<'_, T> {}
+
+        impl<'a, T> IntoIterator for &'a List<T> {
+            type Item = &'a T;
+            type IntoIter = Iter<'a, T>;
+
+            fn into_iter(self) -> Iter<'a, T> {
+                self.iter()
+            }
+        }
+
+        // 値で取り出す。自分しか持っていないノードからは move し、
+        // ほかのリストと共有しているノードからは clone する
+        pub struct IntoIter<T> {
+            list: List<T>,
+        }
+
+        impl<T: Clone> Iterator for IntoIter<T> {
+            type Item = T;
+
+            fn next(&mut self) -> Option<T> {
+                let node = self.list.head.take()?;
+                self.list.len -= 1;
+                // 共有していそうなら、手放す前に値と次を写しておく。
+                // 写したあとで自分が最後の持ち主になっても、into_inner で受け取るので
+                // 片付けるのはこのノード1つだけ（次は self.list.head が持っている）
+                if $Ptr::strong_count(&node) > 1 {
+                    let value = node.value.clone();
+                    self.list.head = node.next.clone();
+                    drop($Ptr::into_inner(node));
+                    return Some(value);
+                }
+                // 持っているのは自分だけなので、ほかから増えることはなく必ず受け取れる
+                let node = $Ptr::into_inner(node)?;
+                self.list.head = node.next;
+                Some(node.value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.list.len, Some(self.list.len))
+            }
+        }
+
+        impl<T: Clone> ExactSizeIterator for IntoIter<T> {}
+        impl<T: Clone> FusedIterator for IntoIter<T> {}
+
+        impl<T: Clone> IntoIterator for List<T> {
+            type Item = T;
+            type IntoIter = IntoIter<T>;
+
+            fn into_iter(self) -> IntoIter<T> {
+                IntoIter { list: self }
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            fn list(values: &[i32]) -> List<i32> {
+                values.iter().copied().collect()
+            }
+
+            fn to_vec(list: &List<i32>) -> Vec<i32> {
+                list.iter().copied().collect()
+            }
+
+            // 2つのリストの後ろ n 個が、同じノードを共有しているか
+            fn shares_last(a: &List<i32>, b: &List<i32>, n: usize) -> bool {
+                let drop_front = |list: &List<i32>| {
+                    let mut list = list.clone();
+                    while list.len() > n {
+                        list = list.tail().unwrap();
+                    }
+                    list
+                };
+                drop_front(a).ptr_eq(&drop_front(b))
+            }
+
+            #[test]
+            fn cons_head_tail() {
+                let empty = List::new();
+                let a = empty.cons(5).cons(4).cons(3);
+                assert_eq!(to_vec(&a), [3, 4, 5]);
+                assert_eq!(a.head(), Some(&3));
+                assert_eq!(a.len(), 3);
+                assert_eq!(a.tail().unwrap(), list(&[4, 5]));
+                assert_eq!(a.tail().unwrap().len(), 2);
+                assert!(empty.is_empty());
+                assert_eq!(empty.head(), None);
+                assert!(empty.tail().is_none());
+                assert_eq!(format!("{a:?}"), "[3, 4, 5]");
+            }
+
+            #[test]
+            fn versions_share_the_tail() {
+                // main.rs と同じ形: b と c が a を共有する
+                let a = list(&[5]);
+                let b = a.cons(3);
+                let c = a.cons(4);
+                assert!(b.tail().unwrap().ptr_eq(&a));
+                assert!(c.tail().unwrap().ptr_eq(&a));
+                assert_eq!(
+                    (to_vec(&a), to_vec(&b), to_vec(&c)),
+                    (vec![5], vec![3, 5], vec![4, 5])
+                );
+            }
+
+            #[test]
+            fn append_shares_the_second_list() {
+                let a = list(&[1, 2]);
+                let b = list(&[3, 4, 5]);
+                let ab = a.append(&b);
+                assert_eq!(to_vec(&ab), [1, 2, 3, 4, 5]);
+                assert_eq!(ab.len(), 5);
+                assert!(ab.tail().unwrap().tail().unwrap().ptr_eq(&b));
+                assert_eq!(to_vec(&a), [1, 2]);
+                assert!(List::new().append(&b).ptr_eq(&b));
+            }
+
+            #[test]
+            fn filter_shares_the_untouched_suffix() {
+                let a = list(&[1, 2, 3, 4, 6, 8]);
+                let even = a.filter(|n| n % 2 == 0);
+                assert_eq!(to_vec(&even), [2, 4, 6, 8]);
+                assert_eq!(even.len(), 4);
+                // 最後に落とした 3 より後ろ（4, 6, 8）は a のノードのまま
+                assert!(shares_last(&even, &a, 3));
+                assert!(a.filter(|_| true).ptr_eq(&a));
+                assert!(a.filter(|_| false).is_empty());
+            }
+
+            #[test]
+            fn reverse_and_map_make_new_lists() {
+                let a = list(&[1, 2, 3]);
+                assert_eq!(to_vec(&a.reverse()), [3, 2, 1]);
+                let words = a.map(|n| n.to_string());
+                assert_eq!(words.iter().map(String::as_str).collect::<String>(), "123");
+                assert_eq!(words.len(), 3);
+                assert_eq!(to_vec(&a), [1, 2, 3]);
+            }
+
+            #[test]
+            fn into_iter_moves_or_clones() {
+                let shared = list(&[3, 4]);
+                let a = shared.cons(2).cons(1);
+                let values = a.into_iter();
+                assert_eq!(values.len(), 4);
+                assert_eq!(values.collect::<Vec<_>>(), [1, 2, 3, 4]);
+                assert_eq!(to_vec(&shared), [3, 4]);
+
+                let mut total = 0;
+                for n in &shared {
+                    total += n;
+                }
+                assert_eq!(total, 7);
+            }
+
+            #[test]
+            fn million_elements_drop_without_overflow() {
+                let long: List<u32> = (0..1_000_000).collect();
+                let shorter = long.tail().unwrap();
+                assert_eq!(shorter.len(), 999_999);
+                drop(long);
+                assert_eq!(shorter.head(), Some(&1));
+                assert_eq!(shorter.iter().count(), 999_999);
+                drop(shorter);
+
+                let long: List<u32> = (0..1_000_000).collect();
+                assert_eq!(long.filter(|n| n % 2 == 0).len(), 500_000);
+                assert_eq!(long.reverse().head(), Some(&999_999));
+                assert_eq!(long.clone(), long);
+            }
+        }
+    };
+}
+
+pub mod rc {
+    use super::*;
+    use std::rc::Rc;
+
+    persistent_list!(Rc);
+}
+
+pub mod sync {
+    use super::*;
+    use std::sync::Arc;
+
+    persistent_list!(Arc);
+}
+
+pub use rc::List;
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::sync;
+
+    #[test]
+    fn sync_list_is_shared_across_threads() {
+        let shared: sync::List<u64> = (1..=100).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let shared = shared.clone();
+                // 各スレッドが同じ後ろの部分に、自分の先頭をつける
+                thread::spawn(move || {
+                    let mine = shared.cons(i * 1000);
+                    assert!(mine.tail().unwrap().ptr_eq(&shared));
+                    mine.iter().sum::<u64>()
+                })
+            })
+            .collect();
+        let sums: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(sums, [5050, 6050, 7050, 8050]);
+        assert_eq!(shared.len(), 100);
+    }
+
+    #[test]
+    fn sync_list_dropped_from_two_threads_at_once() {
+        // 最後の2つの持ち主が同時に手放しても、後ろを再帰で片付けない
+        for _ in 0..20 {
+            let a: sync::List<u32> = (0..200_000).collect();
+            let b = a.clone();
+            let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+            let handle = {
+                let barrier = std::sync::Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    drop(b);
+                })
+            };
+            barrier.wait();
+            drop(a);
+            handle.join().unwrap();
+        }
+    }
+}
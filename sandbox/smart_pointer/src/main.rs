@@ -27,4 +27,20 @@ fn main() {
     println!("a after = {:?}", a);
     println!("b after = {:?}", b);
     println!("c after = {:?}", c);
+
+    // ================================================================
+    // ライブラリ版（smart_pointer::list）— 同じ共有を immutable なリストで
+    // ================================================================
+    // 値を書き換える代わりに、cons で「新しい版」を作る。a はそのまま残る
+    let a: smart_pointer::list::List<i32> = [5].into_iter().collect();
+    let b = a.cons(3);
+    let c = a.cons(4);
+    println!("list a = {:?}", a);
+    println!("list b = {:?}", b);
+    println!("list c = {:?}", c);
+    println!("b.append(&c) = {:?}", b.append(&c));
+    println!(
+        "b の後ろは a と同じノード: {}",
+        b.tail().unwrap().ptr_eq(&a)
+    );
 }
@@ -0,0 +1,230 @@
+// ====================================================================
+// Cmd — テスト対象のバイナリを実行して出力を取る
+// ====================================================================
+//
+// 結合テストからは Cargo がビルド済みのバイナリのパスを教えてくれる:
+//   env!("CARGO_BIN_EXE_<バイナリ名>")
+//
+//   let out = Cmd::new(env!("CARGO_BIN_EXE_minigrep"))
+//       .args(["to", "poem.txt"])
+//       .current_dir(dir.path())
+//       .run();
+//   assert_eq!(out.code(), Some(0));
+//   assert_eq!(out.stdout_lines(), ["Are you nobody, too?"]);
+//
+// stdin に渡すデータは別スレッドで書く
+//   → 子プロセスが stdout を書き切れず止まり、こちらも stdin の書き込みで止まる…を避ける
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+pub struct Cmd {
+    command: Command,
+    stdin: Option<Vec<u8>>,
+}
+
+impl Cmd {
+    pub fn new(program: impl AsRef<OsStr>) -> Cmd {
+        Cmd {
+            command: Command::new(program),
+            stdin: None,
+        }
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Cmd {
+        self.command.arg(arg);
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Cmd
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command.args(args);
+        self
+    }
+
+    // 子プロセスにだけ設定する（テストを実行しているプロセスの環境は変えない）
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Cmd {
+        self.command.env(key, value);
+        self
+    }
+
+    pub fn env_remove(&mut self, key: impl AsRef<OsStr>) -> &mut Cmd {
+        self.command.env_remove(key);
+        self
+    }
+
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Cmd {
+        self.command.current_dir(dir);
+        self
+    }
+
+    pub fn stdin(&mut self, input: impl Into<Vec<u8>>) -> &mut Cmd {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    // 終わるまで待つ（起動できなければ panic）
+    pub fn run(&mut self) -> Captured {
+        self.command
+            .stdin(if self.stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let program = self.command.get_program().to_string_lossy().into_owned();
+        let mut child = self
+            .command
+            .spawn()
+            .unwrap_or_else(|e| panic!("failed to run `{program}`: {e}"));
+
+        let writer = self
+            .stdin
+            .clone()
+            .zip(child.stdin.take())
+            .map(|(input, mut pipe)| {
+                // 子プロセスが stdin を読まずに終わったときの書き込みエラーは無視する
+                thread::spawn(move || {
+                    let _ = pipe.write_all(&input);
+                })
+            });
+        let output = child
+            .wait_with_output()
+            .unwrap_or_else(|e| panic!("failed to wait for `{program}`: {e}"));
+        if let Some(writer) = writer {
+            writer.join().expect("stdin writer panicked");
+        }
+
+        Captured {
+            program,
+            status: output.status,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+}
+
+// ====================================================================
+// Captured — 実行結果
+// ====================================================================
+
+pub struct Captured {
+    pub program: String,
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Captured {
+    // シグナルで終わったときは None
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    pub fn stdout_lines(&self) -> Vec<&str> {
+        self.stdout.lines().collect()
+    }
+
+    pub fn stderr_lines(&self) -> Vec<&str> {
+        self.stderr.lines().collect()
+    }
+
+    // assert_cmd_snapshot! が保存する形（プログラムのパスは環境で変わるので含めない）
+    //   exit: 0
+    //   --- stdout ---
+    //   ...
+    //   --- stderr ---
+    //   ...
+    pub fn snapshot(&self) -> String {
+        let code = self.code().map_or_else(
+            || "none (killed by a signal)".to_string(),
+            |c| c.to_string(),
+        );
+        let mut out = format!("exit: {code}\n--- stdout ---\n{}", self.stdout);
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("--- stderr ---\n");
+        out.push_str(&self.stderr);
+        out
+    }
+
+    // 失敗していたら stdout と stderr を見せて panic
+    #[track_caller]
+    pub fn assert_success(&self) -> &Captured {
+        assert!(self.success(), "command failed\n{self}");
+        self
+    }
+
+    #[track_caller]
+    pub fn assert_failure(&self) -> &Captured {
+        assert!(!self.success(), "command unexpectedly succeeded\n{self}");
+        self
+    }
+}
+
+impl fmt::Display for Captured {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "program: {}", self.program)?;
+        writeln!(f, "status:  {}", self.status)?;
+        writeln!(f, "--- stdout ---\n{}", self.stdout)?;
+        write!(f, "--- stderr ---\n{}", self.stderr)
+    }
+}
+
+impl fmt::Debug for Captured {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_stdout_stderr_and_status() {
+        let out = Cmd::new("sh")
+            .args(["-c", "echo out; echo err >&2; exit 3"])
+            .run();
+        assert_eq!(out.stdout_lines(), ["out"]);
+        assert_eq!(out.stderr_lines(), ["err"]);
+        assert_eq!(out.code(), Some(3));
+        out.assert_failure();
+        assert_eq!(
+            out.snapshot(),
+            "exit: 3\n--- stdout ---\nout\n--- stderr ---\nerr\n"
+        );
+    }
+
+    #[test]
+    fn passes_stdin_env_and_directory() {
+        let dir = crate::TempDir::new().unwrap();
+        // パイプの容量より大きい入力でも止まらない
+        let input = "あ\n".repeat(100_000);
+        let out = Cmd::new("sh")
+            .args(["-c", "wc -l; echo \"$GREETING\"; pwd"])
+            .env("GREETING", "こんにちは")
+            .current_dir(dir.path())
+            .stdin(input)
+            .run();
+        out.assert_success();
+        let lines = out.stdout_lines();
+        assert_eq!(lines[0].trim(), "100000");
+        assert_eq!(lines[1], "こんにちは");
+        assert_eq!(Path::new(lines[2]), dir.path());
+    }
+}
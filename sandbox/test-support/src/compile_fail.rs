@@ -0,0 +1,251 @@
+// ====================================================================
+// compile_fail — 「コンパイルエラーになる例」が本当にエラーになるか確かめる
+// ====================================================================
+//
+// 教材のコメントアウトされた例（// let u2 = u1; // エラー！）は、ツールチェーンが変わって
+// エラーにならなくなっても誰も気づかない
+// → 例を1つずつファイルにし、期待するエラーコードを書いておいて、実際に rustc に渡す
+//
+//   tests/compile-fail/moved_user.rs:
+//     // expect: E0382
+//     // generics-practice/src/step9.rs の「u1 はもう使えない」
+//     #[derive(Debug)]
+//     struct User { name: String }
+//     pub fn f() { let u1 = User { .. }; let u2 = u1; println!("{:?}", u1); }
+//
+//   tests/compile_fail.rs:
+//     #[test]
+//     fn commented_out_examples_fail_to_compile() {
+//         test_support::compile_fail::check_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compile-fail"));
+//     }
+//
+// tests/ の直下でなくサブディレクトリに置くので、cargo はこれらをテストとしてコンパイルしない
+//
+// 判定:
+//   各ファイルをライブラリとして rustc でコンパイルする（--edition 2024、警告は無視）
+//   出たエラーコードの集まりが「// expect:」に書いたものとちょうど同じなら OK
+//   コンパイルできてしまった・違うエラーが出た・コードのないエラーが出た → 失敗
+//   （違うエラーも失敗にするのは、例の書き間違いで別のエラーになっているのに気づくため）
+//
+// rustc は環境変数 RUSTC があればそれ、なければ PATH の rustc
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{Cmd, TempDir};
+
+pub const EDITION: &str = "2024";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub path: PathBuf,
+    pub expected: BTreeSet<String>,
+    pub found: BTreeSet<String>,
+    // コードのないエラー（構文エラーなど）
+    pub uncoded: Vec<String>,
+    pub compiled: bool,
+    pub stderr: String,
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        !self.compiled
+            && !self.expected.is_empty()
+            && self.uncoded.is_empty()
+            && self.found == self.expected
+    }
+
+    fn describe(&self) -> String {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let list = |codes: &BTreeSet<String>| codes.iter().cloned().collect::<Vec<_>>().join(", ");
+        if self.expected.is_empty() {
+            format!("{name}: missing a `// expect: E....` line")
+        } else if self.compiled {
+            format!(
+                "{name}: compiled successfully (expected {})",
+                list(&self.expected)
+            )
+        } else {
+            let mut found = list(&self.found);
+            for message in &self.uncoded {
+                found.push_str(&format!(
+                    "{}error: {message}",
+                    if found.is_empty() { "" } else { ", " }
+                ));
+            }
+            format!(
+                "{name}: expected {}, got {found}\n{}",
+                list(&self.expected),
+                indent(&self.stderr)
+            )
+        }
+    }
+}
+
+// dir の *.rs をすべて試し、1つでも期待どおりでなければまとめて panic
+#[track_caller]
+pub fn check_dir(dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    let outcomes = run_dir(dir);
+    assert!(
+        !outcomes.is_empty(),
+        "no compile-fail cases in {}",
+        dir.display()
+    );
+
+    let failures: Vec<String> = outcomes
+        .iter()
+        .filter(|o| !o.passed())
+        .map(Outcome::describe)
+        .collect();
+    if !failures.is_empty() {
+        let mut report = format!(
+            "{} of {} compile-fail cases did not fail as expected:\n",
+            failures.len(),
+            outcomes.len()
+        );
+        for failure in failures {
+            let _ = writeln!(report, "  {failure}");
+        }
+        panic!("{report}");
+    }
+}
+
+pub fn run_dir(dir: &Path) -> Vec<Outcome> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("cannot read {}: {e}", dir.display()))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| run_file(path)).collect()
+}
+
+pub fn run_file(path: &Path) -> Outcome {
+    let source =
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+    let out = TempDir::with_prefix("compile-fail").expect("failed to create a temporary directory");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let captured = Cmd::new(rustc)
+        .args([
+            "--edition",
+            EDITION,
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+        ])
+        .args(["--error-format", "short", "-A", "warnings", "--out-dir"])
+        .arg(out.path())
+        .arg(path)
+        .run();
+
+    let (found, uncoded) = errors(&captured.stderr);
+    Outcome {
+        path: path.to_path_buf(),
+        expected: expectations(&source),
+        found,
+        uncoded,
+        compiled: captured.success(),
+        stderr: captured.stderr,
+    }
+}
+
+// "// expect: E0382, E0505" の行から
+fn expectations(source: &str) -> BTreeSet<String> {
+    source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("// expect:"))
+        .flat_map(|codes| codes.split([',', ' ']))
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// --error-format short の出力: "path:line:col: error[E0382]: borrow of moved value: `s1`"
+fn errors(stderr: &str) -> (BTreeSet<String>, Vec<String>) {
+    let mut codes = BTreeSet::new();
+    let mut uncoded = Vec::new();
+    for line in stderr.lines() {
+        // パスに "error" が入っていても（error-practice/ など）誤らないよう、": error" を探す
+        let rest = match line.strip_prefix("error") {
+            Some(rest) if rest.starts_with(['[', ':']) => Some(rest),
+            _ => line.find(": error").map(|at| &line[at + ": error".len()..]),
+        };
+        let Some(rest) = rest else {
+            continue;
+        };
+        if let Some(code) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            codes.insert(code.0.to_string());
+        } else if let Some(message) = rest.strip_prefix(": ")
+            && !message.starts_with("aborting due to")
+        {
+            uncoded.push(message.to_string());
+        }
+    }
+    (codes, uncoded)
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("      {line}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_expectations_and_errors() {
+        assert_eq!(
+            expectations("// expect: E0382, E0505\n// expect: E0597\nfn f() {}"),
+            ["E0382", "E0505", "E0597"].map(String::from).into()
+        );
+        let stderr = "error-practice/a.rs:5:20: error[E0382]: borrow of moved value: `s1`\n\
+                      a.rs:9:1: error: expected item, found `}`\n\
+                      error: aborting due to 2 previous errors\n";
+        let (codes, uncoded) = errors(stderr);
+        assert_eq!(codes, ["E0382".to_string()].into());
+        assert_eq!(uncoded, ["expected item, found `}`"]);
+    }
+
+    #[test]
+    fn judges_real_compilations() {
+        let dir = TempDir::with_tree(
+            "
+            -- moved.rs --
+            // expect: E0382
+            pub fn f() { let s = String::new(); let t = s; drop(t); drop(s); }
+            -- compiles.rs --
+            // expect: E0382
+            pub fn f() { let s = String::new(); drop(s); }
+            -- wrong_code.rs --
+            // expect: E0382
+            pub fn f() -> u32 { \"not a number\" }
+            ",
+        );
+        let outcomes = run_dir(dir.path());
+        let passed: Vec<(String, bool)> = outcomes
+            .iter()
+            .map(|o| {
+                (
+                    o.path.file_name().unwrap().to_string_lossy().into_owned(),
+                    o.passed(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            passed,
+            [
+                ("compiles.rs".to_string(), false),
+                ("moved.rs".to_string(), true),
+                ("wrong_code.rs".to_string(), false),
+            ]
+        );
+        assert!(outcomes[0].describe().contains("compiled successfully"));
+        assert!(outcomes[2].describe().contains("expected E0382, got E0308"));
+    }
+}
@@ -0,0 +1,112 @@
+// ====================================================================
+// EnvGuard — 環境変数を書き換えて、drop で元に戻す
+// ====================================================================
+//
+// cargo test はテストを並列に走らせる。環境変数はプロセス全体で1つなので、
+//   テスト A: IGNORE_CASE=1 にして Config::build
+//   テスト B: IGNORE_CASE を消して Config::build
+// が同時に走ると、互いの設定を読んでしまう（たまにだけ落ちるテストになる）
+//
+// EnvGuard はプロセスで1つのロックを持っている間だけ環境変数を書き換える
+//   let _env = EnvGuard::lock().set("IGNORE_CASE", "1");
+//   let config = Config::build(args)?;     // ← ここでは必ず IGNORE_CASE=1
+//   // _env の drop で元の値に戻り、ロックが外れる
+//
+// 環境変数を「読むだけ」のテストも EnvGuard::lock() を取っておくこと
+//   （書き換えているテストと同時に走らないように）
+// 同じスレッドで2つ取るとデッドロックするので、1つのテストでは1つだけ
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct EnvGuard {
+    // 書き換える前の値（書き換えた順）
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    pub fn lock() -> EnvGuard {
+        // 前のテストがロックを持ったまま panic しても、drop で元に戻してから外れているので
+        // poison は無視してよい
+        let lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        EnvGuard {
+            saved: Vec::new(),
+            _lock: lock,
+        }
+    }
+
+    pub fn set(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> EnvGuard {
+        let key = key.as_ref();
+        self.save(key);
+        // SAFETY: ENV_LOCK を持っているので、このクレートを使う他のテストは環境を触らない
+        //         （std の env 関数どうしは std の中でも排他されている）
+        unsafe { env::set_var(key, value) };
+        self
+    }
+
+    pub fn remove(mut self, key: impl AsRef<OsStr>) -> EnvGuard {
+        let key = key.as_ref();
+        self.save(key);
+        // SAFETY: set と同じ
+        unsafe { env::remove_var(key) };
+        self
+    }
+
+    fn save(&mut self, key: &OsStr) {
+        self.saved.push((key.to_os_string(), env::var_os(key)));
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        // 同じ名前を2回書き換えても最初の値に戻るよう、逆順に戻す
+        for (key, value) in self.saved.drain(..).rev() {
+            // SAFETY: まだ ENV_LOCK を持っている（_lock はこの後で drop される）
+            unsafe {
+                match value {
+                    Some(value) => env::set_var(&key, value),
+                    None => env::remove_var(&key),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "TEST_SUPPORT_ENV_GUARD";
+
+    #[test]
+    fn restores_previous_values() {
+        {
+            let _env = EnvGuard::lock().remove(KEY);
+            assert_eq!(env::var_os(KEY), None);
+        }
+        {
+            let _env = EnvGuard::lock().set(KEY, "first").set(KEY, "second");
+            assert_eq!(env::var(KEY).as_deref(), Ok("second"));
+        }
+        let _env = EnvGuard::lock();
+        assert_eq!(env::var_os(KEY), None);
+    }
+
+    #[test]
+    fn restores_after_a_panic() {
+        let result = std::thread::spawn(|| {
+            let _env = EnvGuard::lock().set(KEY, "during panic");
+            panic!("test failed while holding the guard");
+        })
+        .join();
+        assert!(result.is_err());
+
+        // poison されていても取れて、値は戻っている
+        let _env = EnvGuard::lock();
+        assert_eq!(env::var_os(KEY), None);
+    }
+}
@@ -0,0 +1,135 @@
+// ====================================================================
+// test-support — 結合テスト（tests/）で共有する部品
+// ====================================================================
+//
+// 11.3 の tests/common/mod.rs は「テストで共有するヘルパーを置く場所」だった
+// ここではそれを、どのクレートからも使えるライブラリにまとめる
+//
+//   [dev-dependencies]
+//   test-support = { path = "../test-support" }
+//
+// 部品:
+//   TempDir   — 一時ディレクトリ。drop すると中身ごと消える
+//   FileTree  — インラインの仕様からファイルを並べる（txtar 形式）
+//                 let dir = TempDir::with_tree("
+//                     -- poem.txt --
+//                     I'm nobody! Who are you?
+//                 ");
+//   Cmd       — テスト対象のバイナリを実行し、stdout / stderr / 終了コードを取る
+//                 Cmd::new(env!("CARGO_BIN_EXE_minigrep")).args(["to", "poem.txt"]).run()
+//   EnvGuard  — 環境変数を書き換え、drop で元に戻す（テスト間で1つのロックを共有する）
+//   assert_snapshot! ほか — 出力を snapshots/*.snap と比べる（snapshot.rs を参照）
+//                 assert_debug_snapshot!(rect);
+//                 assert_cmd_snapshot!(Cmd::new(env!("CARGO_BIN_EXE_minigrep")).arg("to"));
+//   compile_fail — コンパイルエラーになるはずの例が、期待どおりのエラーになるか確かめる
+//                 compile_fail::check_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compile-fail"));
+//   prop      — プロパティベーステスト（生成器・縮小・seed での再現。prop.rs を参照）
+//                 prop::check("reverse twice", vecs(ints(0..=9), 0..=20), |v| ...);
+//
+// テストの準備に失敗したらテストも失敗させればよいので、
+// TempDir::with_tree や Cmd::run は Result ではなく panic で知らせる
+
+mod command;
+pub mod compile_fail;
+mod env;
+pub mod prop;
+mod snapshot;
+mod temp;
+mod tree;
+
+pub use command::{Captured, Cmd};
+pub use env::EnvGuard;
+pub use snapshot::UPDATE_VAR;
+pub use temp::TempDir;
+pub use tree::{Entry, FileTree, SpecError};
+
+// マクロから使うもの（直接は使わない）
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::snapshot::{Location, assert_snapshot};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __snapshot_location {
+    () => {
+        $crate::__private::Location {
+            manifest_dir: env!("CARGO_MANIFEST_DIR"),
+            file: file!(),
+            function: {
+                fn f() {}
+                fn name_of<T>(_: T) -> &'static str {
+                    ::std::any::type_name::<T>()
+                }
+                name_of(f)
+            },
+        }
+    };
+}
+
+// Display の結果をスナップショットと比べる
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:literal, $value:expr $(,)?) => {
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            Some($name),
+            stringify!($value),
+            &::std::string::ToString::to_string(&$value),
+        )
+    };
+    ($value:expr $(,)?) => {
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            None,
+            stringify!($value),
+            &::std::string::ToString::to_string(&$value),
+        )
+    };
+}
+
+// {:#?} の結果をスナップショットと比べる
+#[macro_export]
+macro_rules! assert_debug_snapshot {
+    ($name:literal, $value:expr $(,)?) => {
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            Some($name),
+            stringify!($value),
+            &format!("{:#?}", $value),
+        )
+    };
+    ($value:expr $(,)?) => {
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            None,
+            stringify!($value),
+            &format!("{:#?}", $value),
+        )
+    };
+}
+
+// Cmd を実行し、終了コード・stdout・stderr をスナップショットと比べる（Captured を返す）
+#[macro_export]
+macro_rules! assert_cmd_snapshot {
+    ($name:literal, $cmd:expr $(,)?) => {{
+        let captured = $cmd.run();
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            Some($name),
+            stringify!($cmd),
+            &captured.snapshot(),
+        );
+        captured
+    }};
+    ($cmd:expr $(,)?) => {{
+        let captured = $cmd.run();
+        $crate::__private::assert_snapshot(
+            &$crate::__snapshot_location!(),
+            None,
+            stringify!($cmd),
+            &captured.snapshot(),
+        );
+        captured
+    }};
+}
@@ -0,0 +1,425 @@
+// ====================================================================
+// prop — プロパティベーステスト
+// ====================================================================
+//
+// 例を1つずつ書く代わりに「どんな入力でも成り立つ性質」を書き、入力はランダムに作らせる
+//
+//   use test_support::prop::{self, strings, ascii, vecs};
+//
+//   prop::check(
+//       "every line returned by search contains the query",
+//       (strings(ascii(), 1..=3), vecs(strings(ascii(), 0..=20), 0..=10)),
+//       |(query, lines)| {
+//           let contents = lines.join("\n");
+//           search(&query, &contents).iter().all(|line| line.contains(&query))
+//       },
+//   );
+//
+// 性質は bool・() （中で assert! してよい）・Result<(), E: Display> のどれかを返す
+//
+// 失敗したら入力を縮める（shrinking）
+//   ランダムな入力は長くて読みにくいので、失敗したままの「より単純な入力」を探し続ける
+//     整数 → 0 に近づける    Vec・文字列 → 短くする、要素を縮める
+//   panic のメッセージには縮めたあとの入力と seed が出る
+//
+// 再現:
+//   PROP_SEED=0x1234 cargo test   … 同じ seed なら同じ入力の列になる
+//   PROP_CASES=10000 cargo test   … 試す回数（既定 256）
+//
+// 生成器（Strategy）:
+//   ints(0..=100)                          整数（境界値も混ぜる）
+//   bools() / select(&[..])                 真偽値 / 候補から1つ
+//   chars(&['a'..='z']) / ascii() / japanese()   文字（or で合わせられる）
+//   strings(ascii().or(japanese()), 0..=20) 文字列
+//   vecs(ints(0..=9), 0..=10)               Vec
+//   (a, b) / (a, b, c) / (a, b, c, d)       タプル
+//   s.convert(into, from)                   独自の構造体（from で戻せるので縮められる）
+
+mod collection;
+mod num;
+mod strategy;
+mod text;
+
+use std::cell::Cell;
+use std::env;
+use std::fmt::{self, Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use collection::{VecOf, vecs};
+pub use num::{Int, Ints, ints};
+pub use strategy::{Bools, Convert, Select, Strategy, bools, select};
+pub use text::{Chars, Strings, ascii, chars, japanese, strings};
+
+// ====================================================================
+// Rng — xorshift64（リポジトリのテストで使っているものと同じ）
+// ====================================================================
+
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // 0 だと 0 のまま動かないので、seed を混ぜてから使う
+        Rng(splitmix(seed) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // 上位 32 ビット（xorshift は下位ビットの質が少し落ちる）
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    // 0..n（n <= 2^64）
+    pub fn below(&mut self, n: u128) -> u128 {
+        assert!(n > 0, "below(0)");
+        u128::from(self.next_u64()) % n
+    }
+
+    // 1 / n の確率で true
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64().is_multiple_of(n)
+    }
+}
+
+fn splitmix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+// ====================================================================
+// 性質の結果
+// ====================================================================
+
+pub trait Verdict {
+    fn into_result(self) -> Result<(), String>;
+}
+
+impl Verdict for () {
+    fn into_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl Verdict for bool {
+    fn into_result(self) -> Result<(), String> {
+        if self {
+            Ok(())
+        } else {
+            Err("property returned false".to_string())
+        }
+    }
+}
+
+impl<E: Display> Verdict for Result<(), E> {
+    fn into_result(self) -> Result<(), String> {
+        self.map_err(|e| e.to_string())
+    }
+}
+
+// ====================================================================
+// Runner — 入力を作って試し、失敗したら縮める
+// ====================================================================
+
+pub const SEED_VAR: &str = "PROP_SEED";
+pub const CASES_VAR: &str = "PROP_CASES";
+
+// 生成する Vec や文字列の長さの目安は、試すごとに 0 から MAX_SIZE まで大きくする
+const MAX_SIZE: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct Runner {
+    cases: u32,
+    seed: Option<u64>,
+    max_shrinks: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure<T> {
+    pub seed: u64,
+    pub case: u32, // 何回目の入力で失敗したか（1 始まり）
+    pub shrinks: u32,
+    pub original: T,
+    pub minimal: T,
+    pub message: String,
+}
+
+impl<T: Debug> Display for Failure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "failed at case {} (seed {:#x}, shrunk {} times)",
+            self.case, self.seed, self.shrinks
+        )?;
+        writeln!(f, "minimal input: {:#?}", self.minimal)?;
+        writeln!(f, "error: {}", self.message)?;
+        write!(f, "reproduce with: {SEED_VAR}={:#x} cargo test", self.seed)
+    }
+}
+
+impl Default for Runner {
+    fn default() -> Runner {
+        Runner::new()
+    }
+}
+
+impl Runner {
+    // PROP_CASES / PROP_SEED があればそれを使う
+    pub fn new() -> Runner {
+        let cases = env::var(CASES_VAR)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(256);
+        let seed = env::var(SEED_VAR).ok().map(|v| {
+            parse_seed(&v).unwrap_or_else(|| panic!("{SEED_VAR} must be a number, got `{v}`"))
+        });
+        Runner {
+            cases,
+            seed,
+            max_shrinks: 1000,
+        }
+    }
+
+    pub fn cases(mut self, cases: u32) -> Runner {
+        self.cases = cases;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Runner {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn max_shrinks(mut self, max_shrinks: u32) -> Runner {
+        self.max_shrinks = max_shrinks;
+        self
+    }
+
+    // 失敗したら縮めた結果を返す（panic はしない）
+    pub fn find_failure<S, P, R>(&self, strategy: &S, property: P) -> Option<Failure<S::Value>>
+    where
+        S: Strategy,
+        P: Fn(S::Value) -> R,
+        R: Verdict,
+    {
+        let seed = self.seed.unwrap_or_else(random_seed);
+        let mut rng = Rng::new(seed);
+
+        for case in 0..self.cases {
+            let size = case as usize * MAX_SIZE / self.cases.max(1) as usize;
+            let value = strategy.generate(&mut rng, size);
+            if let Err(message) = evaluate(&property, value.clone()) {
+                let (minimal, message, shrinks) =
+                    self.shrink(strategy, &property, value.clone(), message);
+                return Some(Failure {
+                    seed,
+                    case: case + 1,
+                    shrinks,
+                    original: value,
+                    minimal,
+                    message,
+                });
+            }
+        }
+        None
+    }
+
+    #[track_caller]
+    pub fn run<S, P, R>(&self, name: &str, strategy: S, property: P)
+    where
+        S: Strategy,
+        P: Fn(S::Value) -> R,
+        R: Verdict,
+    {
+        if let Some(failure) = self.find_failure(&strategy, property) {
+            panic!("property `{name}` {failure}");
+        }
+    }
+
+    // 失敗したままの候補があればそちらに移る、を候補がなくなるまで続ける
+    fn shrink<S, P, R>(
+        &self,
+        strategy: &S,
+        property: &P,
+        mut value: S::Value,
+        mut message: String,
+    ) -> (S::Value, String, u32)
+    where
+        S: Strategy,
+        P: Fn(S::Value) -> R,
+        R: Verdict,
+    {
+        let mut shrinks = 0;
+        'search: while shrinks < self.max_shrinks {
+            for candidate in strategy.shrink(&value) {
+                if let Err(m) = evaluate(property, candidate.clone()) {
+                    value = candidate;
+                    message = m;
+                    shrinks += 1;
+                    continue 'search;
+                }
+            }
+            break;
+        }
+        (value, message, shrinks)
+    }
+}
+
+// 既定の設定で試し、失敗したら panic
+#[track_caller]
+pub fn check<S, P, R>(name: &str, strategy: S, property: P)
+where
+    S: Strategy,
+    P: Fn(S::Value) -> R,
+    R: Verdict,
+{
+    Runner::new().run(name, strategy, property);
+}
+
+fn parse_seed(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn random_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    // 同じ時刻に始まったテストどうしで重ならないよう、スタック上の位置も混ぜる
+    let local = 0u8;
+    splitmix(nanos ^ (&local as *const u8 as u64) ^ u64::from(std::process::id()))
+}
+
+// ====================================================================
+// panic を捕まえる（縮める途中の panic はメッセージを出さない）
+// ====================================================================
+
+thread_local! {
+    static SILENT: Cell<bool> = const { Cell::new(false) };
+}
+
+fn evaluate<T, P, R>(property: &P, value: T) -> Result<(), String>
+where
+    P: Fn(T) -> R,
+    R: Verdict,
+{
+    // panic フックはプロセスで1つ。このスレッドが評価中のときだけ黙るフックを1回だけ入れる
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENT.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+
+    SILENT.with(|s| s.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| property(value)));
+    SILENT.with(|s| s.set(false));
+
+    match result {
+        Ok(verdict) => verdict.into_result(),
+        Err(payload) => Err(match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "panicked".to_string(),
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner() -> Runner {
+        Runner::new().seed(42).cases(500)
+    }
+
+    #[test]
+    fn passing_properties_pass() {
+        runner().run(
+            "addition commutes",
+            (ints(-1000..=1000), ints(-1000..=1000)),
+            |(a, b)| a + b == b + a,
+        );
+    }
+
+    #[test]
+    fn shrinks_integers_to_the_boundary() {
+        let failure = runner()
+            .find_failure(&ints(0u32..=1_000_000), |x| x < 1234)
+            .unwrap();
+        assert_eq!(failure.minimal, 1234);
+        assert_eq!(failure.message, "property returned false");
+    }
+
+    #[test]
+    fn shrinks_vectors_and_reports_panics() {
+        // 合計が 10 以上で失敗 → 要素1つの [10] まで縮む
+        let failure = runner()
+            .find_failure(&vecs(ints(0..=9), 0..=50), |v: Vec<i32>| {
+                assert!(
+                    v.iter().sum::<i32>() < 10,
+                    "sum was {}",
+                    v.iter().sum::<i32>()
+                );
+            })
+            .unwrap();
+        assert_eq!(failure.minimal.iter().sum::<i32>(), 10);
+        assert!(failure.minimal.len() <= 2, "{:?}", failure.minimal);
+        assert_eq!(failure.message, "sum was 10");
+    }
+
+    #[test]
+    fn shrinks_strings_keeping_the_failing_part() {
+        // 全角文字を含むと失敗 → 全角1文字だけが残る
+        let failure = runner()
+            .find_failure(&strings(ascii().or(japanese()), 0..=30), |s: String| {
+                if s.is_ascii() {
+                    Ok(())
+                } else {
+                    Err(format!("non-ASCII: {s:?}"))
+                }
+            })
+            .unwrap();
+        assert_eq!(failure.minimal.chars().count(), 1);
+        assert!(!failure.minimal.is_ascii());
+    }
+
+    #[test]
+    fn same_seed_gives_same_inputs() {
+        let strategy = vecs(strings(japanese(), 0..=5), 0..=5);
+        let inputs = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..20)
+                .map(|size| strategy.generate(&mut rng, size))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(inputs(7), inputs(7));
+        assert_ne!(inputs(7), inputs(8));
+        assert_eq!(parse_seed("0x1f"), Some(31));
+        assert_eq!(parse_seed("31"), Some(31));
+    }
+
+    #[test]
+    fn failure_message_names_the_seed() {
+        let failure = runner().find_failure(&bools(), |b| !b).unwrap();
+        let text = failure.to_string();
+        assert!(text.contains("minimal input: true"), "{text}");
+        assert!(text.contains("PROP_SEED=0x2a"), "{text}");
+    }
+}
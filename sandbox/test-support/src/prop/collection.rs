@@ -0,0 +1,101 @@
+// ====================================================================
+// vecs — 要素の Strategy から Vec を作る
+// ====================================================================
+//
+// 長さは指定した範囲のうち size の分まで（最初の方の試行では短い Vec ばかりになる）
+//   上限を書かなければ 最小 + 64 まで
+//
+// 縮める候補（単純なものから）:
+//   1. 要素をまとめて取り除く（残せる最短まで一気に → 半分 → … → 1つずつ）
+//   2. 要素を1つずつ縮める
+
+use std::ops::{Bound, RangeBounds};
+
+use super::{Rng, Strategy};
+
+pub struct VecOf<S> {
+    element: S,
+    min: usize,
+    max: usize,
+}
+
+pub fn vecs<S: Strategy>(element: S, len: impl RangeBounds<usize>) -> VecOf<S> {
+    let (min, max) = len_bounds(len);
+    VecOf { element, min, max }
+}
+
+// 文字列（text.rs）と共有する
+pub(super) fn len_bounds(len: impl RangeBounds<usize>) -> (usize, usize) {
+    let min = match len.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n + 1,
+        Bound::Unbounded => 0,
+    };
+    let max = match len.end_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_sub(1).expect("empty length range"),
+        Bound::Unbounded => min + 64,
+    };
+    assert!(min <= max, "empty length range {min}..={max}");
+    (min, max)
+}
+
+impl<S: Strategy> Strategy for VecOf<S> {
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Vec<S::Value> {
+        let max = self.max.min(self.min + size);
+        let len = self.min + rng.below((max - self.min + 1) as u128) as usize;
+        (0..len).map(|_| self.element.generate(rng, size)).collect()
+    }
+
+    fn shrink(&self, value: &Vec<S::Value>) -> Vec<Vec<S::Value>> {
+        let mut candidates = Vec::new();
+
+        let mut chunk = value.len().saturating_sub(self.min);
+        while chunk > 0 {
+            for start in (0..=value.len() - chunk).step_by(chunk) {
+                let mut candidate = value[..start].to_vec();
+                candidate.extend_from_slice(&value[start + chunk..]);
+                candidates.push(candidate);
+            }
+            chunk /= 2;
+        }
+
+        for (i, element) in value.iter().enumerate() {
+            for smaller in self.element.shrink(element) {
+                let mut candidate = value.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop::ints;
+
+    #[test]
+    fn lengths_grow_with_size() {
+        let s = vecs(ints(0..=9), 2..=40);
+        let mut rng = Rng::new(5);
+        assert!((0..100).all(|_| s.generate(&mut rng, 0).len() == 2));
+        let lens: Vec<usize> = (0..500).map(|_| s.generate(&mut rng, 100).len()).collect();
+        assert!(lens.iter().all(|n| (2..=40).contains(n)));
+        assert!(lens.contains(&40));
+    }
+
+    #[test]
+    fn shrinks_by_removing_then_simplifying() {
+        let s = vecs(ints(0..=9), 1..);
+        let candidates = s.shrink(&vec![3, 0, 7]);
+        assert_eq!(candidates[0], [7], "最短（1要素）まで一気に");
+        assert!(candidates.contains(&vec![3, 7]));
+        assert!(candidates.contains(&vec![0, 0, 7]));
+        assert!(candidates.iter().all(|c| !c.is_empty()));
+        assert!(s.shrink(&vec![0]).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+// ====================================================================
+// ints — 範囲の中の整数
+// ====================================================================
+//
+// 一様に選ぶだけだと、最小値・最大値・0 のような「あふれやすい値」にはまず当たらない
+//   → 8回に1回は境界値（範囲の端、0、±1）から選ぶ
+//
+// 縮めるときは 0 に近づける（0 が範囲外なら、0 に一番近い端）
+//   候補: 0、真ん中、3/4 …、1つ手前 の順（二分探索のように、大きく縮められればそちらを使う）
+//
+// 計算は i128 で行う（i64 / u64 までの範囲がすべて収まる）
+
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+
+use super::{Rng, Strategy};
+
+pub trait Int: Copy + Debug + 'static {
+    const MIN: i128;
+    const MAX: i128;
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {$(
+        impl Int for $t {
+            const MIN: i128 = <$t>::MIN as i128;
+            const MAX: i128 = <$t>::MAX as i128;
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(value: i128) -> $t {
+                value as $t
+            }
+        }
+    )*};
+}
+
+impl_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+pub struct Ints<T> {
+    lo: i128,
+    hi: i128,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+// 空の範囲なら panic
+pub fn ints<T: Int>(range: impl RangeBounds<T>) -> Ints<T> {
+    let lo = match range.start_bound() {
+        Bound::Included(&v) => v.to_i128(),
+        Bound::Excluded(&v) => v.to_i128() + 1,
+        Bound::Unbounded => T::MIN,
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(&v) => v.to_i128(),
+        Bound::Excluded(&v) => v.to_i128() - 1,
+        Bound::Unbounded => T::MAX,
+    };
+    assert!(lo <= hi, "ints: empty range {lo}..={hi}");
+    Ints {
+        lo,
+        hi,
+        marker: std::marker::PhantomData,
+    }
+}
+
+impl<T: Int> Ints<T> {
+    // 縮める先（0 に一番近い値）
+    fn target(&self) -> i128 {
+        0i128.clamp(self.lo, self.hi)
+    }
+}
+
+impl<T: Int> Strategy for Ints<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng, _size: usize) -> T {
+        let value = if rng.one_in(8) {
+            let edges = [self.lo, self.hi, self.target(), -1, 1];
+            let edges: Vec<i128> = edges
+                .into_iter()
+                .filter(|v| (self.lo..=self.hi).contains(v))
+                .collect();
+            edges[rng.below(edges.len() as u128) as usize]
+        } else {
+            // 幅は最大 2^64 なので below にそのまま渡せる
+            let span = (self.hi - self.lo) as u128 + 1;
+            self.lo + rng.below(span) as i128
+        };
+        T::from_i128(value)
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let x = value.to_i128();
+        let target = self.target();
+        if x == target {
+            return Vec::new();
+        }
+        let mut candidates = vec![target];
+        let mut step = (x - target) / 2;
+        while step != 0 {
+            candidates.push(x - step);
+            step /= 2;
+        }
+        candidates.into_iter().map(T::from_i128).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_in_range_and_hits_the_edges() {
+        let mut rng = Rng::new(3);
+        let s = ints(-3i8..5);
+        let values: Vec<i8> = (0..2000).map(|_| s.generate(&mut rng, 0)).collect();
+        assert!(values.iter().all(|v| (-3..5).contains(v)));
+        assert!(values.contains(&-3) && values.contains(&4));
+
+        let full = ints::<u64>(..);
+        let values: Vec<u64> = (0..2000).map(|_| full.generate(&mut rng, 0)).collect();
+        assert!(values.contains(&u64::MAX) && values.contains(&0));
+    }
+
+    #[test]
+    fn shrinks_toward_zero_or_the_nearest_end() {
+        assert_eq!(ints(0..=100).shrink(&10), [0, 5, 8, 9]);
+        assert_eq!(ints(-100..=100).shrink(&-4), [0, -2, -3]);
+        assert_eq!(ints(20..=30).shrink(&23), [20, 22]);
+        assert!(ints(0..=100).shrink(&0).is_empty());
+        assert_eq!(ints::<i64>(..).shrink(&i64::MIN)[..2], [0, i64::MIN / 2]);
+    }
+}
@@ -0,0 +1,194 @@
+// ====================================================================
+// Strategy — 入力の作り方と縮め方
+// ====================================================================
+//
+// generate: size（0〜100、試すごとに大きくなる）を目安に値を1つ作る
+// shrink:   value より単純な候補を、単純なものから順に返す（なければ空）
+//           候補は必ず value より「小さく」すること（そうでないと縮める処理が終わらない）
+
+use std::fmt::Debug;
+
+use super::Rng;
+
+pub trait Strategy {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Self::Value;
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value>;
+
+    // 生成した値を別の型にする。from で元に戻せるので、縮めるのは元の Strategy に任せられる
+    //   (ints(1..=20), strings(ascii(), 1..=8))
+    //       .convert(|(size, style)| Shoe { size, style }, |s| (s.size, s.style.clone()))
+    fn convert<U, F, G>(self, into: F, from: G) -> Convert<Self, F, G>
+    where
+        Self: Sized,
+        U: Clone + Debug,
+        F: Fn(Self::Value) -> U,
+        G: Fn(&U) -> Self::Value,
+    {
+        Convert {
+            inner: self,
+            into,
+            from,
+        }
+    }
+}
+
+impl<S: Strategy + ?Sized> Strategy for &S {
+    type Value = S::Value;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> S::Value {
+        (**self).generate(rng, size)
+    }
+
+    fn shrink(&self, value: &S::Value) -> Vec<S::Value> {
+        (**self).shrink(value)
+    }
+}
+
+// ====================================================================
+// Convert
+// ====================================================================
+
+pub struct Convert<S, F, G> {
+    inner: S,
+    into: F,
+    from: G,
+}
+
+impl<S, U, F, G> Strategy for Convert<S, F, G>
+where
+    S: Strategy,
+    U: Clone + Debug,
+    F: Fn(S::Value) -> U,
+    G: Fn(&U) -> S::Value,
+{
+    type Value = U;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> U {
+        (self.into)(self.inner.generate(rng, size))
+    }
+
+    fn shrink(&self, value: &U) -> Vec<U> {
+        let original = (self.from)(value);
+        self.inner
+            .shrink(&original)
+            .into_iter()
+            .map(&self.into)
+            .collect()
+    }
+}
+
+// ====================================================================
+// bools / select
+// ====================================================================
+
+pub struct Bools;
+
+pub fn bools() -> Bools {
+    Bools
+}
+
+impl Strategy for Bools {
+    type Value = bool;
+
+    fn generate(&self, rng: &mut Rng, _size: usize) -> bool {
+        rng.next_u64() & 1 == 1
+    }
+
+    fn shrink(&self, value: &bool) -> Vec<bool> {
+        if *value { vec![false] } else { Vec::new() }
+    }
+}
+
+// 候補から1つ選ぶ。前にある候補ほど単純とみなす
+pub struct Select<T> {
+    values: Vec<T>,
+}
+
+pub fn select<T: Clone + Debug + PartialEq>(values: &[T]) -> Select<T> {
+    assert!(!values.is_empty(), "select needs at least one value");
+    Select {
+        values: values.to_vec(),
+    }
+}
+
+impl<T: Clone + Debug + PartialEq> Strategy for Select<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng, _size: usize) -> T {
+        self.values[rng.below(self.values.len() as u128) as usize].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let index = self.values.iter().position(|v| v == value).unwrap_or(0);
+        self.values[..index].to_vec()
+    }
+}
+
+// ====================================================================
+// タプル — 1つの要素だけを縮めた候補を並べる
+// ====================================================================
+
+macro_rules! impl_tuple {
+    ($($S:ident $i:tt),+) => {
+        impl<$($S: Strategy),+> Strategy for ($($S,)+) {
+            type Value = ($($S::Value,)+);
+
+            fn generate(&self, rng: &mut Rng, size: usize) -> Self::Value {
+                ($(self.$i.generate(rng, size),)+)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = Vec::new();
+                $(
+                    for smaller in self.$i.shrink(&value.$i) {
+                        let mut candidate = value.clone();
+                        candidate.$i = smaller;
+                        candidates.push(candidate);
+                    }
+                )+
+                candidates
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop::ints;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn convert_shrinks_through_the_original() {
+        let points = (ints(-5..=5), ints(-5..=5)).convert(|(x, y)| Point { x, y }, |p| (p.x, p.y));
+        assert_eq!(
+            points.shrink(&Point { x: 2, y: 0 }),
+            [Point { x: 0, y: 0 }, Point { x: 1, y: 0 }]
+        );
+        let mut rng = Rng::new(1);
+        let p = points.generate(&mut rng, 10);
+        assert!((-5..=5).contains(&p.x) && (-5..=5).contains(&p.y));
+    }
+
+    #[test]
+    fn select_and_bools_shrink_toward_the_first_choice() {
+        let s = select(&["small", "medium", "large"]);
+        assert_eq!(s.shrink(&"large"), ["small", "medium"]);
+        assert!(s.shrink(&"small").is_empty());
+        assert_eq!(bools().shrink(&true), [false]);
+        assert!(bools().shrink(&false).is_empty());
+    }
+}
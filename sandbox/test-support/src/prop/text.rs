@@ -0,0 +1,146 @@
+// ====================================================================
+// chars / strings — 文字と文字列（全角を含む）
+// ====================================================================
+//
+// Chars は文字の範囲の集まり。範囲を1つ選んでから、その中の1文字を選ぶ
+//   → 漢字のような大きな範囲があっても、ひらがなや記号も同じくらい出てくる
+//
+//   ascii()     英小文字・英大文字・数字・記号・空白
+//   japanese()  ひらがな・カタカナ・長音・漢字・句読点・かぎ括弧
+//   ascii().or(japanese())  両方
+//
+// 1文字を縮めるときは、最初の範囲の先頭（ascii なら 'a'）→ 文字が属する範囲の先頭 の順
+//   全角文字でだけ失敗するなら 'a' にはならず、'ぁ' などの全角のまま残る
+//
+// 文字列は Vec<char> として作り、縮める（vecs と同じ候補）
+
+use std::ops::{RangeBounds, RangeInclusive};
+
+use super::collection::{VecOf, len_bounds, vecs};
+use super::{Rng, Strategy};
+
+#[derive(Debug, Clone)]
+pub struct Chars {
+    ranges: Vec<RangeInclusive<char>>,
+}
+
+pub fn chars(ranges: &[RangeInclusive<char>]) -> Chars {
+    assert!(
+        !ranges.is_empty() && ranges.iter().all(|r| r.start() <= r.end()),
+        "chars needs non-empty ranges"
+    );
+    Chars {
+        ranges: ranges.to_vec(),
+    }
+}
+
+pub fn ascii() -> Chars {
+    chars(&[
+        'a'..='z',
+        'A'..='Z',
+        '0'..='9',
+        ' '..='/',
+        ':'..='@',
+        '['..='`',
+        '{'..='~',
+    ])
+}
+
+pub fn japanese() -> Chars {
+    chars(&[
+        'ぁ'..='ゖ', // ひらがな
+        'ァ'..='ヺ', // カタカナ
+        'ー'..='ー', // 長音
+        '一'..='龯', // 漢字
+        '、'..='。', // 句読点
+        '「'..='』', // かぎ括弧
+    ])
+}
+
+impl Chars {
+    pub fn or(mut self, other: Chars) -> Chars {
+        self.ranges.extend(other.ranges);
+        self
+    }
+}
+
+impl Strategy for Chars {
+    type Value = char;
+
+    fn generate(&self, rng: &mut Rng, _size: usize) -> char {
+        let range = &self.ranges[rng.below(self.ranges.len() as u128) as usize];
+        let (start, end) = (u32::from(*range.start()), u32::from(*range.end()));
+        // 範囲にサロゲート（U+D800〜U+DFFF）が入っていたら引き直す
+        loop {
+            let code = start + rng.below(u128::from(end - start + 1)) as u32;
+            if let Some(c) = char::from_u32(code) {
+                return c;
+            }
+        }
+    }
+
+    fn shrink(&self, value: &char) -> Vec<char> {
+        let mut candidates = vec![*self.ranges[0].start()];
+        if let Some(range) = self.ranges.iter().find(|r| r.contains(value)) {
+            candidates.push(*range.start());
+        }
+        candidates.dedup();
+        candidates.retain(|c| c != value);
+        candidates
+    }
+}
+
+pub struct Strings {
+    chars: VecOf<Chars>,
+}
+
+pub fn strings(chars: Chars, len: impl RangeBounds<usize>) -> Strings {
+    let (min, max) = len_bounds(len);
+    Strings {
+        chars: vecs(chars, min..=max),
+    }
+}
+
+impl Strategy for Strings {
+    type Value = String;
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        self.chars.generate(rng, size).into_iter().collect()
+    }
+
+    fn shrink(&self, value: &String) -> Vec<String> {
+        let chars: Vec<char> = value.chars().collect();
+        self.chars
+            .shrink(&chars)
+            .into_iter()
+            .map(|c| c.into_iter().collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_only_from_the_ranges() {
+        let mut rng = Rng::new(9);
+        let s = strings(japanese(), 0..=20);
+        for size in 0..100 {
+            let text = s.generate(&mut rng, size);
+            assert!(text.chars().count() <= 20);
+            assert!(text.chars().all(|c| !c.is_ascii()), "{text}");
+        }
+        let c = chars(&['x'..='z']);
+        assert!((0..100).all(|_| ('x'..='z').contains(&c.generate(&mut rng, 0))));
+    }
+
+    #[test]
+    fn shrinks_chars_toward_simple_ones() {
+        let s = ascii().or(japanese());
+        assert_eq!(s.shrink(&'Q'), ['a', 'A']);
+        assert_eq!(s.shrink(&'漢'), ['a', '一']);
+        assert!(s.shrink(&'a').is_empty());
+        assert_eq!(strings(ascii(), 0..).shrink(&"ab".to_string())[0], "");
+    }
+}
@@ -0,0 +1,280 @@
+// ====================================================================
+// スナップショットテスト — 出力をファイルに保存しておき、次回から比べる
+// ====================================================================
+//
+// {:#?} の出力やバイナリの stdout のように、assert_eq! に期待値を書き写すと長くなるものを
+// snapshots/ ディレクトリの .snap ファイルと比べる
+//
+//   assert_snapshot!(text);                  // Display の結果
+//   assert_snapshot!("poem", text);          // 名前を付ける（省略するとテスト関数の名前）
+//   assert_debug_snapshot!(rect);            // {:#?} の結果
+//   assert_cmd_snapshot!(cmd);               // 実行して終了コード・stdout・stderr
+//
+// 保存先はテストを書いたファイルの隣:
+//   tests/cli.rs の prints_usage  →  tests/snapshots/cli__prints_usage.snap
+//   1つのテストで名前なしを2回呼ぶと、2回目は prints_usage-2
+//
+// 一致しないとき（まだファイルがないときも）:
+//   差分を表示して panic し、今回の出力を .snap.new に保存する
+//   内容を確かめて、よければ UPDATE_SNAPSHOTS=1 cargo test で受け入れる
+//   （.snap を書き換え、残っている .snap.new を消す）
+//
+// 差分には色を付ける（NO_COLOR を設定すると付けない）
+
+mod diff;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+// これを設定して（0 以外の値で）cargo test すると、今回の出力でスナップショットを書き換える
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+// マクロを呼んだ場所（マクロが組み立てる）
+#[derive(Debug, Clone, Copy)]
+pub struct Location {
+    pub manifest_dir: &'static str,
+    pub file: &'static str,
+    // 呼び出し元の関数の中に定義した fn の型名（"cli::prints_usage::f" のような形）
+    pub function: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Check,
+    Update,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Matched,
+    Written,
+    // expected が None ならまだ保存されていなかった
+    Mismatch { expected: Option<String> },
+}
+
+#[track_caller]
+pub fn assert_snapshot(location: &Location, name: Option<&str>, expression: &str, actual: &str) {
+    let name = match name {
+        Some(name) => sanitize(name),
+        None => auto_name(location),
+    };
+    let path = snapshot_path(location, &name);
+    let mode = match env::var_os(UPDATE_VAR) {
+        Some(v) if !v.is_empty() && v != "0" => Mode::Update,
+        _ => Mode::Check,
+    };
+
+    let outcome = check(&path, location.file, expression, actual, mode)
+        .unwrap_or_else(|e| panic!("failed to access snapshot {}: {e}", path.display()));
+    let Outcome::Mismatch { expected } = outcome else {
+        return;
+    };
+
+    let color = env::var_os("NO_COLOR").is_none();
+    let lines = diff::diff_lines(expected.as_deref().unwrap_or(""), actual);
+    let headline = match expected {
+        Some(_) => format!("snapshot `{name}` does not match {}", path.display()),
+        None => format!("snapshot `{name}` does not exist yet ({})", path.display()),
+    };
+    panic!(
+        "{headline}\n\n{}\nthe new output was saved to {}\nrun with {UPDATE_VAR}=1 to accept it",
+        diff::render(&lines, color),
+        new_path(&path).display()
+    );
+}
+
+fn check(
+    path: &Path,
+    source: &str,
+    expression: &str,
+    actual: &str,
+    mode: Mode,
+) -> io::Result<Outcome> {
+    let expected = match fs::read_to_string(path) {
+        Ok(text) => Some(body(&text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let new = new_path(path);
+    if expected.as_deref() == Some(actual) {
+        remove_if_exists(&new)?;
+        return Ok(Outcome::Matched);
+    }
+
+    let contents = format!(
+        "---\nsource: {source}\nexpression: {}\n---\n{actual}\n",
+        expression.replace('\n', " ")
+    );
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    match mode {
+        Mode::Update => {
+            fs::write(path, contents)?;
+            remove_if_exists(&new)?;
+            Ok(Outcome::Written)
+        }
+        Mode::Check => {
+            fs::write(new, contents)?;
+            Ok(Outcome::Mismatch { expected })
+        }
+    }
+}
+
+// "---" で囲んだヘッダを除いた中身（保存するときに足した最後の改行も除く）
+fn body(text: &str) -> String {
+    let text = text.replace("\r\n", "\n");
+    let rest = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map_or(text.as_str(), |(_, body)| body);
+    rest.strip_suffix('\n').unwrap_or(rest).to_string()
+}
+
+fn new_path(path: &Path) -> PathBuf {
+    path.with_extension("snap.new")
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+// ====================================================================
+// 名前と保存先
+// ====================================================================
+
+// テスト関数の名前（"{{closure}}" は飛ばす）。同じテストの2回目からは -2, -3, …
+fn auto_name(location: &Location) -> String {
+    static SEEN: Mutex<BTreeMap<String, usize>> = Mutex::new(BTreeMap::new());
+
+    let function = location
+        .function
+        .strip_suffix("::f")
+        .unwrap_or(location.function);
+    let base = function
+        .rsplit("::")
+        .find(|segment| *segment != "{{closure}}")
+        .unwrap_or("snapshot");
+
+    let key = format!("{}::{function}", location.file);
+    let mut seen = SEEN.lock().unwrap_or_else(PoisonError::into_inner);
+    let count = seen.entry(key).or_insert(0);
+    *count += 1;
+    match *count {
+        1 => sanitize(base),
+        n => format!("{}-{n}", sanitize(base)),
+    }
+}
+
+// ファイル名に使えない文字は _ にする
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// <ソースのディレクトリ>/snapshots/<ソースのファイル名>__<名前>.snap
+fn snapshot_path(location: &Location, name: &str) -> PathBuf {
+    let source = source_path(location);
+    let stem = source
+        .file_stem()
+        .map_or_else(|| "snapshot".into(), |s| s.to_string_lossy());
+    let dir = source.parent().unwrap_or(Path::new("."));
+    dir.join("snapshots").join(format!("{stem}__{name}.snap"))
+}
+
+// file!() はパッケージのディレクトリからの相対パス。ワークスペースの中ではワークスペースの
+// ルートからの相対パスになるので、見つかるまで上のディレクトリをたどる
+fn source_path(location: &Location) -> PathBuf {
+    let manifest_dir = Path::new(location.manifest_dir);
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(location.file))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| manifest_dir.join(location.file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn check_writes_new_files_until_accepted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.join("snapshots/t__a.snap");
+        let new = new_path(&path);
+
+        // まだない → .snap.new に保存して不一致
+        let outcome = check(&path, "t.rs", "x", "one\ntwo", Mode::Check).unwrap();
+        assert_eq!(outcome, Outcome::Mismatch { expected: None });
+        assert!(!path.exists());
+        assert!(new.exists());
+
+        // 受け入れる → .snap に保存して .snap.new は消える
+        let outcome = check(&path, "t.rs", "x", "one\ntwo", Mode::Update).unwrap();
+        assert_eq!(outcome, Outcome::Written);
+        assert!(!new.exists());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\nsource: t.rs\nexpression: x\n---\none\ntwo\n"
+        );
+
+        // 同じなら一致、違えば保存済みの中身と一緒に不一致
+        let outcome = check(&path, "t.rs", "x", "one\ntwo", Mode::Check).unwrap();
+        assert_eq!(outcome, Outcome::Matched);
+        let outcome = check(&path, "t.rs", "x", "one\nthree", Mode::Check).unwrap();
+        assert_eq!(
+            outcome,
+            Outcome::Mismatch {
+                expected: Some("one\ntwo".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn body_round_trips_trailing_newlines_and_crlf() {
+        for actual in ["", "x", "x\n", "a\n---\nb\n\n"] {
+            let saved = format!("---\nsource: s\nexpression: e\n---\n{actual}\n");
+            assert_eq!(body(&saved), actual);
+            assert_eq!(body(&saved.replace('\n', "\r\n")), actual);
+        }
+    }
+
+    #[test]
+    fn names_come_from_the_test_function() {
+        let location = Location {
+            manifest_dir: env!("CARGO_MANIFEST_DIR"),
+            file: file!(),
+            function: "crate::tests::prints_usage::{{closure}}::f",
+        };
+        assert_eq!(auto_name(&location), "prints_usage");
+        assert_eq!(auto_name(&location), "prints_usage-2");
+        assert_eq!(
+            snapshot_path(&location, "prints_usage"),
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("src/snapshots/snapshot__prints_usage.snap")
+        );
+        assert_eq!(sanitize("a b/c"), "a_b_c");
+    }
+
+    #[test]
+    fn macros_compare_against_saved_files() {
+        let rows = vec![("ferris", 7), ("corro", 3)];
+        crate::assert_debug_snapshot!(rows);
+        crate::assert_snapshot!("table", format!("{:<8}|{:>3}", "ferris", 7));
+    }
+}
@@ -0,0 +1,117 @@
+// ====================================================================
+// 行単位の差分（スナップショットが一致しなかったときの表示用）
+// ====================================================================
+//
+// 最長共通部分列（LCS）を表で求め、共通でない行を - / + で示す
+//   - 保存されているスナップショットにだけある行（赤）
+//   + 今回の出力にだけある行（緑）
+//
+// 表は 行数 × 行数 の大きさになるので、大きすぎるときは全体を - と + で並べるだけにする
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+const MAX_TABLE: usize = 4_000_000;
+
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let (n, m) = (old.len(), new.len());
+
+    if n * m > MAX_TABLE {
+        let removed = old.iter().map(|l| Line::Removed(l));
+        return removed.chain(new.iter().map(|l| Line::Added(l))).collect();
+    }
+
+    // lcs[i][j] = old[i..] と new[j..] の最長共通部分列の長さ
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| Line::Removed(l)));
+    lines.extend(new[j..].iter().map(|l| Line::Added(l)));
+    lines
+}
+
+// 表示用の文字列（color が true なら ANSI エスケープで色を付ける）
+pub fn render(lines: &[Line<'_>], color: bool) -> String {
+    let (red, green, dim, reset) = if color {
+        ("\x1b[31m", "\x1b[32m", "\x1b[2m", "\x1b[0m")
+    } else {
+        ("", "", "", "")
+    };
+    let mut out = String::new();
+    for line in lines {
+        let (style, sign, text) = match line {
+            Line::Same(text) => (dim, ' ', text),
+            Line::Removed(text) => (red, '-', text),
+            Line::Added(text) => (green, '+', text),
+        };
+        out.push_str(&format!("{style}{sign} {text}{reset}\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_common_lines_and_marks_changes() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nc\nx\nd\ne");
+        assert_eq!(
+            lines,
+            [
+                Line::Same("a"),
+                Line::Removed("b"),
+                Line::Same("c"),
+                Line::Added("x"),
+                Line::Same("d"),
+                Line::Added("e"),
+            ]
+        );
+        assert_eq!(
+            render(&lines[..2], false),
+            "  a\n- b\n",
+            "色なしなら記号だけ"
+        );
+        assert_eq!(render(&lines[1..2], true), "\x1b[31m- b\x1b[0m\n");
+    }
+
+    #[test]
+    fn identical_and_empty_inputs() {
+        assert!(
+            diff_lines("x\ny", "x\ny")
+                .iter()
+                .all(|l| matches!(l, Line::Same(_)))
+        );
+        assert_eq!(diff_lines("", "new"), [Line::Added("new")]);
+        assert_eq!(diff_lines("old", ""), [Line::Removed("old")]);
+    }
+}
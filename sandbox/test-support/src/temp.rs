@@ -0,0 +1,110 @@
+// ====================================================================
+// TempDir — drop で消える一時ディレクトリ
+// ====================================================================
+//
+// 名前は「接頭辞-プロセスID-通し番号」→ 並列に走るテストどうしでもぶつからない
+// 作るときは create_dir（すでにあれば失敗）なので、前回の残りがあっても混ざらない
+//
+// パスは canonicalize 済み（macOS の /tmp → /private/tmp のようなリンクを解決しておく）
+//   → テスト対象が出力したパスと比べやすい
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::tree::FileTree;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempDir {
+    pub fn new() -> io::Result<TempDir> {
+        TempDir::with_prefix("test-support")
+    }
+
+    pub fn with_prefix(prefix: &str) -> io::Result<TempDir> {
+        let base = std::env::temp_dir();
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = base.join(format!("{prefix}-{}-{n}", process::id()));
+            match fs::create_dir(&path) {
+                Ok(()) => {
+                    return Ok(TempDir {
+                        path: path.canonicalize()?,
+                        keep: false,
+                    });
+                }
+                // 同じプロセスIDの前回の残り → 次の番号を試す
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // spec のファイルを並べた一時ディレクトリ（失敗したら panic）
+    pub fn with_tree(spec: &str) -> TempDir {
+        let dir = TempDir::new().expect("failed to create a temporary directory");
+        let tree = FileTree::parse(spec).unwrap_or_else(|e| panic!("invalid file tree: {e}"));
+        tree.write(dir.path())
+            .unwrap_or_else(|e| panic!("failed to write file tree: {e}"));
+        dir
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+
+    // 中身を確かめたいときに: drop しても消さない
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        self.path.clone()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if !self.keep {
+            // 消せなくてもテストの結果には関係ないので無視する
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_on_drop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_path_buf();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::create_dir(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/b.txt"), "b").unwrap();
+        assert!(path.is_dir());
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unique_and_keepable() {
+        let a = TempDir::with_prefix("unique").unwrap();
+        let b = TempDir::with_prefix("unique").unwrap();
+        assert_ne!(a.path(), b.path());
+
+        let kept = a.keep();
+        assert!(kept.is_dir());
+        fs::remove_dir_all(&kept).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+// ====================================================================
+// FileTree — インラインの仕様からファイルを並べる
+// ====================================================================
+//
+// 仕様は txtar 形式（Go のテストでよく使われる）:
+//
+//   最初の見出しより前は説明（無視される）
+//   -- poem.txt --
+//   I'm nobody! Who are you?
+//   -- src/lib.rs --
+//   pub fn f() {}
+//   -- empty/ --
+//
+//   「-- パス --」の次の行から、次の見出しまでがファイルの中身（改行も含めてそのまま）
+//   パスが / で終わるものは空のディレクトリ
+//
+// テストの中の文字列リテラルはインデントされがちなので、
+// 全部の行に共通する先頭の空白は取り除いてから読む
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    File { path: PathBuf, contents: String },
+    Dir { path: PathBuf },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileTree {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub line: usize, // 1 始まり（インデントを取り除いたあとの行番号と同じ）
+    pub message: String,
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for SpecError {}
+
+impl FileTree {
+    pub fn parse(spec: &str) -> Result<FileTree, SpecError> {
+        let text = dedent(spec);
+        let mut entries = Vec::new();
+        let mut current: Option<(PathBuf, String)> = None;
+
+        for (i, line) in text.split_inclusive('\n').enumerate() {
+            let Some(name) = header(line) else {
+                if let Some((_, contents)) = current.as_mut() {
+                    contents.push_str(line);
+                }
+                continue;
+            };
+
+            if let Some((path, contents)) = current.take() {
+                entries.push(Entry::File { path, contents });
+            }
+            let path = check_path(name).map_err(|message| SpecError {
+                line: i + 1,
+                message,
+            })?;
+            if name.ends_with('/') {
+                entries.push(Entry::Dir { path });
+            } else {
+                current = Some((path, String::new()));
+            }
+        }
+        if let Some((path, contents)) = current {
+            entries.push(Entry::File { path, contents });
+        }
+        Ok(FileTree { entries })
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    // root の下に作る（親ディレクトリも作る）
+    pub fn write(&self, root: &Path) -> io::Result<()> {
+        for entry in &self.entries {
+            match entry {
+                Entry::Dir { path } => fs::create_dir_all(root.join(path))?,
+                Entry::File { path, contents } => {
+                    let path = root.join(path);
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(path, contents)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// "-- name --" の name（前後の空白は除く）
+fn header(line: &str) -> Option<&str> {
+    let name = line
+        .trim_end_matches(['\n', '\r'])
+        .strip_prefix("-- ")?
+        .strip_suffix(" --")?
+        .trim();
+    (!name.is_empty()).then_some(name)
+}
+
+// 一時ディレクトリの外に書けないよう、相対パスで .. を含まないものだけ
+fn check_path(name: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(name);
+    let safe = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if safe {
+        Ok(path)
+    } else {
+        Err(format!(
+            "path must be relative and stay inside the tree: `{name}`"
+        ))
+    }
+}
+
+// 空でない行に共通する先頭の空白を取り除く（最初が改行だけなら、その行も捨てる）
+fn dedent(spec: &str) -> String {
+    let spec = spec.strip_prefix('\n').unwrap_or(spec);
+    let indent = spec
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    spec.split_inclusive('\n')
+        .map(|l| l.get(indent..).unwrap_or(l.trim_start_matches([' ', '\t'])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn parses_files_and_directories() {
+        let tree = FileTree::parse(
+            "
+            説明の行は無視される
+            -- poem.txt --
+            I'm nobody!
+              Who are you?
+            -- out/ --
+            -- src/lib.rs --
+            pub fn f() {}",
+        )
+        .unwrap();
+        assert_eq!(
+            tree.entries(),
+            [
+                Entry::File {
+                    path: "poem.txt".into(),
+                    contents: "I'm nobody!\n  Who are you?\n".into(),
+                },
+                Entry::Dir { path: "out".into() },
+                Entry::File {
+                    path: "src/lib.rs".into(),
+                    contents: "pub fn f() {}".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_tree() {
+        for bad in [
+            "-- ../evil --\n",
+            "-- /etc/passwd --\n",
+            "-- a/../../b --\n",
+        ] {
+            let err = FileTree::parse(bad).unwrap_err();
+            assert_eq!(err.line, 1, "{bad}");
+        }
+    }
+
+    #[test]
+    fn writes_into_a_directory() {
+        let dir = TempDir::with_tree(
+            "
+            -- a/b/c.txt --
+            deep
+            -- empty/ --
+            ",
+        );
+        assert_eq!(fs::read_to_string(dir.join("a/b/c.txt")).unwrap(), "deep\n");
+        assert!(dir.join("empty").is_dir());
+    }
+}